@@ -0,0 +1,411 @@
+use crate::{
+    bus::EventBus,
+    calendar, config,
+    core::{
+        commands::default_year,
+        events::Event,
+        leaderboard::{Leaderboard, ProblemPart},
+        source::PUZZLE_EVENT,
+        standings::{standings_board_iter, Scoring},
+    },
+    error::BotError,
+    graphql::{self, ApiSchema},
+    scheduler::{daily_summary, scrape_private_leaderboard},
+    storage::{MemoryCache, AUDIT},
+    utils::current_year_day,
+};
+use async_graphql::SimpleObject;
+use hyper::{
+    body, header,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, StatusCode,
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use tracing::{error, info};
+
+// Number of audit log entries returned when no limit is given
+const AUDIT_DEFAULT_LIMIT: usize = 100;
+
+#[derive(Serialize, SimpleObject)]
+pub struct MemberStanding {
+    position: usize,
+    id: u64,
+    name: String,
+    stars: usize,
+    score: usize,
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct Standings {
+    year: i32,
+    pub(crate) standings: Vec<MemberStanding>,
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct MemberYear {
+    year: i32,
+    position: usize,
+    stars: usize,
+    score: usize,
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct Member {
+    id: u64,
+    name: String,
+    // Former display names, oldest first
+    previous_names: Vec<String>,
+    years: Vec<MemberYear>,
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct Completion {
+    id: u64,
+    name: String,
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct Day {
+    year: i32,
+    day: u8,
+    completions: Vec<Completion>,
+}
+
+/// Serves a read-only JSON API over the private leaderboard kept by the bot:
+/// - `/api/standings?year=2023`: local standings of the year.
+/// - `/api/member/{id}`: standings of an AoC member for each year.
+/// - `/api/day/{n}?year=2023`: completion times of the day, fastest first.
+/// - `/api/graphql`: GraphQL queries (POST) over the same data and the solutions posted.
+/// - `/api/calendar.ics?year=2023`: iCalendar feed of the puzzle unlocks and the posts of the
+///   bot scheduled in `calendar_posts` (name, cron expression).
+///
+/// Admin endpoints (POST, see `admin_route`) require the admin token as bearer token.
+///
+/// The year defaults to the one used by commands.
+pub async fn serve(
+    port: u16,
+    cache: MemoryCache,
+    sender: Arc<EventBus>,
+    calendar_posts: Vec<(String, String)>,
+) {
+    let schema = graphql::schema(cache.clone());
+    let calendar_posts = Arc::new(calendar_posts);
+    let make_svc = make_service_fn(move |_| {
+        let cache = cache.clone();
+        let schema = schema.clone();
+        let sender = sender.clone();
+        let calendar_posts = calendar_posts.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let cache = cache.clone();
+                let schema = schema.clone();
+                let sender = sender.clone();
+                let calendar_posts = calendar_posts.clone();
+                async move {
+                    match req.uri().path() {
+                        "/api/graphql" => graphql_route(req, schema).await,
+                        "/api/calendar.ics" => Ok(calendar_response(&req, &calendar_posts)),
+                        path if path.starts_with("/api/admin/") => {
+                            admin_route(req, cache, sender).await
+                        }
+                        _ => api_route(req, cache).await,
+                    }
+                }
+            }))
+        }
+    });
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("Serving API on {addr}.");
+    if let Err(e) = hyper::Server::bind(&addr).serve(make_svc).await {
+        error!("API stopped. {e}");
+    }
+}
+
+async fn api_route(req: Request<Body>, cache: MemoryCache) -> Result<Response<Body>, Infallible> {
+    let year = match query_year(&req) {
+        Ok(year) => year,
+        Err(_) => return Ok(status_response(StatusCode::BAD_REQUEST)),
+    };
+    let path = req.uri().path().trim_end_matches('/').to_string();
+
+    let body = cache
+        .read(move |data| api_body(&data.leaderboard, &path, year))
+        .await;
+    match body {
+        None => Ok(status_response(StatusCode::NOT_FOUND)),
+        Some(Ok(body)) => Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()),
+        Some(Err(e)) => {
+            error!("Could not serialize API response. {e}");
+            Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+    }
+}
+
+/// JSON body of a read-only route, None if the route or the resource does not exist.
+fn api_body(
+    leaderboard: &Leaderboard,
+    path: &str,
+    year: i32,
+) -> Option<serde_json::Result<String>> {
+    if path == "/api/standings" {
+        Some(serde_json::to_string(&standings(leaderboard, year)))
+    } else if let Some(id) = path.strip_prefix("/api/member/") {
+        let member = member(leaderboard, id.parse::<u64>().ok()?)?;
+        Some(serde_json::to_string(&member))
+    } else if let Some(day) = path.strip_prefix("/api/day/") {
+        match day.parse::<u8>() {
+            Ok(day) if (1..=PUZZLE_EVENT.days()).contains(&day) => Some(serde_json::to_string(
+                &day_completions(leaderboard, year, day),
+            )),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+async fn graphql_route(
+    req: Request<Body>,
+    schema: ApiSchema,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::POST {
+        return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+    let request = match body::to_bytes(req.into_body()).await {
+        Ok(bytes) => serde_json::from_slice::<async_graphql::Request>(&bytes),
+        Err(e) => {
+            error!("Could not read GraphQL request. {e}");
+            return Ok(status_response(StatusCode::BAD_REQUEST));
+        }
+    };
+    let request = match request {
+        Ok(request) => request,
+        Err(_) => return Ok(status_response(StatusCode::BAD_REQUEST)),
+    };
+    // Errors of the query are reported in the response body, as per GraphQL conventions.
+    let response = schema.execute(request).await;
+    match serde_json::to_string(&response) {
+        Ok(body) => Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()),
+        Err(e) => {
+            error!("Could not serialize GraphQL response. {e}");
+            Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+    }
+}
+
+/// Operator actions, for when Slack cannot be used:
+/// - `/api/admin/refresh`: scrapes the private leaderboard now, announcing the changes.
+/// - `/api/admin/replay`: posts again the messages which could not be sent to Slack.
+/// - `/api/admin/summary/{n}?year=2023`: posts again the summary of the day.
+///
+/// The audit log of the commands is read with `GET /api/admin/audit?limit=50&user=U0123`.
+async fn admin_route(
+    req: Request<Body>,
+    cache: MemoryCache,
+    sender: Arc<EventBus>,
+) -> Result<Response<Body>, Infallible> {
+    let settings = &config::SETTINGS;
+    let token = match &settings.api_admin_token {
+        Some(token) => token,
+        None => return Ok(status_response(StatusCode::NOT_FOUND)),
+    };
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|value| value == token);
+    if !authorized {
+        return Ok(status_response(StatusCode::UNAUTHORIZED));
+    }
+    let path = req.uri().path().trim_end_matches('/');
+    match (path, req.method()) {
+        ("/api/admin/audit", &Method::GET) => return Ok(audit_response(&req)),
+        ("/api/admin/audit", _) => return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED)),
+        (_, &Method::POST) => (),
+        _ => return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED)),
+    }
+    let year = match query_year(&req) {
+        Ok(year) => year,
+        Err(_) => return Ok(status_response(StatusCode::BAD_REQUEST)),
+    };
+
+    let event = if path == "/api/admin/refresh" {
+        info!("Private leaderboard refresh requested from the admin API.");
+        let (year, day) = current_year_day();
+        scrape_private_leaderboard(&cache, &sender, year, day).await;
+        return Ok(status_response(StatusCode::OK));
+    } else if path == "/api/admin/replay" {
+        Event::DeadLettersReplayRequested
+    } else if let Some(day) = path.strip_prefix("/api/admin/summary/") {
+        match day.parse::<u8>() {
+            Ok(day) if (1..=PUZZLE_EVENT.days()).contains(&day) => {
+                daily_summary(&cache, year, day).await
+            }
+            _ => return Ok(status_response(StatusCode::NOT_FOUND)),
+        }
+    } else {
+        return Ok(status_response(StatusCode::NOT_FOUND));
+    };
+
+    info!("{} requested from the admin API.", <&str>::from(&event));
+    match sender.send(event).await {
+        Ok(()) => Ok(status_response(StatusCode::ACCEPTED)),
+        Err(e) => {
+            let error =
+                BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+            error!("{error}");
+            Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+    }
+}
+
+/// iCalendar feed of the season, which calendars subscribe to.
+fn calendar_response(req: &Request<Body>, posts: &[(String, String)]) -> Response<Body> {
+    let year = match query_year(req) {
+        Ok(year) => year,
+        Err(_) => return status_response(StatusCode::BAD_REQUEST),
+    };
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "text/calendar; charset=utf-8")
+        .body(Body::from(calendar::ics(year, posts)))
+        .unwrap()
+}
+
+/// Latest commands received, newest first, optionally for a single Slack user.
+fn audit_response(req: &Request<Body>) -> Response<Body> {
+    let limit = match query_param(req, "limit").map(|limit| limit.parse::<usize>()) {
+        Some(Ok(limit)) => limit,
+        Some(Err(_)) => return status_response(StatusCode::BAD_REQUEST),
+        None => AUDIT_DEFAULT_LIMIT,
+    };
+    let user = query_param(req, "user");
+    let entries = AUDIT
+        .latest(usize::MAX)
+        .into_iter()
+        .filter(|entry| user.is_none() || entry.user_id.as_deref() == user)
+        .take(limit)
+        .collect::<Vec<_>>();
+    match serde_json::to_string(&entries) {
+        Ok(body) => Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap(),
+        Err(e) => {
+            error!("Could not serialize API response. {e}");
+            status_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn status_response(code: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(code)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn query_param<'a>(req: &'a Request<Body>, name: &str) -> Option<&'a str> {
+    req.uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+}
+
+/// Year given as `year` query parameter, if any, default year otherwise.
+fn query_year(req: &Request<Body>) -> Result<i32, std::num::ParseIntError> {
+    match query_param(req, "year") {
+        Some(year) => year.parse::<i32>(),
+        None => Ok(default_year()),
+    }
+}
+
+pub(crate) fn standings(leaderboard: &Leaderboard, year: i32) -> Standings {
+    let standings = standings_board_iter(&Scoring::LOCAL, leaderboard, year)
+        .enumerate()
+        .map(|(idx, (id, scores, score))| MemberStanding {
+            position: idx + 1,
+            id: id.numeric,
+            name: id.name.to_string(),
+            stars: scores.iter().map(|(n, _)| *n as usize).sum(),
+            score,
+        })
+        .collect();
+    Standings { year, standings }
+}
+
+pub(crate) fn member(leaderboard: &Leaderboard, id: u64) -> Option<Member> {
+    let name = leaderboard
+        .iter()
+        .find(|e| e.id.numeric == id)
+        .map(|e| e.id.name.to_string())?;
+    let years = leaderboard
+        .iter()
+        .map(|e| e.year)
+        .unique()
+        .sorted()
+        .filter_map(|year| {
+            standings_board_iter(&Scoring::LOCAL, leaderboard, year)
+                .enumerate()
+                .find(|(_idx, (member, _scores, _score))| member.numeric == id)
+                .map(|(idx, (_member, scores, score))| MemberYear {
+                    year,
+                    position: idx + 1,
+                    stars: scores.iter().map(|(n, _)| *n as usize).sum(),
+                    score,
+                })
+        })
+        .collect();
+    let previous_names = leaderboard
+        .previous_names(id)
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    Some(Member {
+        id,
+        name,
+        previous_names,
+        years,
+    })
+}
+
+pub(crate) fn day_completions(leaderboard: &Leaderboard, year: i32, day: u8) -> Day {
+    let completions = leaderboard
+        .entries_per_member_for_year_day(year, day)
+        .into_iter()
+        .map(|(id, entries)| {
+            let part =
+                |part: ProblemPart| entries.iter().find(|e| e.part == part).map(|e| e.timestamp);
+            (id, part(ProblemPart::FIRST), part(ProblemPart::SECOND))
+        })
+        // Members with both parts first, then by completion time
+        .sorted_by_key(|(id, part1, part2)| (part2.is_none(), *part2, *part1, *id))
+        .map(|(id, part1, part2)| Completion {
+            id: id.numeric,
+            name: id.name.to_string(),
+            part1: part1.map(|t| t.to_rfc3339()),
+            part2: part2.map(|t| t.to_rfc3339()),
+        })
+        .collect();
+    Day {
+        year,
+        day,
+        completions,
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{config, core::events::Event};
+use once_cell::sync::{Lazy, OnceCell};
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::sync::mpsc::{
+    self, error::SendError, error::TrySendError, Receiver, Sender, WeakSender,
+};
+use tracing::warn;
+
+const OVERFLOW_POLICIES: [&'static str; 2] = ["block", "drop"];
+
+pub static BUS_METRICS: Lazy<BusMetrics> = Lazy::new(BusMetrics::default);
+
+/// What producers do when the event bus is full.
+#[derive(Debug, Clone, Copy)]
+pub enum OverflowPolicy {
+    // Wait for the messaging engine to catch up
+    BLOCK,
+    // Drop non-critical announcements, other events still wait
+    DROP,
+}
+
+impl OverflowPolicy {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            policy if policy == OVERFLOW_POLICIES[0] => Some(OverflowPolicy::BLOCK),
+            policy if policy == OVERFLOW_POLICIES[1] => Some(OverflowPolicy::DROP),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverflowPolicy::BLOCK => write!(f, "{}", OVERFLOW_POLICIES[0]),
+            OverflowPolicy::DROP => write!(f, "{}", OVERFLOW_POLICIES[1]),
+        }
+    }
+}
+
+/// Metrics of the event bus, reported by the health endpoints.
+#[derive(Default)]
+pub struct BusMetrics {
+    published: AtomicU64,
+    dropped: AtomicU64,
+    // Not keeping the bus open, so the messaging engine stops once all producers are gone
+    sender: OnceCell<WeakSender<Event>>,
+}
+
+impl BusMetrics {
+    pub fn published(&self) -> u64 {
+        self.published.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// (events waiting to be handled, capacity of the bus)
+    pub fn depth(&self) -> Option<(usize, usize)> {
+        let sender = self.sender.get()?.upgrade()?;
+        Some((
+            sender.max_capacity() - sender.capacity(),
+            sender.max_capacity(),
+        ))
+    }
+}
+
+/// Bounded channel carrying the events from the producers (scheduler, Slack listener, API) to the
+/// messaging engine, applying the configured overflow policy when full.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: Sender<Event>,
+    policy: OverflowPolicy,
+}
+
+impl EventBus {
+    /// Event bus with the configured capacity and overflow policy, and its receiving end.
+    pub fn new() -> (EventBus, Receiver<Event>) {
+        let settings = &config::SETTINGS;
+        let (sender, receiver) = mpsc::channel::<Event>(settings.event_bus_capacity);
+        let _ = BUS_METRICS.sender.set(sender.downgrade());
+        let policy = OverflowPolicy::from_string(&settings.event_bus_overflow)
+            .unwrap_or(OverflowPolicy::BLOCK);
+        (EventBus { sender, policy }, receiver)
+    }
+
+    pub async fn send(&self, event: Event) -> Result<(), SendError<Event>> {
+        BUS_METRICS.published.fetch_add(1, Ordering::Relaxed);
+        match self.policy {
+            OverflowPolicy::DROP if event.is_snoozable() => match self.sender.try_send(event) {
+                Err(TrySendError::Full(event)) => {
+                    BUS_METRICS.dropped.fetch_add(1, Ordering::Relaxed);
+                    warn!("Event bus full, dropping {}.", <&str>::from(&event));
+                    Ok(())
+                }
+                Err(TrySendError::Closed(event)) => Err(SendError(event)),
+                Ok(()) => Ok(()),
+            },
+            _ => self.sender.send(event).await,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    core::{leaderboard::Entry, source::PUZZLE_EVENT},
+    scheduler::JobProcess,
+    utils::now,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::str::FromStr;
+
+// Identifier of the product generating the feed, as required by iCalendar
+const PRODID: &'static str = "-//christmas-elf-officer//calendar//EN";
+// Domain of the unique ids of the events
+const UID_DOMAIN: &'static str = "christmas-elf-officer";
+// Duration given to the events, which are instants
+const EVENT_DURATION: &'static str = "PT15M";
+
+/// Posts of the bot shown in the calendar, (name, cron expression), from the scheduled jobs.
+pub fn scheduled_posts(jobs: &[JobProcess<'_>]) -> Vec<(String, String)> {
+    jobs.iter()
+        .filter_map(|job| {
+            Some((
+                job.calendar_name()?.to_string(),
+                job.schedule()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// iCalendar feed of the season: the puzzle unlocks, the milestones of the season, and the
+/// scheduled posts of the bot during the season.
+pub fn ics(year: i32, posts: &[(String, String)]) -> String {
+    let event = &PUZZLE_EVENT;
+    let stamp = format_time(now());
+    let mut events = vec![];
+
+    // Days of the season worth a reminder on top of their unlock
+    let days = event.days();
+    let milestones = [
+        (1, "the season starts"),
+        ((days + 1) / 2, "halfway through the season"),
+        (days, "last puzzle of the season"),
+    ];
+    for day in 1..=days {
+        let Ok(unlock) = Entry::puzzle_unlock(year, day) else {
+            continue;
+        };
+        let summary = match milestones.iter().find(|(d, _)| *d == day) {
+            Some((_, milestone)) => {
+                format!("{} {year} day {day} unlocks, {milestone}", event.name())
+            }
+            None => format!("{} {year} day {day} unlocks", event.name()),
+        };
+        events.push(vec![
+            format!("UID:{year}-day-{day}@{UID_DOMAIN}"),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART:{}", format_time(unlock)),
+            format!("DURATION:{EVENT_DURATION}"),
+            format!("SUMMARY:{}", escape_text(&summary)),
+            format!("URL:{}", event.puzzle_url(year, day)),
+        ]);
+    }
+
+    // Posts of the bot are scheduled in UTC, like the jobs posting them, around the season.
+    let (Ok(start), Ok(last)) = (
+        Entry::puzzle_unlock(year, 1),
+        Entry::puzzle_unlock(year, days),
+    ) else {
+        return calendar(event.name(), year, events);
+    };
+    let (start, end) = (start - Duration::days(1), last + Duration::days(2));
+    for (name, schedule) in posts {
+        let Ok(schedule) = cron::Schedule::from_str(schedule) else {
+            continue;
+        };
+        for time in schedule.after(&start).take_while(|time| *time < end) {
+            events.push(vec![
+                format!(
+                    "UID:{}-{}@{UID_DOMAIN}",
+                    name.to_lowercase().replace(' ', "-"),
+                    time.timestamp()
+                ),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART:{}", format_time(time)),
+                format!("DURATION:{EVENT_DURATION}"),
+                format!("SUMMARY:{}", escape_text(&format!("{name} of the bot"))),
+            ]);
+        }
+    }
+    calendar(event.name(), year, events)
+}
+
+fn calendar(name: &str, year: i32, events: Vec<Vec<String>>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{} {year}", escape_text(name)),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.extend(event);
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    // Lines are ended with CRLF, as required by iCalendar
+    lines.join("\r\n") + "\r\n"
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Text values escape backslashes, semicolons, commas and newlines.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
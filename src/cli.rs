@@ -1,5 +1,18 @@
-use clap::Parser;
+use crate::{
+    client::aoc::configured_source,
+    core::{
+        display,
+        standings::{
+            first_star_latencies, member_standing, standings_board, standings_board_iter, Scoring,
+        },
+    },
+    error::{BotError, BotResult},
+    secrets,
+    utils::{current_year_day, format_duration},
+};
+use clap::{Parser, Subcommand};
 use serde::Serialize;
+use std::{fs, path::PathBuf};
 
 fn is_false(b: &bool) -> bool {
     !b
@@ -11,4 +24,119 @@ pub struct Cli {
     #[arg(long)]
     #[serde(skip_serializing_if = "is_false")]
     pub all_years: bool,
+
+    /// Past season to replay at accelerated speed, e.g. for demos
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_year: Option<i32>,
+
+    /// Runs once and prints to stdout, instead of starting the bot
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Standings of the private leaderboard for a year
+    Standings {
+        /// Year of the standings, the current one by default
+        #[arg(long)]
+        year: Option<i32>,
+        /// Scoring of the standings (local or stars)
+        #[arg(long, default_value = "local")]
+        scoring: String,
+    },
+    /// Standing and daily stars of a member for a year
+    Stats {
+        /// AoC name of the member
+        member: String,
+        /// Year of the stats, the current one by default
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Fetches the private leaderboard for a year
+    Fetch {
+        /// Year of the leaderboard, the current one by default
+        #[arg(long)]
+        year: Option<i32>,
+        /// File the entries of the leaderboard are written to as JSON, instead of stdout
+        #[arg(long)]
+        dump: Option<PathBuf>,
+    },
+}
+
+impl CliCommand {
+    /// Runs the command on the private leaderboard freshly fetched, printing to stdout.
+    pub async fn run(&self) -> BotResult<()> {
+        secrets::load().await?;
+        let (current_year, _day) = current_year_day();
+        let year = match self {
+            CliCommand::Standings { year, .. }
+            | CliCommand::Stats { year, .. }
+            | CliCommand::Fetch { year, .. } => year.unwrap_or(current_year),
+        };
+        let scraped = configured_source().fetch_private(year).await?;
+        let leaderboard = &scraped.leaderboard;
+
+        match self {
+            CliCommand::Standings { scoring, .. } => {
+                let scoring = Scoring::from_string(scoring).ok_or_else(|| {
+                    BotError::Config(format!("Unknown scoring method '{scoring}'."))
+                })?;
+                println!(
+                    "{}",
+                    display::board(standings_board(&scoring, leaderboard, year))
+                );
+            }
+            CliCommand::Stats { member, .. } => {
+                let board = standings_board_iter(&Scoring::LOCAL, leaderboard, year);
+                let Some((position, stars, score)) = member_standing(board, member) else {
+                    return Err(BotError::Compute(format!(
+                        "{member} has no stars in {year}."
+                    )));
+                };
+                println!("{member} in {year}: position {position}, {stars} stars, {score} points.");
+                if let Some((_id, best, median, worst)) = first_star_latencies(leaderboard, year)
+                    .into_iter()
+                    .find(|(id, ..)| &*id.name == member)
+                {
+                    println!(
+                        "First star: median {}, best {}, worst {}.",
+                        format_duration(median),
+                        format_duration(best),
+                        format_duration(worst)
+                    );
+                }
+                let days = standings_board_iter(&Scoring::LOCAL, leaderboard, year)
+                    .find(|(id, _scores, _total)| &*id.name == member)
+                    .map(|(_id, scores, _total)| scores);
+                for (day, (stars, points)) in days.into_iter().flatten().enumerate() {
+                    if *stars > 0 {
+                        println!(
+                            "Day {:>2}: {:<2} {points}",
+                            day + 1,
+                            "*".repeat(*stars as usize)
+                        );
+                    }
+                }
+            }
+            CliCommand::Fetch { dump, .. } => {
+                let json = serde_json::to_string_pretty(&leaderboard.iter().collect::<Vec<_>>())
+                    .map_err(|e| BotError::Compute(format!("Could not serialize entries. {e}")))?;
+                match dump {
+                    Some(path) => {
+                        fs::write(path, json)?;
+                        println!(
+                            "{} entries of {year} written to {}.",
+                            leaderboard.len(),
+                            path.display()
+                        );
+                    }
+                    None => println!("{json}"),
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,2 +1,7 @@
 pub mod aoc;
+pub mod feed;
+pub mod fixtures;
+pub mod github;
+pub mod notifiers;
+pub mod replay;
 pub mod slack;
@@ -1,12 +1,41 @@
 use crate::{
+    client::{feed::FeedSource, fixtures::FixtureSource, replay::ReplaySource},
     config,
-    core::leaderboard::{Entry, Identifier, Leaderboard, ProblemPart, ScrapedLeaderboard},
+    core::{
+        leaderboard::{
+            Entry, Identifier, Leaderboard, ProblemPart, ScrapedLeaderboard, SELECTOR_RANK,
+            SELECTOR_TIME,
+        },
+        source::AocSource,
+    },
     error::{BotError, BotResult},
+    health::HEALTH,
+    secrets,
+    utils::{now, record_server_time},
 };
-use chrono::{TimeZone, Utc};
-use reqwest::{Client, StatusCode};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{header, Client, StatusCode};
 use scraper::{Html, Selector};
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, sync::Arc};
+use tracing::{info_span, Instrument};
+
+// Selectors of the AoC pages, named in layout alerts when they stop matching
+const SELECTOR_TITLE: &'static str = "article.day-desc > h2";
+const SELECTOR_FIRST_PART: &'static str = "span.leaderboard-daydesc-first";
+const SELECTOR_BOTH_PARTS: &'static str = "span.leaderboard-daydesc-both";
+const SELECTOR_ENTRY: &'static str = "div.leaderboard-entry";
+// Any element with an AoC user id, entries being expected to have one
+const SELECTOR_MEMBER: &'static str = "[data-user-id]";
+
+// Title of puzzles whose title could not be found
+pub(crate) const UNKNOWN_TITLE: &'static str = "N/A";
+
+// Links to puzzles, e.g. https://adventofcode.com/2023/day/1
+static REGEX_PUZZLE_URL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/(?<year>\d{4})/day/(?<day>\d{1,2})\b").unwrap());
 
 enum Endpoint {
     GlobalLeaderboard(i32, u8),
@@ -30,6 +59,22 @@ impl fmt::Display for Endpoint {
     }
 }
 
+/// Source configured for the bot: fixture files if a fixtures directory is set, the leaderboard
+/// feed of the puzzle event if any, the AoC website otherwise.
+pub fn configured_source() -> Box<dyn AocSource> {
+    let settings = &config::SETTINGS;
+    let source: Box<dyn AocSource> =
+        match (&settings.aoc_fixtures_dir, &settings.event_leaderboard_url) {
+            (Some(dir), _) => Box::new(FixtureSource::new(dir)),
+            (None, Some(url)) => Box::new(FeedSource::new(url)),
+            (None, None) => Box::new(AoC::new()),
+        };
+    match settings.replay_year {
+        Some(year) => Box::new(ReplaySource::new(source, year)),
+        None => source,
+    }
+}
+
 pub struct AoC {
     http_client: Client,
     base_url: String,
@@ -38,42 +83,43 @@ pub struct AoC {
 }
 
 impl AoC {
+    /// Client configured with the bot settings.
     pub fn new() -> Self {
         let settings = &config::SETTINGS;
+        Self::with_session(
+            &settings.aoc_base_url,
+            &secrets::aoc_session_cookie(),
+            settings.aoc_private_leaderboard_id,
+            settings.aoc_api_timeout_sec,
+        )
+    }
+
+    /// Client for a private leaderboard, authenticated with the session cookie of one of its
+    /// members. Requests time out after `timeout_sec` seconds.
+    pub fn with_session(
+        base_url: &str,
+        session_cookie: &str,
+        private_leaderboard_id: u64,
+        timeout_sec: u64,
+    ) -> Self {
         let http_client = Client::builder()
-            .timeout(std::time::Duration::new(settings.aoc_api_timeout_sec, 0))
+            .timeout(std::time::Duration::new(timeout_sec, 0))
             .build()
             .unwrap();
         Self {
             http_client,
-            base_url: settings.aoc_base_url.clone(),
-            private_leaderboard_id: settings.aoc_private_leaderboard_id,
-            session_cookie: settings.aoc_session_cookie.clone(),
+            base_url: base_url.to_string(),
+            private_leaderboard_id,
+            session_cookie: session_cookie.to_string(),
         }
     }
 
-    pub async fn global_leaderboard(&self, year: i32, day: u8) -> BotResult<ScrapedLeaderboard> {
-        let leaderboard_response = self.get_global_leaderboard(year, day).await?;
-        let leaderboard = AoC::parse_global_leaderboard(&leaderboard_response, year, day)?;
-        Ok(ScrapedLeaderboard {
-            timestamp: Utc::now(),
-            leaderboard,
-        })
-    }
-
-    pub async fn private_leaderboard(&self, year: i32) -> BotResult<ScrapedLeaderboard> {
-        let leaderboard_response = self.get_private_leaderboard(year).await?;
-        let leaderboard = AoC::parse_private_leaderboard(&leaderboard_response)?;
-        Ok(ScrapedLeaderboard {
-            timestamp: Utc::now(),
-            leaderboard,
-        })
-    }
-
-    pub async fn daily_challenge(&self, year: i32, day: u8) -> BotResult<String> {
-        let daily_challenge = self.get_daily_challenge(year, day).await?;
-        let title = AoC::parse_daily_challenge_title(&daily_challenge)?;
-        Ok(title)
+    /// (year, day) of the puzzle an url links to, if any.
+    pub fn puzzle_from_url(url: &str) -> Option<(i32, u8)> {
+        let caps = REGEX_PUZZLE_URL.captures(url)?;
+        let year = caps.name("year")?.as_str().parse::<i32>().ok()?;
+        let day = caps.name("day")?.as_str().parse::<u8>().ok()?;
+        Some((year, day))
     }
 
     async fn get(&self, endpoint: &Endpoint, session_cookie: Option<String>) -> BotResult<String> {
@@ -84,10 +130,33 @@ impl AoC {
         if let Some(session) = session_cookie {
             request = request.header("cookie", format!("session={session}"))
         }
-        let response = request.send().await?;
+        let response =
+            request
+                .send()
+                .await
+                .map_err(|e| match e.is_timeout() || e.is_connect() {
+                    true => BotError::Unavailable(e.to_string()),
+                    false => BotError::from(e),
+                })?;
+
+        // The AoC clock is the reference for release times.
+        if let Some(server_time) = response
+            .headers()
+            .get(header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+        {
+            record_server_time(server_time.with_timezone(&Utc));
+        }
 
         match response.status() {
             StatusCode::OK => response.text().await.map_err(|_| BotError::Parse),
+            // AoC is down or overloaded, e.g. right after a release.
+            StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => {
+                Err(BotError::Unavailable(format!("{}", response.status())))
+            }
             // AoC responds with INTERNAL_SERVER_ERROR when the session cookie is invalid.
             StatusCode::INTERNAL_SERVER_ERROR => Err(BotError::Http(format!(
                 "{}. The session cookie might have expired.",
@@ -117,19 +186,25 @@ impl AoC {
         Ok(resp)
     }
 
-    fn parse_daily_challenge_title(challenge: &str) -> BotResult<String> {
+    pub(crate) fn parse_daily_challenge_title(challenge: &str) -> BotResult<String> {
         let document = Html::parse_document(&challenge);
-        let selector_title = Selector::parse(r#"article.day-desc > h2"#).unwrap();
+        let selector_title = Selector::parse(SELECTOR_TITLE).unwrap();
 
-        let default = "N/A";
         let title = document
             .select(&selector_title)
             .next()
-            .map_or(default, |node| node.text().next().unwrap_or(default));
+            .ok_or_else(|| BotError::Layout(format!("`{SELECTOR_TITLE}` found no puzzle title.")))?
+            .text()
+            .next()
+            .unwrap_or(UNKNOWN_TITLE);
         Ok(title.to_string())
     }
 
-    fn parse_global_leaderboard(leaderboard: &str, year: i32, day: u8) -> BotResult<Leaderboard> {
+    pub(crate) fn parse_global_leaderboard(
+        leaderboard: &str,
+        year: i32,
+        day: u8,
+    ) -> BotResult<Leaderboard> {
         // The HTML document is organized like so:
         //
         //      <p>First hundred users to get <span class="leaderboard-daydesc-both">both stars</span> on Day XX:</p>
@@ -144,8 +219,8 @@ impl AoC {
         // information based on the siblings of the <p> elements.
 
         let document = Html::parse_document(&leaderboard);
-        let selector_first_part = Selector::parse(r#"span.leaderboard-daydesc-first"#).unwrap();
-        let selector_second_part = Selector::parse(r#"span.leaderboard-daydesc-both"#).unwrap();
+        let selector_first_part = Selector::parse(SELECTOR_FIRST_PART).unwrap();
+        let selector_second_part = Selector::parse(SELECTOR_BOTH_PARTS).unwrap();
 
         // Entries first part. The selector will only give us the div below the p>span.leaderboard-daydesc-first element
         let entries_first = document
@@ -185,6 +260,8 @@ impl AoC {
                 })
             });
 
+        AoC::check_global_layout(&document, entries_first.len() + entries_second.len())?;
+
         let mut all_entries = Leaderboard::new();
         all_entries.extend(entries_first);
         all_entries.extend(entries_second);
@@ -192,7 +269,34 @@ impl AoC {
         Ok(all_entries)
     }
 
-    fn parse_private_leaderboard(leaderboard: &str) -> BotResult<Leaderboard> {
+    /// Checks the global leaderboard page still has the expected layout, given the number of
+    /// entries parsed. Otherwise the error names the selector which broke, rather than the
+    /// statistics quietly being empty.
+    fn check_global_layout(document: &Html, n_parsed: usize) -> BotResult<()> {
+        let hits = |selector: &str| document.select(&Selector::parse(selector).unwrap()).count();
+        let n_entries = hits(SELECTOR_ENTRY);
+        let n_headings = hits(SELECTOR_FIRST_PART) + hits(SELECTOR_BOTH_PARTS);
+        let broken = if n_entries == 0 {
+            // No entry right after the release, unless entries are there under another class.
+            match hits(SELECTOR_MEMBER) {
+                0 => return Ok(()),
+                n => format!("`{SELECTOR_ENTRY}` found none of the {n} entries"),
+            }
+        } else if n_headings == 0 {
+            format!("`{SELECTOR_FIRST_PART}` and `{SELECTOR_BOTH_PARTS}` found no part heading")
+        } else if hits(SELECTOR_RANK) < n_entries {
+            format!("`{SELECTOR_RANK}` found ranks for some of the {n_entries} entries only")
+        } else if hits(SELECTOR_TIME) < n_entries {
+            format!("`{SELECTOR_TIME}` found times for some of the {n_entries} entries only")
+        } else if n_parsed * 2 < n_entries {
+            format!("only {n_parsed} of the {n_entries} `{SELECTOR_ENTRY}` could be parsed")
+        } else {
+            return Ok(());
+        };
+        Err(BotError::Layout(format!("Global leaderboard: {broken}.")))
+    }
+
+    pub(crate) fn parse_private_leaderboard(leaderboard: &str) -> BotResult<Leaderboard> {
         // Response from AOC private leaderboard API.
         // Structs defined here as it is only used by this function.
         use serde::Deserialize;
@@ -223,13 +327,24 @@ impl AoC {
             get_star_ts: i64,
         }
 
-        let parsed = serde_json::from_str::<AOCPrivateLeaderboardResponse>(&leaderboard).unwrap();
+        let parsed =
+            serde_json::from_str::<AOCPrivateLeaderboardResponse>(&leaderboard).map_err(|e| {
+                match leaderboard.trim_start().starts_with('<') {
+                    // A web page instead of JSON, e.g. the maintenance page
+                    true => BotError::Unavailable("AoC answered with a web page.".to_string()),
+                    false => BotError::AOC(format!("Could not parse private leaderboard. {e}")),
+                }
+            })?;
+        let year: i32 = parsed.event.parse().map_err(|_| BotError::Parse)?;
         let mut earned_stars = Leaderboard::new();
 
         for (_, member) in parsed.members.iter() {
-            let name = match &member.name {
-                Some(name) => name.to_string(),
-                None => format!("anonymous user #{}", member.id),
+            // Members without any star yet are in the leaderboard too.
+            earned_stars.add_member(year, member.id);
+            // Shared by all the entries of the member
+            let name: Arc<str> = match &member.name {
+                Some(name) => name.as_str().into(),
+                None => Identifier::anonymous_name(member.id).into(),
             };
 
             for (day, stars) in member.completion_day_level.iter() {
@@ -240,12 +355,12 @@ impl AoC {
                             .timestamp_opt(info.get_star_ts, 0)
                             .single()
                             .ok_or(BotError::Parse)?,
-                        year: parsed.event.parse().map_err(|_| BotError::Parse)?,
+                        year,
                         day: day.parse::<u8>().map_err(|_| BotError::Parse)?,
                         part: ProblemPart::from(star),
                         rank: None,
                         id: Identifier {
-                            name: name.clone(),
+                            name: Arc::clone(&name),
                             numeric: member.id,
                         },
                     });
@@ -256,3 +371,39 @@ impl AoC {
         Ok(earned_stars)
     }
 }
+
+#[async_trait]
+impl AocSource for AoC {
+    fn name(&self) -> &'static str {
+        "aoc"
+    }
+
+    async fn fetch_private(&self, year: i32) -> BotResult<ScrapedLeaderboard> {
+        let leaderboard_response = self
+            .get_private_leaderboard(year)
+            .instrument(info_span!("fetch", year))
+            .await?;
+        let leaderboard = info_span!("parse")
+            .in_scope(|| AoC::parse_private_leaderboard(&leaderboard_response))?;
+        HEALTH.record_aoc_fetch();
+        Ok(ScrapedLeaderboard {
+            timestamp: now(),
+            leaderboard,
+        })
+    }
+
+    async fn fetch_global_day(&self, year: i32, day: u8) -> BotResult<ScrapedLeaderboard> {
+        let leaderboard_response = self.get_global_leaderboard(year, day).await?;
+        let leaderboard = AoC::parse_global_leaderboard(&leaderboard_response, year, day)?;
+        Ok(ScrapedLeaderboard {
+            timestamp: now(),
+            leaderboard,
+        })
+    }
+
+    async fn fetch_puzzle_title(&self, year: i32, day: u8) -> BotResult<String> {
+        let daily_challenge = self.get_daily_challenge(year, day).await?;
+        let title = AoC::parse_daily_challenge_title(&daily_challenge)?;
+        Ok(title)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    client::aoc::{AoC, UNKNOWN_TITLE},
+    config,
+    core::{leaderboard::ScrapedLeaderboard, source::AocSource},
+    error::{BotError, BotResult},
+    health::HEALTH,
+    utils::now,
+};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+
+/// Private leaderboards of a scheduled puzzle event, published at `event_leaderboard_url` in the
+/// AoC JSON format. Such events have no global leaderboard, and their puzzles no title.
+pub struct FeedSource {
+    http_client: Client,
+    url: String,
+}
+
+impl FeedSource {
+    pub fn new(url: &str) -> Self {
+        let http_client = Client::builder()
+            .timeout(std::time::Duration::new(
+                config::SETTINGS.aoc_api_timeout_sec,
+                0,
+            ))
+            .build()
+            .unwrap();
+        FeedSource {
+            http_client,
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AocSource for FeedSource {
+    fn name(&self) -> &'static str {
+        "feed"
+    }
+
+    fn has_global_leaderboard(&self) -> bool {
+        false
+    }
+
+    async fn fetch_private(&self, year: i32) -> BotResult<ScrapedLeaderboard> {
+        let url = self.url.replace("{year}", &year.to_string());
+        let response = self.http_client.get(url).send().await.map_err(|e| {
+            match e.is_timeout() || e.is_connect() {
+                true => BotError::Unavailable(e.to_string()),
+                false => BotError::from(e),
+            }
+        })?;
+        let content = match response.status() {
+            StatusCode::OK => response.text().await.map_err(|_| BotError::Parse)?,
+            status => return Err(BotError::Http(format!("{status}"))),
+        };
+        HEALTH.record_aoc_fetch();
+        Ok(ScrapedLeaderboard {
+            timestamp: now(),
+            leaderboard: AoC::parse_private_leaderboard(&content)?,
+        })
+    }
+
+    async fn fetch_global_day(&self, _year: i32, _day: u8) -> BotResult<ScrapedLeaderboard> {
+        Err(BotError::AOC(
+            "The puzzle event has no global leaderboard.".to_string(),
+        ))
+    }
+
+    async fn fetch_puzzle_title(&self, _year: i32, _day: u8) -> BotResult<String> {
+        Ok(UNKNOWN_TITLE.to_string())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    client::aoc::AoC,
+    core::{leaderboard::ScrapedLeaderboard, source::AocSource},
+    error::BotResult,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{fs, path::PathBuf};
+
+/// AoC data read from files, as saved from the AoC website:
+/// - `{dir}/{year}/private.json`: private leaderboard of the year,
+/// - `{dir}/{year}/{day}/global.html`: global leaderboard of the day,
+/// - `{dir}/{year}/{day}/puzzle.html`: puzzle of the day.
+pub struct FixtureSource {
+    dir: PathBuf,
+}
+
+impl FixtureSource {
+    pub fn new(dir: &str) -> Self {
+        FixtureSource {
+            dir: PathBuf::from(dir),
+        }
+    }
+
+    fn read(&self, path: PathBuf) -> BotResult<String> {
+        Ok(fs::read_to_string(self.dir.join(path))?)
+    }
+}
+
+#[async_trait]
+impl AocSource for FixtureSource {
+    fn name(&self) -> &'static str {
+        "fixtures"
+    }
+
+    async fn fetch_private(&self, year: i32) -> BotResult<ScrapedLeaderboard> {
+        let content = self.read([&year.to_string(), "private.json"].iter().collect())?;
+        Ok(ScrapedLeaderboard {
+            timestamp: Utc::now(),
+            leaderboard: AoC::parse_private_leaderboard(&content)?,
+        })
+    }
+
+    async fn fetch_global_day(&self, year: i32, day: u8) -> BotResult<ScrapedLeaderboard> {
+        let path = [&year.to_string(), &day.to_string(), "global.html"];
+        let content = self.read(path.iter().collect())?;
+        Ok(ScrapedLeaderboard {
+            timestamp: Utc::now(),
+            leaderboard: AoC::parse_global_leaderboard(&content, year, day)?,
+        })
+    }
+
+    async fn fetch_puzzle_title(&self, year: i32, day: u8) -> BotResult<String> {
+        let path = [&year.to_string(), &day.to_string(), "puzzle.html"];
+        let content = self.read(path.iter().collect())?;
+        AoC::parse_daily_challenge_title(&content)
+    }
+}
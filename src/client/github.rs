@@ -0,0 +1,137 @@
+use crate::{
+    config,
+    error::{BotError, BotResult},
+    secrets,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use regex::Regex;
+use reqwest::{header, Client, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+
+// GitHub requires a user agent on API requests
+const USER_AGENT: &'static str = "christmas-elf-officer";
+// Latest commits of a repository checked for the solution of the day, each commit whose
+// message does not tell costing one more request
+const COMMITS_CHECKED: usize = 10;
+// Placeholder of the day in the pattern matching the commits of a day
+const DAY_PLACEHOLDER: &'static str = "{day}";
+
+#[derive(Debug, Deserialize)]
+struct Commit {
+    sha: String,
+    html_url: String,
+    commit: CommitDetail,
+    // Only given when a single commit is fetched
+    #[serde(default)]
+    files: Vec<ChangedFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitDetail {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChangedFile {
+    filename: String,
+}
+
+/// Commit of a member sharing their solution of a day: (short sha, url).
+#[derive(Debug, Clone)]
+pub struct SolutionCommit {
+    pub sha: String,
+    pub url: String,
+}
+
+pub struct GitHub {
+    http_client: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl GitHub {
+    /// Client configured with the bot settings.
+    pub fn new() -> Self {
+        let settings = &config::SETTINGS;
+        let http_client = Client::builder()
+            .timeout(std::time::Duration::new(settings.aoc_api_timeout_sec, 0))
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap();
+        Self {
+            http_client,
+            base_url: settings.github_api_url.trim_end_matches('/').to_string(),
+            token: secrets::github_token(),
+        }
+    }
+
+    /// Pattern matching the commit messages and the file paths of the solutions of the day.
+    pub fn day_pattern(day: u8) -> BotResult<Regex> {
+        let pattern = config::SETTINGS
+            .github_day_pattern
+            .replace(DAY_PLACEHOLDER, &day.to_string());
+        Regex::new(&pattern).map_err(|e| BotError::Config(e.to_string()))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> BotResult<T> {
+        let mut request = self
+            .http_client
+            .get(format!("{}{path}", self.base_url))
+            .header(header::ACCEPT, "application/vnd.github+json");
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        match response.status() {
+            StatusCode::OK => {
+                let body = response.text().await.map_err(|_| BotError::Parse)?;
+                serde_json::from_str(&body).map_err(|_| BotError::Parse)
+            }
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => Err(BotError::Http(format!(
+                "{}. The GitHub API rate limit might be exceeded, set a GITHUB_TOKEN.",
+                response.status()
+            ))),
+            _ => Err(BotError::Http(format!("{}", response.status()))),
+        }
+    }
+
+    /// Latest commit pushed to the repository since the time sharing the solution of the day:
+    /// its message or one of the files it changed matches the day pattern.
+    pub async fn solution_commit(
+        &self,
+        repository: &str,
+        day: u8,
+        since: DateTime<Utc>,
+    ) -> BotResult<Option<SolutionCommit>> {
+        let pattern = Self::day_pattern(day)?;
+        let since = since.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let commits: Vec<Commit> = self
+            .get(&format!(
+                "/repos/{repository}/commits?since={since}&per_page={COMMITS_CHECKED}"
+            ))
+            .await?;
+
+        // Newest first, the files being fetched for the commits whose message does not tell.
+        for commit in commits {
+            let matching = match pattern.is_match(&commit.commit.message) {
+                true => true,
+                false => {
+                    let detail: Commit = self
+                        .get(&format!("/repos/{repository}/commits/{}", commit.sha))
+                        .await?;
+                    detail
+                        .files
+                        .iter()
+                        .any(|file| pattern.is_match(&file.filename))
+                }
+            };
+            if matching {
+                return Ok(Some(SolutionCommit {
+                    sha: commit.sha.chars().take(7).collect(),
+                    url: commit.html_url,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    config,
+    core::notifier::{Announcement, Notifier},
+    error::{BotError, BotResult},
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use tracing::error;
+
+/// Posts announcements as JSON (name, payload and text) to an url, for custom integrations.
+pub struct WebhookNotifier {
+    http_client: Client,
+    url: String,
+}
+
+/// Posts the text of announcements to a Discord channel webhook.
+pub struct DiscordNotifier {
+    http_client: Client,
+    url: String,
+}
+
+/// Prints the text of announcements to stdout.
+pub struct ConsoleNotifier;
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn notify(&self, announcement: &Announcement) -> BotResult<()> {
+        self.http_client
+            .post(&self.url)
+            .json(announcement)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn notify(&self, announcement: &Announcement) -> BotResult<()> {
+        self.http_client
+            .post(&self.url)
+            .json(&json!({ "content": announcement.text }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for ConsoleNotifier {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+
+    async fn notify(&self, announcement: &Announcement) -> BotResult<()> {
+        println!("[{}] {}", announcement.name, announcement.text);
+        Ok(())
+    }
+}
+
+/// Frontends configured next to Slack.
+pub fn configured_notifiers() -> Vec<Box<dyn Notifier>> {
+    let settings = &config::SETTINGS;
+    let http_client = Client::builder()
+        .timeout(std::time::Duration::new(settings.aoc_api_timeout_sec, 0))
+        .build()
+        .unwrap();
+
+    let mut notifiers: Vec<Box<dyn Notifier>> = vec![];
+    for url in settings.notifier_webhooks.iter().flatten() {
+        notifiers.push(Box::new(WebhookNotifier {
+            http_client: http_client.clone(),
+            url: url.to_string(),
+        }));
+    }
+    if let Some(url) = &settings.discord_webhook {
+        notifiers.push(Box::new(DiscordNotifier {
+            http_client: http_client.clone(),
+            url: url.to_string(),
+        }));
+    }
+    if settings.console_announcements {
+        notifiers.push(Box::new(ConsoleNotifier));
+    }
+    notifiers
+}
+
+/// Sends the announcement to all the frontends, failures being logged.
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], announcement: &Announcement) {
+    for notifier in notifiers {
+        if let Err(e) = notifier.notify(announcement).await {
+            let error = BotError::Http(format!(
+                "Could not send announcement to {}. {e}",
+                notifier.name()
+            ));
+            error!("{error}");
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    config,
+    core::{
+        leaderboard::{Entry, Leaderboard, ScrapedLeaderboard},
+        source::AocSource,
+    },
+    error::BotResult,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use tokio::sync::OnceCell;
+
+// Start of the replay, when the first puzzle of the replayed season unlocks
+static REPLAY_START: Lazy<DateTime<Utc>> = Lazy::new(Utc::now);
+// Private leaderboard of the replayed season, fetched once when the replay starts
+static SEASON: OnceCell<ScrapedLeaderboard> = OnceCell::const_new();
+
+/// Time in the replayed season, a day lasting `replay_day_sec` seconds from the replay start.
+pub fn replay_time(year: i32) -> BotResult<DateTime<Utc>> {
+    let settings = &config::SETTINGS;
+    let elapsed = (Utc::now() - *REPLAY_START).num_milliseconds();
+    let replayed = elapsed * 86_400 / settings.replay_day_sec.max(1) as i64;
+    Ok(Entry::puzzle_unlock(year, 1)? + Duration::milliseconds(replayed))
+}
+
+/// Past season replayed from another source, only showing the stars obtained by the time of
+/// the replay. The private leaderboard of the season is fetched once, and filtered on each
+/// scrape: the source is not asked again for the whole replay.
+pub struct ReplaySource {
+    source: Box<dyn AocSource>,
+    year: i32,
+}
+
+impl ReplaySource {
+    pub fn new(source: Box<dyn AocSource>, year: i32) -> Self {
+        ReplaySource { source, year }
+    }
+
+    /// Stars of the replayed season obtained by the time of the replay.
+    fn replayed(&self, year: i32, scraped: &ScrapedLeaderboard) -> BotResult<ScrapedLeaderboard> {
+        let time = replay_time(year)?;
+        let mut leaderboard = Leaderboard::new();
+        for id in scraped.leaderboard.members_for_year(year) {
+            leaderboard.add_member(year, id);
+        }
+        leaderboard.extend(
+            scraped
+                .leaderboard
+                .iter()
+                .filter(|e| e.timestamp <= time)
+                .cloned(),
+        );
+        Ok(ScrapedLeaderboard {
+            timestamp: time,
+            leaderboard,
+        })
+    }
+}
+
+#[async_trait]
+impl AocSource for ReplaySource {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    async fn fetch_private(&self, year: i32) -> BotResult<ScrapedLeaderboard> {
+        if year != self.year {
+            return self.source.fetch_private(year).await;
+        }
+        let season = SEASON
+            .get_or_try_init(|| self.source.fetch_private(year))
+            .await?;
+        self.replayed(year, season)
+    }
+
+    async fn fetch_global_day(&self, year: i32, day: u8) -> BotResult<ScrapedLeaderboard> {
+        let scraped = self.source.fetch_global_day(year, day).await?;
+        match year == self.year {
+            true => self.replayed(year, &scraped),
+            false => Ok(scraped),
+        }
+    }
+
+    async fn fetch_puzzle_title(&self, year: i32, day: u8) -> BotResult<String> {
+        self.source.fetch_puzzle_title(year, day).await
+    }
+}
@@ -1,131 +1,806 @@
 use crate::{
+    bus::EventBus,
+    client::{
+        aoc::{configured_source, AoC},
+        notifiers::{configured_notifiers, notify_all},
+    },
     config,
-    core::{commands::Command, events::Event},
-    error::BotError,
-    storage::MemoryCache,
+    core::{
+        commands::Command,
+        display,
+        events::{Event, MUTABLE_EVENTS},
+        identities,
+        leaderboard::{Entry, ProblemPart},
+        standings::{member_standing, standings_board_iter, Scoring},
+        templates::{MessageTemplate, RenderOptions},
+    },
+    error::{BotError, BotResult},
+    handoff::Handoff,
+    health::HEALTH,
+    secrets,
+    shutdown::SHUTDOWN,
+    storage::{
+        AuditEntry, DeadLetter, MemoryCache, SolutionPost, StoredSettings, AUDIT, DEAD_LETTERS,
+        KUDOS, SETTINGS_STORE,
+    },
+    utils::{current_year_day, format_duration, merge_highlights, now, DayHighlight},
 };
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
 use http::StatusCode;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response,
+};
+use minijinja::context;
+use serde::Serialize;
 use slack_morphism::{
-    api::SlackApiChatPostMessageRequest,
-    events::{SlackEventCallbackBody, SlackPushEventCallback},
-    hyper_tokio::{SlackClientHyperConnector, SlackHyperClient},
-    listener::{SlackClientEventsListenerEnvironment, SlackClientEventsUserState},
-    SlackApiToken, SlackApiTokenValue, SlackChannelId, SlackClient, SlackClientSocketModeConfig,
-    SlackClientSocketModeListener, SlackMessageContent, SlackSocketModeListenerCallbacks,
+    api::{
+        SlackApiChatGetPermalinkRequest, SlackApiChatPostEphemeralRequest,
+        SlackApiChatPostMessageRequest, SlackApiChatScheduleMessageRequest,
+        SlackApiChatUnfurlMapItemV2, SlackApiChatUnfurlRequestV2, SlackApiChatUnfurlSource,
+        SlackApiChatUpdateRequest, SlackApiConversationsInfoRequest,
+        SlackApiConversationsSetTopicRequest, SlackApiFilesUploadRequest,
+        SlackApiReactionsAddRequest, SlackApiUserGroupsUsersListRequest, SlackApiUsersInfoRequest,
+        SlackApiViewsOpenRequest, SlackApiViewsPublishRequest,
+    },
+    blocks::{
+        SlackActionBlockElement, SlackActionsBlock, SlackBlockButtonElement, SlackBlockChoiceItem,
+        SlackBlockConversationsSelectElement, SlackBlockId, SlackBlockMarkDownText,
+        SlackBlockMultiStaticSelectElement, SlackBlockPlainTextOnly, SlackBlockStaticSelectElement,
+        SlackHomeView, SlackInputBlock, SlackInputBlockElement, SlackModalView, SlackSectionBlock,
+        SlackView,
+    },
+    events::{
+        SlackCommandEvent, SlackCommandEventResponse, SlackEventCallbackBody,
+        SlackInteractionActionContainer, SlackInteractionBlockActionsEvent, SlackInteractionEvent,
+        SlackInteractionViewSubmissionEvent, SlackLinkSharedEvent, SlackPushEvent,
+        SlackPushEventCallback,
+    },
+    hyper_tokio::{
+        chain_service_routes_fn, SlackClientEventsHyperListener, SlackClientHyperConnector,
+        SlackClientHyperHttpsConnector, SlackHyperClient,
+    },
+    listener::{
+        SlackClientEventsListenerEnvironment, SlackClientEventsUserState,
+        SlackCommandEventsListenerConfig, SlackInteractionEventsListenerConfig,
+        SlackPushEventsListenerConfig,
+    },
+    prelude::SlackApiRateControlConfig,
+    SlackActionId, SlackApiToken, SlackApiTokenValue, SlackChannelId, SlackClient,
+    SlackClientSession, SlackClientSocketModeConfig, SlackClientSocketModeListener,
+    SlackConversationId, SlackDateTime, SlackMessageContent, SlackReactionName, SlackReactionsItem,
+    SlackSocketModeListenerCallbacks, SlackTs, SlackUserGroupId, SlackUserId,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
 };
-use std::sync::Arc;
-use tokio::sync::mpsc::{Receiver, Sender};
-use tracing::error;
+use tokio::{sync::mpsc::Receiver, task::JoinHandle};
+use tracing::{error, info, info_span, instrument, warn, Instrument};
+
+const SLACK_TRANSPORTS: [&'static str; 2] = ["socket_mode", "events_api"];
+// App Home tab and its quick actions
+const APP_HOME_TAB: &'static str = "home";
+const HOME_REFRESH_ACTION: &'static str = "home_refresh";
+const HOME_NUDGE_ACTION: &'static str = "home_nudge";
+const ADMIN_ONLY_MESSAGE: &'static str = "🔒 Sorry, only admins can do this.";
+// Results of the commands recorded in the audit log
+const AUDIT_OK: &'static str = "ok";
+const AUDIT_DENIED: &'static str = "denied: admin only";
+const SLACK_MAX_RETRIES: usize = 5;
+const REPLY_MODES: [&'static str; 3] = ["thread", "channel", "output_thread"];
+const OUTPUT_THREAD_MESSAGE: &'static str =
+    "🤖 *Bot output* - command responses are posted in this thread.";
+const PAGE_PREVIOUS_ACTION: &'static str = "page_previous";
+const PAGE_NEXT_ACTION: &'static str = "page_next";
+const PAGE_VALUE_SEPARATOR: char = '|';
+const CONFIG_SUBCOMMAND: &'static str = "config";
+const CONFIG_CALLBACK_ID: &'static str = "aoc_config";
+const CONFIG_DEFAULT_CHANNEL: &'static str = "default_channel";
+const CONFIG_MONITORING_CHANNEL: &'static str = "monitoring_channel";
+const CONFIG_DEFAULT_YEAR: &'static str = "default_year";
+const CONFIG_MUTED_EVENTS: &'static str = "muted_events";
+const CURRENT_YEAR_VALUE: &'static str = "current";
+const FIRST_AOC_YEAR: i32 = 2015;
+const REACTION_STAR: &'static str = "star";
+const REACTION_BOTH_STARS: &'static str = "star2";
+const REACTION_FIRST: &'static str = "trophy";
+// Longest text of a message, longer ones being split (Slack truncates at 40000 characters, and
+// recommends staying under 4000)
+const SLACK_MESSAGE_LIMIT: usize = 4000;
+const CODE_FENCE: &'static str = "```";
+// Reactions counted as kudos on solution posts
+const KUDOS_REACTIONS: [&'static str; 3] = ["star", "heart", "fire"];
+
+// Request of the pins.add/pins.remove Slack methods, not covered by the Slack client.
+#[derive(Serialize)]
+struct SlackApiPinsRequest {
+    channel: SlackChannelId,
+    timestamp: SlackTs,
+}
+
+// Request of the bookmarks.add Slack method, not covered by the Slack client.
+#[derive(Serialize)]
+struct SlackApiBookmarksAddRequest {
+    channel_id: SlackChannelId,
+    title: String,
+    #[serde(rename = "type")]
+    bookmark_type: String,
+    link: String,
+}
+
+// Current daily solutions thread (channel, ts)
+type SolutionsThread = Arc<Mutex<Option<(SlackChannelId, SlackTs)>>>;
 
 struct MyEnvironment {
-    sender: Arc<Sender<Event>>,
+    sender: Arc<EventBus>,
     cache: MemoryCache,
+    solutions_thread: SolutionsThread,
+    // User id of the bot itself, if known
+    bot_user: Option<SlackUserId>,
 }
 
 pub struct AoCSlackClient {
     client: Arc<SlackHyperClient>,
+    solutions_thread: SolutionsThread,
 }
 
 impl AoCSlackClient {
     pub fn new() -> Self {
-        let client = Arc::new(SlackClient::new(SlackClientHyperConnector::new()));
-        Self { client }
+        // Outgoing calls are throttled according to the Slack methods rate limits, and retried
+        // after the delay requested by Slack when rate limited anyway.
+        let rate_control = SlackApiRateControlConfig::new().with_max_retries(SLACK_MAX_RETRIES);
+        let connector = SlackClientHyperConnector::new().with_rate_control(rate_control);
+        let client = Arc::new(SlackClient::new(connector));
+        Self {
+            client,
+            solutions_thread: Arc::new(Mutex::new(None)),
+        }
     }
 
+    /// Handles the events until the bot is stopped, resuming from the state handed off by the
+    /// previous process. Returns the state to hand off to the next one.
     pub async fn handle_messages_and_events(
         &self,
         cache: MemoryCache,
-        tx: Sender<Event>,
+        tx: EventBus,
         rx: Receiver<Event>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        self.listen_for_events(rx).await;
-        self.start_slack_client_with_socket_mode(cache.clone(), tx)
-            .await?;
-        Ok(())
+        resumed: Handoff,
+    ) -> Result<Handoff, Box<dyn std::error::Error + Send + Sync>> {
+        let settings = &config::SETTINGS;
+        *self.solutions_thread.lock().unwrap() = resumed
+            .solutions_thread
+            .clone()
+            .map(|(channel_id, ts)| (SlackChannelId(channel_id), SlackTs(ts)));
+        let listener = self.listen_for_events(tx.clone(), rx, resumed).await;
+        match SlackTransport::from_string(&settings.slack_transport) {
+            Some(SlackTransport::SOCKET) => {
+                self.start_slack_client_with_socket_mode(cache.clone(), tx)
+                    .await?
+            }
+            Some(SlackTransport::EVENTS) => {
+                self.start_slack_client_with_events_api(cache.clone(), tx)
+                    .await?
+            }
+            None => {
+                return Err(Box::new(BotError::Config(format!(
+                    "Unknown Slack transport '{}'.",
+                    settings.slack_transport
+                ))))
+            }
+        };
+
+        // Slack is disconnected, waiting for the messages in flight to be sent.
+        let timeout = std::time::Duration::from_secs(settings.shutdown_timeout_sec);
+        let mut handoff = match tokio::time::timeout(timeout, listener).await {
+            Ok(Ok(handoff)) => handoff,
+            Ok(Err(e)) => {
+                error!("Messaging engine stopped unexpectedly. {e}");
+                Handoff::default()
+            }
+            Err(_) => {
+                warn!("Pending messages not sent after {timeout:?}, giving up.");
+                Handoff::default()
+            }
+        };
+        handoff.solutions_thread = self
+            .solutions_thread
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(channel_id, ts)| (channel_id.0.to_string(), ts.0.to_string()));
+        Ok(handoff)
     }
 
     // Spaw listener for events and post corresponding annoucements/messages
-    async fn listen_for_events(&self, mut rx: Receiver<Event>) {
+    async fn listen_for_events(
+        &self,
+        tx: EventBus,
+        mut rx: Receiver<Event>,
+        resumed: Handoff,
+    ) -> JoinHandle<Handoff> {
         let client = self.client.clone();
+        let solutions_thread = self.solutions_thread.clone();
 
         tokio::spawn(async move {
             let settings = &config::SETTINGS;
-            while let Some(event) = rx.recv().await {
-                let channel_id = SlackChannelId(settings.slack_default_channel.to_string());
-                let app_token_value: SlackApiTokenValue = settings.slack_token.to_string().into();
-                let app_token: SlackApiToken = SlackApiToken::new(app_token_value);
-                let session = client.open_session(&app_token);
-
-                let response_text = event.to_string();
-
-                let response = match &event {
-                    Event::PrivateLeaderboardUpdated => {
-                        if let Some(channel_id) = &settings.slack_monitoring_channel {
-                            Some(SlackApiChatPostMessageRequest::new(
-                                SlackChannelId(channel_id.to_string()),
-                                SlackMessageContent::new().with_text(response_text),
-                            ))
-                        } else {
-                            None
-                        }
-                    }
-                    Event::CommandReceived(channel_id, thread_ts, _cmd) => {
-                        // let data = cache.data.lock().unwrap();
-                        // // TODO: inject timestamp too
-                        // let ranking = data.leaderboard.standings_by_local_score();
-
-                        Some(
-                            SlackApiChatPostMessageRequest::new(
-                                channel_id.clone(),
-                                SlackMessageContent::new().with_text(response_text),
-                            )
-                            .with_thread_ts(thread_ts.clone()),
-                        )
+            // Dedicated bot output thread per channel
+            let mut output_threads: HashMap<SlackChannelId, SlackTs> = resumed
+                .output_threads
+                .into_iter()
+                .map(|(channel_id, ts)| (SlackChannelId(channel_id), SlackTs(ts)))
+                .collect();
+            // Events received while coalescing announcements, to be handled next.
+            // Announcements handed off by the previous process come first.
+            let mut pending: VecDeque<Event> = resumed
+                .pending
+                .into_iter()
+                .map(|(channel_id, text)| Event::HandedOff(SlackChannelId(channel_id), text))
+                .collect();
+            // Snoozed channels, with the end of the snooze and the announcements held meanwhile
+            let mut snoozes: HashMap<SlackChannelId, (DateTime<Utc>, Vec<Event>)> = HashMap::new();
+            for (channel_id, until) in resumed.snoozes {
+                let channel_id = SlackChannelId(channel_id);
+                wake_up_at(tx.clone(), channel_id.clone(), until);
+                snoozes.insert(channel_id, (until, vec![]));
+            }
+            // Completions held during the aggregation window, if one is open
+            let mut held_completions: Option<Vec<DayHighlight>> = None;
+            // Frontends announcements are sent to as well
+            let notifiers = configured_notifiers();
+            while let Some(event) = next_event(&mut rx, &mut pending).await {
+                // Completions are held during the aggregation window, and announced together
+                // once it ends.
+                let event = match event {
+                    Event::PrivateLeaderboardNewEntries(highlights)
+                        if settings.completions_window_sec > 0 =>
+                    {
+                        if held_completions.is_none() {
+                            end_window_after(tx.clone(), settings.completions_window_sec);
+                        }
+                        held_completions
+                            .get_or_insert_with(Vec::new)
+                            .extend(highlights);
+                        continue;
+                    }
+                    Event::CompletionsWindowEnded => match held_completions.take() {
+                        Some(highlights) => {
+                            Event::PrivateLeaderboardNewEntries(merge_highlights(highlights))
+                        }
+                        None => continue,
+                    },
+                    event => event,
+                };
+                // Everything logged while handling the event is attached to its kind.
+                let span = info_span!("dispatch", event = <&str>::from(&event));
+                // Commands are audited once handled, with the reason they were not answered if any.
+                let audited = match &event {
+                    Event::CommandReceived(channel_id, ts, cmd, _user_id) => {
+                        let result = match cmd {
+                            Command::NotValid(reason) => format!("invalid: {reason}"),
+                            _ => AUDIT_OK.to_string(),
+                        };
+                        Some((channel_id.clone(), ts.clone(), result))
                     }
-                    _ => Some(SlackApiChatPostMessageRequest::new(
-                        channel_id.clone(),
-                        SlackMessageContent::new().with_text(response_text),
-                    )),
+                    _ => None,
                 };
+                let mut failure: Option<String> = None;
+                async {
+                    if event.is_muted() {
+                        return;
+                    }
+                    // Public announcements are sent to the other frontends too.
+                    if !notifiers.is_empty() {
+                        if let Some(announcement) = event.announcement() {
+                            notify_all(&notifiers, &announcement).await;
+                        }
+                    }
+                    let stored = SETTINGS_STORE.get();
+                    let channel_id = replay_channel().unwrap_or_else(|| {
+                        SlackChannelId(
+                            stored
+                                .default_channel
+                                .unwrap_or_else(|| settings.slack_default_channel.to_string()),
+                        )
+                    });
+                    let app_token_value: SlackApiTokenValue = secrets::slack_token().into();
+                    let app_token: SlackApiToken = SlackApiToken::new(app_token_value);
+                    let session = client.open_session(&app_token);
 
-                if let Some(response) = response {
-                    match session.chat_post_message(&response).await {
-                        Err(e) => {
+                    // Other pages of a command response are shown by updating the response in place.
+                    if let Event::CommandPageRequested(channel_id, message_ts, cmd, page) = &event {
+                        let content = command_content(&event, cmd, channel_id, *page);
+                        let request = SlackApiChatUpdateRequest::new(
+                            channel_id.clone(),
+                            content,
+                            message_ts.clone(),
+                        );
+                        if let Err(e) = session.chat_update(&request).await {
                             let error = BotError::Slack(e.to_string());
                             error!("{error}");
+                        };
+                        return;
+                    }
+
+                    if let Event::DeadLettersReplayRequested = event {
+                        replay_dead_letters(&session).await;
+                        return;
+                    }
+
+                    // Snooze timers of channels snoozed again or woken up in the meantime are ignored.
+                    if let Event::SnoozeEnded(channel_id, until) = &event {
+                        if !snoozes
+                            .get(channel_id)
+                            .is_some_and(|(end, _held)| end == until)
+                        {
+                            return;
+                        }
+                        end_snooze(&mut snoozes, channel_id, &mut pending);
+                    }
+
+                    // Countdowns are scheduled on Slack side, so they are posted right on time.
+                    if let Event::DailyCountdown(year, day, minutes) = &event {
+                        let channel_id = event
+                            .mutable_name()
+                            .and_then(routed_channel)
+                            .unwrap_or_else(|| channel_id.clone());
+                        if let Err(e) =
+                            schedule_countdown(&session, &event, &channel_id, *year, *day, *minutes)
+                                .await
+                        {
+                            error!("{e}");
+                        }
+                        return;
+                    }
+
+                    // The current leader is shown in the channel topic.
+                    if let Event::PrivateLeaderboardNewLeader(..) = &event {
+                        if settings.slack_leader_topic {
+                            let topic = event.for_channel(&channel_id.0).to_string();
+                            let request = SlackApiConversationsSetTopicRequest::new(
+                                channel_id.clone(),
+                                topic,
+                            );
+                            if let Err(e) = session.conversations_set_topic(&request).await {
+                                let error = BotError::Slack(e.to_string());
+                                error!("{error}");
+                            };
                         }
-                        Ok(res) => {
-                            // If Solution thread initialization, post a first message in thread
-                            if let Event::DailySolutionsThreadToInitialize(_day) = event {
-                                let thread_ts = res.ts;
-                                let message = format!(":warning: Last warning, spoiler ahead!");
-                                let first_thread_message = SlackApiChatPostMessageRequest::new(
-                                    channel_id,
-                                    SlackMessageContent::new().with_text(message),
+                        return;
+                    }
+
+                    // Standings messages are edited in place rather than posted again.
+                    if let Event::PrivateLeaderboardUpdated(..) = &event {
+                        let standings_channels =
+                            settings.slack_standings_channels.clone().or_else(|| {
+                                stored
+                                    .monitoring_channel
+                                    .clone()
+                                    .or_else(|| settings.slack_monitoring_channel.clone())
+                                    .map(|channel_id| vec![channel_id])
+                            });
+                        for channel_id in standings_channels.unwrap_or_default() {
+                            let channel_id = SlackChannelId(channel_id);
+                            if let Err(e) = update_standings(&session, &event, &channel_id).await {
+                                error!("{e}");
+                            }
+                        }
+                        return;
+                    }
+
+                    // Channel the event should be posted to, if any.
+                    let target_channel = match &event {
+                        Event::CommandReceived(channel_id, _thread_ts, _cmd, _user_id) => {
+                            Some(channel_id.clone())
+                        }
+                        // The end of a snooze is announced in the snoozed channel
+                        Event::SnoozeEnded(channel_id, _until) => Some(channel_id.clone()),
+                        Event::HandedOff(channel_id, _text) => Some(channel_id.clone()),
+                        // The spotlight and the solutions pushed to GitHub are posted in the daily
+                        // solutions thread, if any
+                        Event::DailySpotlight(..) | Event::GitHubSolution(..) => solutions_thread
+                            .lock()
+                            .unwrap()
+                            .as_ref()
+                            .map(|(channel_id, _ts)| channel_id.clone())
+                            .or_else(|| {
+                                Some(
+                                    event
+                                        .mutable_name()
+                                        .and_then(routed_channel)
+                                        .unwrap_or_else(|| channel_id.clone()),
                                 )
-                                .with_thread_ts(thread_ts);
-                                if let Err(e) =
-                                    session.chat_post_message(&first_thread_message).await
+                            }),
+                        // Layout alerts are for admins only
+                        Event::AocLayoutChanged(_details) => stored
+                            .monitoring_channel
+                            .clone()
+                            .or_else(|| settings.slack_monitoring_channel.clone())
+                            .map(SlackChannelId),
+                        // Nudges and welcome messages are sent as direct messages
+                        Event::NudgeMember(user, _, _)
+                        | Event::WelcomeMember(user, _)
+                        | Event::IdentityVerified(user, _) => {
+                            Some(SlackChannelId(user.to_string()))
+                        }
+                        // Announcements follow the routing table, if any route is configured
+                        _ => Some(
+                            event
+                                .mutable_name()
+                                .and_then(routed_channel)
+                                .unwrap_or_else(|| channel_id.clone()),
+                        ),
+                    };
+
+                    // Non-critical announcements are held while their channel is snoozed.
+                    if event.is_snoozable() {
+                        if let Some((_until, held)) = target_channel
+                            .as_ref()
+                            .and_then(|channel| snoozes.get_mut(channel))
+                        {
+                            held.push(event);
+                            return;
+                        }
+                    }
+
+                    // Thread commands are answered in, depending on the channel reply mode.
+                    let reply_thread = match &event {
+                        Event::CommandReceived(channel_id, thread_ts, _cmd, _user_id) => {
+                            match ReplyMode::for_channel(&channel_id.0) {
+                                ReplyMode::THREAD => Some(thread_ts.clone()),
+                                ReplyMode::CHANNEL => None,
+                                ReplyMode::OUTPUT => {
+                                    output_thread(&session, &mut output_threads, channel_id).await
+                                }
+                            }
+                        }
+                        Event::DailySpotlight(..) | Event::GitHubSolution(..) => solutions_thread
+                            .lock()
+                            .unwrap()
+                            .as_ref()
+                            .map(|(_channel_id, ts)| ts.clone()),
+                        _ => None,
+                    };
+
+                    // Admin commands are refused to other users, with a message only visible to them.
+                    if let Event::CommandReceived(channel_id, _thread_ts, cmd, user_id) = &event {
+                        if cmd.requires_admin() {
+                            match user_id {
+                                Some(user_id) if is_admin(&session, user_id).await => (),
+                                Some(user_id) => {
+                                    let request = SlackApiChatPostEphemeralRequest::new(
+                                        channel_id.clone(),
+                                        user_id.clone(),
+                                        SlackMessageContent::new()
+                                            .with_text(ADMIN_ONLY_MESSAGE.to_string()),
+                                    )
+                                    .opt_thread_ts(reply_thread);
+                                    if let Err(e) = session.chat_post_ephemeral(&request).await {
+                                        let error = BotError::Slack(e.to_string());
+                                        error!("{error}");
+                                    };
+                                    failure = Some(AUDIT_DENIED.to_string());
+                                    return;
+                                }
+                                None => {
+                                    failure = Some(AUDIT_DENIED.to_string());
+                                    return;
+                                }
+                            }
+                        }
+                    }
+
+                    // Commands do what they ask once allowed, e.g. registering an identity.
+                    let event = match event {
+                        Event::CommandReceived(channel_id, thread_ts, cmd, user_id) => {
+                            let caller = user_id.as_ref().map(|user_id| user_id.0.as_str());
+                            let cmd = cmd.apply(caller);
+                            if let Command::NotValid(reason) = &cmd {
+                                failure = Some(format!("invalid: {reason}"));
+                            }
+                            Event::CommandReceived(channel_id, thread_ts, cmd, user_id)
+                        }
+                        event => event,
+                    };
+
+                    // Snoozes are started and ended by commands, a timer waking the channel up.
+                    if let Event::CommandReceived(channel_id, _thread_ts, cmd, _user_id) = &event {
+                        match cmd {
+                            Command::Snooze(until) => {
+                                snoozes
+                                    .entry(channel_id.clone())
+                                    .and_modify(|snooze| snooze.0 = *until)
+                                    .or_insert((*until, vec![]));
+                                wake_up_at(tx.clone(), channel_id.clone(), *until);
+                            }
+                            Command::Unsnooze => end_snooze(&mut snoozes, channel_id, &mut pending),
+                            _ => (),
+                        }
+                    }
+
+                    // Exports are uploaded as files, the response being the comment of the file.
+                    // Ephemeral answers can't carry files, they are sent without.
+                    if let Event::CommandReceived(channel_id, _thread_ts, cmd, _user_id) = &event {
+                        let attachment = cmd
+                            .attachment()
+                            .filter(|_| !cmd.keyword().is_some_and(is_ephemeral_command));
+                        if let Some((filename, content)) = attachment {
+                            let request = SlackApiFilesUploadRequest::new()
+                                .with_channels(vec![channel_id.clone()])
+                                .with_content(content)
+                                .with_filename(filename.clone())
+                                .with_title(filename)
+                                .with_initial_comment(event.for_channel(&channel_id.0).to_string())
+                                .opt_thread_ts(reply_thread);
+                            if let Err(e) = session.files_upload(&request).await {
+                                let error = BotError::Slack(e.to_string());
+                                error!("{error}");
+                                failure = Some(error.to_string());
+                            };
+                            return;
+                        }
+                    }
+
+                    // Personal commands can be answered with a message only visible to the requester.
+                    if let Event::CommandReceived(channel_id, _thread_ts, cmd, Some(user_id)) =
+                        &event
+                    {
+                        if cmd.keyword().is_some_and(is_ephemeral_command) {
+                            let text = event.for_channel(&channel_id.0).to_string();
+                            let request = SlackApiChatPostEphemeralRequest::new(
+                                channel_id.clone(),
+                                user_id.clone(),
+                                SlackMessageContent::new().with_text(text),
+                            )
+                            .opt_thread_ts(reply_thread);
+                            if let Err(e) = session.chat_post_ephemeral(&request).await {
+                                let error = BotError::Slack(e.to_string());
+                                error!("{error}");
+                                failure = Some(error.to_string());
+                            };
+                            return;
+                        }
+                    }
+
+                    let response = info_span!("render").in_scope(|| {
+                        target_channel.map(|target_channel| match &event {
+                            Event::CommandReceived(_channel_id, _thread_ts, cmd, _user_id) => {
+                                let content = command_content(&event, cmd, &target_channel, 0);
+                                SlackApiChatPostMessageRequest::new(target_channel, content)
+                                    .opt_thread_ts(reply_thread)
+                            }
+                            _ => {
+                                let response_text =
+                                    event.for_channel(&target_channel.0).to_string();
+                                SlackApiChatPostMessageRequest::new(
+                                    target_channel,
+                                    SlackMessageContent::new().with_text(response_text),
+                                )
+                                .opt_thread_ts(reply_thread)
+                            }
+                        })
+                    });
+
+                    if let Some(response) = response {
+                        // Long messages are sent in several parts, the last one being the response.
+                        let (leading, response) = split_request(response);
+                        for request in leading {
+                            if let Err(e) = session.chat_post_message(&request).await {
+                                let error = BotError::Slack(e.to_string());
+                                error!("{error}");
+                                failure = Some(error.to_string());
+                                dead_letter(&session, &request, &error).await;
+                            }
+                        }
+                        match session
+                            .chat_post_message(&response)
+                            .instrument(info_span!("send"))
+                            .await
+                        {
+                            Err(e) => {
+                                let error = BotError::Slack(e.to_string());
+                                error!("{error}");
+                                failure = Some(error.to_string());
+                                dead_letter(&session, &response, &error).await;
+                            }
+                            Ok(res) => {
+                                if let Event::CommandReceived(_, _, Command::Replay(_), _) = &event
                                 {
-                                    let error = BotError::Slack(e.to_string());
-                                    error!("{error}");
-                                };
+                                    replay_dead_letters(&session).await;
+                                }
+
+                                // If Solution thread initialization, post a first message in thread
+                                if let Event::DailySolutionsThreadToInitialize(day) = event {
+                                    let channel_id = res.channel.clone();
+                                    let thread_ts = res.ts.clone();
+                                    let message = format!(":warning: Last warning, spoiler ahead!");
+                                    let first_thread_message = SlackApiChatPostMessageRequest::new(
+                                        channel_id.clone(),
+                                        SlackMessageContent::new().with_text(message),
+                                    )
+                                    .with_thread_ts(thread_ts.clone());
+                                    if let Err(e) =
+                                        session.chat_post_message(&first_thread_message).await
+                                    {
+                                        let error = BotError::Slack(e.to_string());
+                                        error!("{error}");
+                                    };
+
+                                    // Pin today's thread in place of yesterday's, and bookmark it.
+                                    let previous_thread = solutions_thread.lock().unwrap().take();
+                                    if let Some((channel_id, ts)) = previous_thread {
+                                        if let Err(e) =
+                                            unpin_message(&session, &channel_id, &ts).await
+                                        {
+                                            error!("{e}");
+                                        }
+                                    }
+                                    if let Err(e) = pin_and_bookmark_thread(
+                                        &session,
+                                        &channel_id,
+                                        &thread_ts,
+                                        day,
+                                    )
+                                    .await
+                                    {
+                                        error!("{e}");
+                                    }
+                                    *solutions_thread.lock().unwrap() =
+                                        Some((channel_id, thread_ts));
+                                }
+
+                                // React to completions announcements
+                                if let Event::PrivateLeaderboardNewEntries(highlights) = &event {
+                                    for reaction in completion_reactions(highlights) {
+                                        let request = SlackApiReactionsAddRequest::new(
+                                            res.channel.clone(),
+                                            SlackReactionName(reaction.to_string()),
+                                            res.ts.clone(),
+                                        );
+                                        if let Err(e) = session.reactions_add(&request).await {
+                                            let error = BotError::Slack(e.to_string());
+                                            error!("{error}");
+                                        };
+                                    }
+                                }
                             }
                         }
                     }
                 }
+                .instrument(span)
+                .await;
+                if let Some((channel_id, ts, result)) = audited {
+                    AUDIT.handled(&channel_id.0, &ts.0, failure.unwrap_or(result));
+                }
             }
-        });
+            let held: usize = snoozes.values().map(|(_until, held)| held.len()).sum();
+            if held > 0 && settings.handoff_file.is_none() {
+                warn!("Stopped with {held} announcements held by snoozed channels.");
+            }
+
+            // Announcements held by snoozed channels are handed off rendered, the events
+            // themselves being specific to this process.
+            let mut handoff = Handoff::default();
+            // So are completions held by an aggregation window still open.
+            if let Some(highlights) = held_completions {
+                if settings.handoff_file.is_none() {
+                    warn!("Stopped with completions held by the aggregation window.");
+                }
+                let event = Event::PrivateLeaderboardNewEntries(highlights);
+                let channel_id = event
+                    .mutable_name()
+                    .and_then(routed_channel)
+                    .unwrap_or_else(|| {
+                        SlackChannelId(
+                            SETTINGS_STORE
+                                .get()
+                                .default_channel
+                                .unwrap_or_else(|| settings.slack_default_channel.to_string()),
+                        )
+                    });
+                let text = event.for_channel(&channel_id.0).to_string();
+                handoff.pending.push((channel_id.0, text));
+            }
+            for (channel_id, (until, held)) in snoozes {
+                for event in held {
+                    let text = event.for_channel(&channel_id.0).to_string();
+                    handoff.pending.push((channel_id.0.to_string(), text));
+                }
+                handoff.snoozes.insert(channel_id.0, until);
+            }
+            handoff.output_threads = output_threads
+                .into_iter()
+                .map(|(channel_id, ts)| (channel_id.0, ts.0))
+                .collect();
+            handoff
+        })
+    }
+
+    /// Problems with the Slack configuration, checked at startup: the token is valid, and the
+    /// configured channels exist with the bot as a member.
+    pub async fn check_configuration(&self) -> Vec<String> {
+        let settings = &config::SETTINGS;
+        let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+        let session = self.client.open_session(&token);
+        if let Err(e) = session.auth_test().await {
+            return vec![format!("SLACK_TOKEN is not a valid Slack token. {e}")];
+        }
+
+        let mut channels = vec![(
+            "SLACK_DEFAULT_CHANNEL",
+            settings.slack_default_channel.to_string(),
+        )];
+        let configured = [
+            (
+                "SLACK_MONITORING_CHANNEL",
+                settings.slack_monitoring_channel.iter().collect(),
+            ),
+            (
+                "SLACK_EVENT_ROUTES",
+                settings
+                    .slack_event_routes
+                    .iter()
+                    .flat_map(|r| r.values())
+                    .collect(),
+            ),
+            (
+                "SLACK_STANDINGS_CHANNELS",
+                settings.slack_standings_channels.iter().flatten().collect(),
+            ),
+            (
+                "REPLAY_CHANNEL",
+                settings.replay_channel.iter().collect::<Vec<&String>>(),
+            ),
+        ];
+        for (setting, channel_ids) in configured {
+            channels.extend(channel_ids.into_iter().map(|id| (setting, id.to_string())));
+        }
+        channels.sort();
+        channels.dedup();
+
+        let mut problems = vec![];
+        for (setting, channel_id) in channels {
+            let request = SlackApiConversationsInfoRequest::new(SlackChannelId(channel_id.clone()));
+            match session.conversations_info(&request).await {
+                Ok(response) if response.channel.flags.is_member == Some(false) => {
+                    problems.push(format!(
+                        "{setting}: the bot is not a member of channel {channel_id}, invite it to the channel."
+                    ))
+                }
+                Ok(_) => (),
+                Err(e) => problems.push(format!(
+                    "{setting}: channel {channel_id} cannot be found, or is private and the bot is not a member. {e}"
+                )),
+            }
+        }
+        problems
+    }
+
+    /// User id of the bot, to recognize its own actions.
+    async fn bot_user(&self) -> Option<SlackUserId> {
+        let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+        let session = self.client.open_session(&token);
+        match session.auth_test().await {
+            Ok(response) => Some(response.user_id),
+            Err(e) => {
+                let error = BotError::Slack(e.to_string());
+                error!("{error}");
+                None
+            }
+        }
     }
 
     async fn start_slack_client_with_socket_mode(
         &self,
         cache: MemoryCache,
-        tx: Sender<Event>,
+        tx: EventBus,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let settings = &config::SETTINGS;
         let socket_mode_callbacks = SlackSocketModeListenerCallbacks::new()
-            .with_push_events(push_events_socket_mode_function);
+            .with_push_events(push_events_socket_mode_function)
+            .with_interaction_events(interaction_events_function)
+            .with_command_events(command_events_function);
 
         let listener_environment = Arc::new(
             SlackClientEventsListenerEnvironment::new(self.client.clone())
@@ -133,6 +808,8 @@ impl AoCSlackClient {
                 .with_user_state(MyEnvironment {
                     sender: Arc::new(tx),
                     cache,
+                    solutions_thread: self.solutions_thread.clone(),
+                    bot_user: self.bot_user().await,
                 }),
         );
 
@@ -142,22 +819,1361 @@ impl AoCSlackClient {
             socket_mode_callbacks,
         );
 
-        let app_token_value: SlackApiTokenValue = settings.slack_app_token.to_string().into();
+        let app_token_value: SlackApiTokenValue = secrets::slack_app_token()
+            .ok_or(BotError::Config(
+                "An app token is required to use Socket Mode.".to_string(),
+            ))?
+            .into();
         let app_token: SlackApiToken = SlackApiToken::new(app_token_value);
 
         socket_mode_listener.listen_for(&app_token).await?;
+        HEALTH.set_slack_connected(true);
 
-        socket_mode_listener.serve().await;
+        SHUTDOWN.cancelled().await;
+        socket_mode_listener.shutdown().await;
+        HEALTH.set_slack_connected(false);
 
         Ok(())
     }
+
+    async fn start_slack_client_with_events_api(
+        &self,
+        cache: MemoryCache,
+        tx: EventBus,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let settings = &config::SETTINGS;
+
+        // Requests are verified against the signing secret of the app by the listener.
+        let signing_secret = secrets::slack_signing_secret().ok_or(BotError::Config(
+            "A signing secret is required to use the Events API.".to_string(),
+        ))?;
+        let push_events_config = Arc::new(SlackPushEventsListenerConfig::new(
+            signing_secret.to_string().into(),
+        ));
+        let interactions_config = Arc::new(SlackInteractionEventsListenerConfig::new(
+            signing_secret.to_string().into(),
+        ));
+        let commands_config = Arc::new(SlackCommandEventsListenerConfig::new(
+            signing_secret.to_string().into(),
+        ));
+
+        let listener_environment = Arc::new(
+            SlackClientEventsListenerEnvironment::new(self.client.clone())
+                .with_error_handler(error_handler)
+                .with_user_state(MyEnvironment {
+                    sender: Arc::new(tx),
+                    cache,
+                    solutions_thread: self.solutions_thread.clone(),
+                    bot_user: self.bot_user().await,
+                }),
+        );
+
+        let make_svc = make_service_fn(move |_| {
+            let push_events_config = push_events_config.clone();
+            let interactions_config = interactions_config.clone();
+            let commands_config = commands_config.clone();
+            let listener = SlackClientEventsHyperListener::new(listener_environment.clone());
+            async move {
+                let routes = chain_service_routes_fn(
+                    listener.push_events_service_fn(
+                        push_events_config,
+                        push_events_events_api_function,
+                    ),
+                    chain_service_routes_fn(
+                        listener.interaction_events_service_fn(
+                            interactions_config,
+                            interaction_events_function,
+                        ),
+                        chain_service_routes_fn(
+                            listener.command_events_service_fn(
+                                commands_config,
+                                command_events_function,
+                            ),
+                            not_found_route,
+                        ),
+                    ),
+                );
+                Ok::<_, Infallible>(service_fn(routes))
+            }
+        });
+
+        let addr = SocketAddr::from(([0, 0, 0, 0], settings.slack_events_api_port));
+        info!("Listening for Slack Events API requests on {addr}.");
+        let server = hyper::Server::bind(&addr)
+            .serve(make_svc)
+            .with_graceful_shutdown(SHUTDOWN.cancelled());
+        HEALTH.set_slack_connected(true);
+        let served = server.await;
+        HEALTH.set_slack_connected(false);
+        served?;
+
+        Ok(())
+    }
+}
+
+/// Next event to handle. Bursts of completions announcements waiting in the queue are coalesced
+/// into a single announcement, other events keeping their order.
+async fn next_event(rx: &mut Receiver<Event>, pending: &mut VecDeque<Event>) -> Option<Event> {
+    let event = match pending.pop_front() {
+        Some(event) => event,
+        None => tokio::select! {
+            biased;
+            event = rx.recv() => event?,
+            _ = SHUTDOWN.cancelled() => {
+                // No more events accepted, those already sent are still handled.
+                rx.close();
+                rx.recv().await?
+            }
+        },
+    };
+    match event {
+        Event::PrivateLeaderboardNewEntries(mut highlights) => {
+            while let Ok(next) = rx.try_recv() {
+                match next {
+                    Event::PrivateLeaderboardNewEntries(more) => highlights.extend(more),
+                    other => pending.push_back(other),
+                }
+            }
+            Some(Event::PrivateLeaderboardNewEntries(merge_highlights(
+                highlights,
+            )))
+        }
+        event => Some(event),
+    }
+}
+
+/// Wakes the channel up at the end of its snooze, unless snoozed again or woken up meanwhile.
+fn wake_up_at(sender: EventBus, channel_id: SlackChannelId, until: DateTime<Utc>) {
+    tokio::spawn(async move {
+        if let Ok(duration) = (until - Utc::now()).to_std() {
+            tokio::time::sleep(duration).await;
+        }
+        if let Err(e) = sender.send(Event::SnoozeEnded(channel_id, until)).await {
+            error!("{}", e);
+        };
+    });
+}
+
+/// Ends the aggregation window of completions after the given number of seconds.
+fn end_window_after(sender: EventBus, window_sec: u64) {
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(window_sec)).await;
+        if let Err(e) = sender.send(Event::CompletionsWindowEnded).await {
+            error!("{}", e);
+        };
+    });
+}
+
+/// End the snooze of a channel, the announcements held meanwhile being handled next.
+/// Held completions are merged in a single announcement.
+fn end_snooze(
+    snoozes: &mut HashMap<SlackChannelId, (DateTime<Utc>, Vec<Event>)>,
+    channel_id: &SlackChannelId,
+    pending: &mut VecDeque<Event>,
+) {
+    if let Some((_until, held)) = snoozes.remove(channel_id) {
+        info!(
+            "Snooze of channel {} ended, {} announcements held.",
+            channel_id.0,
+            held.len()
+        );
+        let mut highlights = vec![];
+        let mut events = vec![];
+        for event in held {
+            match event {
+                Event::PrivateLeaderboardNewEntries(more) => highlights.extend(more),
+                other => events.push(other),
+            }
+        }
+        if !highlights.is_empty() {
+            events.push(Event::PrivateLeaderboardNewEntries(merge_highlights(
+                highlights,
+            )));
+        }
+        for event in events.into_iter().rev() {
+            pending.push_front(event);
+        }
+    }
+}
+
+// How the bot receives events from Slack.
+enum SlackTransport {
+    SOCKET,
+    EVENTS,
+}
+
+impl SlackTransport {
+    fn from_string(s: &str) -> Option<Self> {
+        match s {
+            transport if transport == SLACK_TRANSPORTS[0] => Some(SlackTransport::SOCKET),
+            transport if transport == SLACK_TRANSPORTS[1] => Some(SlackTransport::EVENTS),
+            _ => None,
+        }
+    }
+}
+
+// Where command responses are posted in a channel.
+enum ReplyMode {
+    // In thread under the command message (default)
+    THREAD,
+    // In the channel itself
+    CHANNEL,
+    // In a dedicated bot output thread
+    OUTPUT,
+}
+
+impl ReplyMode {
+    fn from_string(s: &str) -> Option<Self> {
+        match s {
+            mode if mode == REPLY_MODES[0] => Some(ReplyMode::THREAD),
+            mode if mode == REPLY_MODES[1] => Some(ReplyMode::CHANNEL),
+            mode if mode == REPLY_MODES[2] => Some(ReplyMode::OUTPUT),
+            _ => None,
+        }
+    }
+
+    /// Reply mode configured for the channel. Default is thread.
+    fn for_channel(channel_id: &str) -> Self {
+        let settings = &config::SETTINGS;
+        settings
+            .slack_channels_reply_mode
+            .as_ref()
+            .and_then(|modes| modes.get(channel_id))
+            .and_then(|mode| ReplyMode::from_string(mode))
+            .unwrap_or(ReplyMode::THREAD)
+    }
+}
+
+/// Bot output thread of the channel, started on first use.
+async fn output_thread(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    output_threads: &mut HashMap<SlackChannelId, SlackTs>,
+    channel_id: &SlackChannelId,
+) -> Option<SlackTs> {
+    if let Some(ts) = output_threads.get(channel_id) {
+        return Some(ts.clone());
+    }
+    let request = SlackApiChatPostMessageRequest::new(
+        channel_id.clone(),
+        SlackMessageContent::new().with_text(OUTPUT_THREAD_MESSAGE.to_string()),
+    );
+    match session.chat_post_message(&request).await {
+        Ok(res) => {
+            output_threads.insert(channel_id.clone(), res.ts.clone());
+            Some(res.ts)
+        }
+        Err(e) => {
+            let error = BotError::Slack(e.to_string());
+            error!("{error}");
+            None
+        }
+    }
+}
+
+async fn push_events_events_api_function(
+    event: SlackPushEvent,
+    client: Arc<SlackHyperClient>,
+    states: SlackClientEventsUserState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Url verification requests are answered by the listener itself.
+    match event {
+        SlackPushEvent::EventCallback(event) => {
+            push_events_socket_mode_function(event, client, states).await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Message content of a command response. Long responses are paginated, with buttons to
+/// navigate between pages.
+fn command_content(
+    event: &Event,
+    cmd: &Command,
+    channel_id: &SlackChannelId,
+    page: usize,
+) -> SlackMessageContent {
+    let n_pages = RenderOptions::for_channel(&channel_id.0).n_pages(cmd.n_rows());
+    let text = event.for_channel(&channel_id.0).with_page(page).to_string();
+    let content = SlackMessageContent::new().with_text(text.clone());
+
+    match (n_pages > 1, cmd.to_input()) {
+        (true, Some(input)) => {
+            let mut buttons = vec![];
+            if page > 0 {
+                buttons.push(page_button(
+                    PAGE_PREVIOUS_ACTION,
+                    "◀ Prev",
+                    page - 1,
+                    &input,
+                ));
+            }
+            if page + 1 < n_pages {
+                buttons.push(page_button(PAGE_NEXT_ACTION, "Next ▶", page + 1, &input));
+            }
+            content.with_blocks(vec![
+                SlackSectionBlock::new()
+                    .with_text(SlackBlockMarkDownText::new(text).into())
+                    .into(),
+                SlackSectionBlock::new()
+                    .with_text(
+                        SlackBlockMarkDownText::new(format!("Page {}/{n_pages}", page + 1)).into(),
+                    )
+                    .into(),
+                SlackActionsBlock::new(buttons).into(),
+            ])
+        }
+        _ => content,
+    }
+}
+
+/// Requests sending the text of a message longer than `SLACK_MESSAGE_LIMIT` in several parts,
+/// the last one being the original request. Messages with blocks are paginated instead.
+fn split_request(
+    mut request: SlackApiChatPostMessageRequest,
+) -> (
+    Vec<SlackApiChatPostMessageRequest>,
+    SlackApiChatPostMessageRequest,
+) {
+    let text = match (&request.content.text, &request.content.blocks) {
+        (Some(text), None) if text.len() > SLACK_MESSAGE_LIMIT => text,
+        _ => return (vec![], request),
+    };
+    let mut chunks = split_message(text, SLACK_MESSAGE_LIMIT);
+    request.content.text = chunks.pop();
+    let leading = chunks
+        .into_iter()
+        .map(|chunk| {
+            SlackApiChatPostMessageRequest::new(
+                request.channel.clone(),
+                SlackMessageContent::new().with_text(chunk),
+            )
+            .opt_thread_ts(request.thread_ts.clone())
+        })
+        .collect();
+    (leading, request)
+}
+
+/// Text split in chunks of at most `limit` bytes on line boundaries, code blocks cut by a split
+/// being closed at the end of a chunk and opened again at the start of the next one.
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    // Room kept for the fences closing and opening code blocks
+    let budget = limit - 2 * CODE_FENCE.len();
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    let mut in_code_block = false;
+    for line in text.split('\n').flat_map(|line| split_line(line, budget)) {
+        let fresh = chunk.is_empty() || chunk == CODE_FENCE;
+        if !fresh && chunk.len() + 1 + line.len() > budget {
+            if in_code_block {
+                chunk.push_str(CODE_FENCE);
+            }
+            chunks.push(std::mem::take(&mut chunk));
+            if in_code_block {
+                chunk.push_str(CODE_FENCE);
+            }
+        } else if !fresh {
+            chunk.push('\n');
+        }
+        chunk.push_str(line);
+        in_code_block ^= line.matches(CODE_FENCE).count() % 2 == 1;
+    }
+    chunks.push(chunk);
+    chunks
+}
+
+// Pieces of at most `limit` bytes of a line, cut on character boundaries.
+fn split_line(line: &str, limit: usize) -> Vec<&str> {
+    let mut pieces = vec![];
+    let mut rest = line;
+    while rest.len() > limit {
+        let mut end = limit;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces.push(rest);
+    pieces
+}
+
+// The button value holds the page and the command input, so pages can be rebuilt on click.
+fn page_button(action: &str, label: &str, page: usize, input: &str) -> SlackActionBlockElement {
+    SlackActionBlockElement::Button(
+        SlackBlockButtonElement::new(action.into(), SlackBlockPlainTextOnly::from(label))
+            .with_value(format!("{page}{PAGE_VALUE_SEPARATOR}{input}")),
+    )
+}
+
+async fn interaction_events_function(
+    event: SlackInteractionEvent,
+    client: Arc<SlackHyperClient>,
+    states: SlackClientEventsUserState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match event {
+        SlackInteractionEvent::BlockActions(block_actions) => match &block_actions.container {
+            SlackInteractionActionContainer::View(_) => {
+                handle_home_action(block_actions, &client, states).await
+            }
+            _ => handle_page_request(block_actions, states).await,
+        },
+        SlackInteractionEvent::ViewSubmission(submission) => {
+            save_config_submission(submission, &client).await;
+        }
+        _ => (),
+    };
+    Ok(())
+}
+
+async fn handle_page_request(
+    block_actions: SlackInteractionBlockActionsEvent,
+    states: SlackClientEventsUserState,
+) {
+    let message = match block_actions.container {
+        SlackInteractionActionContainer::Message(container) => container
+            .channel_id
+            .map(|channel_id| (channel_id, container.message_ts)),
+        _ => None,
+    };
+
+    // Page requested by the clicked pagination button, if any.
+    let requested_page = block_actions
+        .actions
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|action| {
+            [PAGE_PREVIOUS_ACTION, PAGE_NEXT_ACTION].contains(&action.action_id.0.as_str())
+        })
+        .find_map(|action| {
+            action.value.and_then(|value| {
+                value
+                    .split_once(PAGE_VALUE_SEPARATOR)
+                    .and_then(|(page, input)| {
+                        page.parse::<usize>().ok().map(|p| (p, input.to_string()))
+                    })
+            })
+        });
+
+    let caller = block_actions.user.map(|user| user.id.0);
+
+    if let (Some((channel_id, message_ts)), Some((page, input))) = (message, requested_page) {
+        let states = states.read().await;
+        if let Some(env) = states.get_user_state::<MyEnvironment>() {
+            let cmd = env
+                .cache
+                .read(move |data| Command::build_from(input, data, caller.as_deref()))
+                .await;
+            if let Some(cmd) = cmd {
+                if let Err(e) = env
+                    .sender
+                    .send(Event::CommandPageRequested(
+                        channel_id, message_ts, cmd, page,
+                    ))
+                    .await
+                {
+                    error!("{}", e);
+                };
+            }
+        }
+    }
+}
+
+/// App Home tab of a user, with personal stats, today's completion, the top 10 and quick actions.
+async fn home_view(env: &MyEnvironment, user_id: &SlackUserId) -> SlackView {
+    let (year, day) = current_year_day();
+    let name = identities::member_name(&user_id.0);
+    let member = name.clone();
+    let text = env
+        .cache
+        .read(move |data| {
+            let name = member;
+            let board =
+                standings_board_iter(&Scoring::LOCAL, &data.leaderboard, year).collect::<Vec<_>>();
+            let standing = name
+                .as_ref()
+                .and_then(|name| member_standing(board.iter().copied(), name));
+            // Number of parts of the day completed by the user
+            let parts = name.as_ref().map(|name| {
+                data.leaderboard
+                    .iter()
+                    .filter(|e| e.year == year && e.day == day && *e.id.name == *name)
+                    .count()
+            });
+            let in_advent = now().month() == 12 && day <= 25;
+            let timestamp = data.timestamp.with_timezone(&Local);
+
+            MessageTemplate::AppHome
+                .render(context! {
+                    year => year,
+                    name => name,
+                    position => standing.map(|s| s.0),
+                    stars => standing.map(|s| s.1),
+                    score => standing.map(|s| s.2),
+                    day => in_advent.then_some(day),
+                    parts => parts,
+                    board => display::board(
+                        board
+                            .iter()
+                            .take(10)
+                            .map(|(id, days, score)| (*id, **days, *score))
+                            .collect()
+                    ),
+                    timestamp => format!("{}", timestamp.format("%d/%m/%Y %H:%M:%S")),
+                })
+                .unwrap_or_else(|e| e.to_string())
+        })
+        .await;
+
+    let mut buttons = vec![SlackActionBlockElement::Button(
+        SlackBlockButtonElement::new(
+            HOME_REFRESH_ACTION.into(),
+            SlackBlockPlainTextOnly::from("🔄 Refresh"),
+        ),
+    )];
+    // Nudges are only available to members with a known identity.
+    if name.is_some() {
+        let subscribed = SETTINGS_STORE.get().nudge_subscribers.contains(&user_id.0);
+        let label = match subscribed {
+            true => "🔕 Stop nudges",
+            false => "🔔 Nudge me",
+        };
+        if let Some(input) = Command::Nudge(!subscribed).to_input() {
+            buttons.push(SlackActionBlockElement::Button(
+                SlackBlockButtonElement::new(
+                    HOME_NUDGE_ACTION.into(),
+                    SlackBlockPlainTextOnly::from(label),
+                )
+                .with_value(input),
+            ));
+        }
+    }
+
+    SlackView::Home(SlackHomeView::new(vec![
+        SlackSectionBlock::new()
+            .with_text(SlackBlockMarkDownText::new(text).into())
+            .into(),
+        SlackActionsBlock::new(buttons).into(),
+    ]))
+}
+
+async fn publish_home(
+    client: &SlackHyperClient,
+    env: &MyEnvironment,
+    user_id: &SlackUserId,
+) -> BotResult<()> {
+    let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+    let session = client.open_session(&token);
+    let view = home_view(env, user_id).await;
+    let request = SlackApiViewsPublishRequest::new(user_id.clone(), view);
+    session
+        .views_publish(&request)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    Ok(())
+}
+
+// Quick actions of the App Home tab. The tab is published again once the action is done.
+async fn handle_home_action(
+    block_actions: SlackInteractionBlockActionsEvent,
+    client: &SlackHyperClient,
+    states: SlackClientEventsUserState,
+) {
+    let states = states.read().await;
+    let user_id = block_actions.user.map(|user| user.id);
+    if let (Some(user_id), Some(env)) = (user_id, states.get_user_state::<MyEnvironment>()) {
+        for action in block_actions.actions.unwrap_or_default() {
+            match action.action_id.0.as_str() {
+                HOME_NUDGE_ACTION => {
+                    if let Some(input) = action.value {
+                        let caller = user_id.0.clone();
+                        let cmd = env
+                            .cache
+                            .read(move |data| Command::build_from(input, data, Some(&caller)))
+                            .await;
+                        if let Some(cmd) = cmd {
+                            cmd.apply(Some(&user_id.0));
+                        }
+                    }
+                }
+                HOME_REFRESH_ACTION => (),
+                _ => continue,
+            }
+            if let Err(e) = publish_home(client, env, &user_id).await {
+                error!("{e}");
+            }
+        }
+    }
+}
+
+async fn unfurl_puzzle_links(
+    client: &SlackHyperClient,
+    env: &MyEnvironment,
+    link_shared: &SlackLinkSharedEvent,
+) -> BotResult<()> {
+    let aoc_client = configured_source();
+    let options = RenderOptions::for_channel(&link_shared.channel.0);
+    let mut unfurls = HashMap::new();
+
+    for link in link_shared.links.iter() {
+        let url = link.url.to_string();
+        if let Some((year, day)) = AoC::puzzle_from_url(&url) {
+            let title = aoc_client.fetch_puzzle_title(year, day).await?;
+            let (solved, fastest) = env
+                .cache
+                .read(move |data| {
+                    let solved = data
+                        .leaderboard
+                        .iter()
+                        .filter(|e| e.year == year && e.day == day && e.part == ProblemPart::SECOND)
+                        .count();
+                    let fastest = data
+                        .leaderboard
+                        .statistics_for_year_day(year, day)
+                        .ok()
+                        .and_then(|stats| stats.p2_fast)
+                        .map(format_duration);
+                    (solved, fastest)
+                })
+                .await;
+            let text = MessageTemplate::PuzzleUnfurl
+                .render_with(
+                    &options,
+                    context! {
+                        title => title,
+                        year => year,
+                        day => day,
+                        solved => solved,
+                        fastest => fastest,
+                    },
+                )
+                .map_err(|e| BotError::Compute(e.to_string()))?;
+            unfurls.insert(
+                url,
+                SlackApiChatUnfurlMapItemV2::new(vec![SlackSectionBlock::new()
+                    .with_text(SlackBlockMarkDownText::new(text).into())
+                    .into()]),
+            );
+        }
+    }
+    if unfurls.is_empty() {
+        return Ok(());
+    }
+
+    let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+    let session = client.open_session(&token);
+    // Links are unfurled where they were shared: in a message, or in the message composer.
+    let source = match link_shared.source.as_str() {
+        "composer" => SlackApiChatUnfurlSource::Composer,
+        _ => SlackApiChatUnfurlSource::ConversationsHistory,
+    };
+    let request = SlackApiChatUnfurlRequestV2::new(source, link_shared.unfurl_id.clone(), unfurls);
+    session
+        .chat_unfurl_v2(&request)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    Ok(())
+}
+
+async fn schedule_countdown(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    event: &Event,
+    channel_id: &SlackChannelId,
+    year: i32,
+    day: u8,
+    minutes: u32,
+) -> BotResult<()> {
+    let post_at = Entry::puzzle_unlock(year, day)? - Duration::minutes(minutes.into());
+    if post_at <= now() {
+        return Ok(());
+    }
+    let text = event.for_channel(&channel_id.0).to_string();
+    let request = SlackApiChatScheduleMessageRequest::new(
+        channel_id.clone(),
+        SlackMessageContent::new().with_text(text),
+        SlackDateTime(post_at),
+    );
+    session
+        .chat_schedule_message(&request)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    info!("Countdown for day {day} scheduled at {post_at}.");
+    Ok(())
+}
+
+/// Edit the pinned standings message of the channel, posting and pinning it first if needed.
+async fn update_standings(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    event: &Event,
+    channel_id: &SlackChannelId,
+) -> BotResult<()> {
+    let text = event.for_channel(&channel_id.0).to_string();
+    // A single message is pinned and edited, showing the first rows for large leaderboards.
+    let text = split_message(&text, SLACK_MESSAGE_LIMIT)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let stored = SETTINGS_STORE.get();
+
+    if let Some(ts) = stored.standings_messages.get(&channel_id.0) {
+        let request = SlackApiChatUpdateRequest::new(
+            channel_id.clone(),
+            SlackMessageContent::new().with_text(text.clone()),
+            SlackTs(ts.to_string()),
+        );
+        match session.chat_update(&request).await {
+            Ok(_) => return Ok(()),
+            // The message may have been deleted, a new one is posted.
+            Err(e) => error!("Could not update standings message. {e}"),
+        }
+    }
+
+    let request = SlackApiChatPostMessageRequest::new(
+        channel_id.clone(),
+        SlackMessageContent::new().with_text(text),
+    );
+    let res = session
+        .chat_post_message(&request)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    pin_message(session, channel_id, &res.ts).await?;
+
+    SETTINGS_STORE.update(|stored| {
+        stored
+            .standings_messages
+            .insert(channel_id.0.to_string(), res.ts.0)
+    });
+    Ok(())
+}
+
+async fn pin_message(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> BotResult<()> {
+    let request = SlackApiPinsRequest {
+        channel: channel_id.clone(),
+        timestamp: ts.clone(),
+    };
+    let _: serde_json::Value = session
+        .http_session_api
+        .http_post("pins.add", &request, None)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    Ok(())
+}
+
+/// Pin the daily solutions thread, and add it to the channel bookmarks.
+async fn pin_and_bookmark_thread(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+    day: u8,
+) -> BotResult<()> {
+    pin_message(session, channel_id, ts).await?;
+
+    let permalink = session
+        .chat_get_permalink(&SlackApiChatGetPermalinkRequest::new(
+            channel_id.clone(),
+            ts.clone(),
+        ))
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?
+        .permalink;
+    let bookmark_request = SlackApiBookmarksAddRequest {
+        channel_id: channel_id.clone(),
+        title: format!("Day {day} solutions"),
+        bookmark_type: "link".to_string(),
+        link: permalink.to_string(),
+    };
+    let _: serde_json::Value = session
+        .http_session_api
+        .http_post("bookmarks.add", &bookmark_request, None)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    Ok(())
+}
+
+async fn unpin_message(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> BotResult<()> {
+    let request = SlackApiPinsRequest {
+        channel: channel_id.clone(),
+        timestamp: ts.clone(),
+    };
+    let _: serde_json::Value = session
+        .http_session_api
+        .http_post("pins.remove", &request, None)
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?;
+    Ok(())
+}
+
+/// Keep a message which could not be sent in the dead letter queue, and notify the monitoring
+/// channel so it can be replayed with `!replay`.
+async fn dead_letter(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    request: &SlackApiChatPostMessageRequest,
+    error: &BotError,
+) {
+    let settings = &config::SETTINGS;
+    let letter = DeadLetter {
+        channel_id: request.channel.0.to_string(),
+        thread_ts: request.thread_ts.as_ref().map(|ts| ts.0.to_string()),
+        text: request.content.text.clone().unwrap_or_default(),
+        error: error.to_string(),
+    };
+    let n_letters = DEAD_LETTERS.update(|letters| {
+        letters.push(letter);
+        letters.len()
+    });
+
+    let monitoring_channel = SETTINGS_STORE
+        .get()
+        .monitoring_channel
+        .or_else(|| settings.slack_monitoring_channel.clone());
+    if let Some(monitoring_channel) = monitoring_channel {
+        let text = format!(
+            "⚠️ A message to <#{}> could not be sent ({error}). {n_letters} message(s) waiting, \
+            use `!replay` to send them again.",
+            request.channel.0
+        );
+        let notification = SlackApiChatPostMessageRequest::new(
+            SlackChannelId(monitoring_channel),
+            SlackMessageContent::new().with_text(text),
+        );
+        if let Err(e) = session.chat_post_message(&notification).await {
+            let error = BotError::Slack(e.to_string());
+            error!("{error}");
+        }
+    }
+}
+
+/// Send again the messages of the dead letter queue. Messages failing again stay in the queue.
+async fn replay_dead_letters(session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>) {
+    let letters = DEAD_LETTERS.get();
+    let n_replayed = letters.len();
+    info!("Replaying {n_replayed} dead letters.");
+
+    let mut remaining = vec![];
+    for letter in letters {
+        let request = SlackApiChatPostMessageRequest::new(
+            SlackChannelId(letter.channel_id.to_string()),
+            SlackMessageContent::new().with_text(letter.text.to_string()),
+        )
+        .opt_thread_ts(letter.thread_ts.as_ref().map(|ts| SlackTs(ts.to_string())));
+        if let Err(e) = session.chat_post_message(&request).await {
+            let error = BotError::Slack(e.to_string());
+            error!("{error}");
+            remaining.push(DeadLetter {
+                error: error.to_string(),
+                ..letter
+            });
+        }
+    }
+
+    // Letters added during the replay are kept after the ones failing again.
+    DEAD_LETTERS.update(|letters| {
+        let added = letters.split_off(n_replayed.min(letters.len()));
+        *letters = remaining;
+        letters.extend(added);
+    });
+}
+
+/// Channel configured in the routing table for the announcement, if any.
+fn routed_channel(announcement: &str) -> Option<SlackChannelId> {
+    let settings = &config::SETTINGS;
+    replay_channel().or_else(|| {
+        settings
+            .slack_event_routes
+            .as_ref()
+            .and_then(|routes| routes.get(announcement))
+            .map(|channel_id| SlackChannelId(channel_id.to_string()))
+    })
+}
+
+/// Channel all the announcements go to while replaying a season, if configured.
+fn replay_channel() -> Option<SlackChannelId> {
+    let settings = &config::SETTINGS;
+    settings
+        .replay_year
+        .and(settings.replay_channel.as_ref())
+        .map(|channel_id| SlackChannelId(channel_id.to_string()))
+}
+
+fn is_ephemeral_command(keyword: &str) -> bool {
+    let settings = &config::SETTINGS;
+    settings
+        .slack_ephemeral_commands
+        .as_ref()
+        .is_some_and(|commands| commands.iter().any(|c| c == keyword))
+}
+
+/// Admins are the users listed in the configuration, or the members of the admin user group.
+async fn is_admin(
+    session: &SlackClientSession<'_, SlackClientHyperHttpsConnector>,
+    user_id: &SlackUserId,
+) -> bool {
+    let settings = &config::SETTINGS;
+    if settings
+        .slack_admin_ids
+        .as_ref()
+        .is_some_and(|ids| ids.contains(&user_id.0))
+    {
+        return true;
+    }
+    match &settings.slack_admin_group {
+        Some(group) => {
+            let request = SlackApiUserGroupsUsersListRequest::new(SlackUserGroupId(group.clone()));
+            match session.usergroups_users_list(&request).await {
+                Ok(response) => response.users.contains(user_id),
+                Err(e) => {
+                    let error = BotError::Slack(e.to_string());
+                    error!("{error}");
+                    false
+                }
+            }
+        }
+        None => false,
+    }
+}
+
+async fn command_events_function(
+    event: SlackCommandEvent,
+    client: Arc<SlackHyperClient>,
+    _states: SlackClientEventsUserState,
+) -> Result<SlackCommandEventResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+    let session = client.open_session(&token);
+    let text = match event.text.as_deref().map(str::trim) {
+        Some(CONFIG_SUBCOMMAND) if is_admin(&session, &event.user_id).await => {
+            let request = SlackApiViewsOpenRequest::new(
+                event.trigger_id.clone(),
+                config_modal(&SETTINGS_STORE.get()),
+            );
+            match session.views_open(&request).await {
+                Ok(_) => "Opening configuration.",
+                Err(e) => {
+                    let error = BotError::Slack(e.to_string());
+                    error!("{error}");
+                    "Could not open configuration."
+                }
+            }
+        }
+        Some(CONFIG_SUBCOMMAND) => ADMIN_ONLY_MESSAGE,
+        _ => "Usage: `/aoc config`",
+    };
+    Ok(SlackCommandEventResponse::new(
+        SlackMessageContent::new().with_text(text.to_string()),
+    ))
+}
+
+/// Configuration modal, prefilled with the current settings.
+fn config_modal(stored: &StoredSettings) -> SlackView {
+    let settings = &config::SETTINGS;
+
+    let default_channel = stored
+        .default_channel
+        .clone()
+        .unwrap_or_else(|| settings.slack_default_channel.to_string());
+    let default_channel_select =
+        SlackBlockConversationsSelectElement::new(CONFIG_DEFAULT_CHANNEL.into())
+            .with_initial_conversation(SlackConversationId(default_channel));
+
+    let mut monitoring_channel_select =
+        SlackBlockConversationsSelectElement::new(CONFIG_MONITORING_CHANNEL.into());
+    if let Some(channel) = stored
+        .monitoring_channel
+        .clone()
+        .or_else(|| settings.slack_monitoring_channel.clone())
+    {
+        monitoring_channel_select =
+            monitoring_channel_select.with_initial_conversation(SlackConversationId(channel));
+    }
+
+    // Years of AoC events, the current one being the default.
+    let year_option = |year: Option<i32>| {
+        let (label, value) = match year {
+            Some(year) => (year.to_string(), year.to_string()),
+            None => ("Current year".to_string(), CURRENT_YEAR_VALUE.to_string()),
+        };
+        SlackBlockChoiceItem::new(SlackBlockPlainTextOnly::from(label), value)
+    };
+    let year_options = std::iter::once(None)
+        .chain((FIRST_AOC_YEAR..=current_year_day().0).rev().map(Some))
+        .map(year_option)
+        .collect::<Vec<_>>();
+    let year_select = SlackBlockStaticSelectElement::new(CONFIG_DEFAULT_YEAR.into())
+        .with_options(year_options)
+        .with_initial_option(year_option(stored.default_year));
+
+    let event_option = |name: &str| {
+        SlackBlockChoiceItem::new(SlackBlockPlainTextOnly::from(name), name.to_string())
+    };
+    let mut muted_select = SlackBlockMultiStaticSelectElement::new(CONFIG_MUTED_EVENTS.into())
+        .with_options(
+            MUTABLE_EVENTS
+                .iter()
+                .map(|name| event_option(name))
+                .collect(),
+        );
+    if !stored.muted_events.is_empty() {
+        muted_select = muted_select.with_initial_options(
+            stored
+                .muted_events
+                .iter()
+                .map(|name| event_option(name))
+                .collect(),
+        );
+    }
+
+    let blocks = vec![
+        SlackInputBlock::new(
+            "Default channel".into(),
+            SlackInputBlockElement::ConversationsSelect(default_channel_select),
+        )
+        .with_block_id(CONFIG_DEFAULT_CHANNEL.into())
+        .into(),
+        SlackInputBlock::new(
+            "Monitoring channel".into(),
+            SlackInputBlockElement::ConversationsSelect(monitoring_channel_select),
+        )
+        .with_block_id(CONFIG_MONITORING_CHANNEL.into())
+        .with_optional(true)
+        .into(),
+        SlackInputBlock::new(
+            "Default year".into(),
+            SlackInputBlockElement::StaticSelect(year_select),
+        )
+        .with_block_id(CONFIG_DEFAULT_YEAR.into())
+        .into(),
+        SlackInputBlock::new(
+            "Muted events".into(),
+            SlackInputBlockElement::MultiStaticSelect(muted_select),
+        )
+        .with_block_id(CONFIG_MUTED_EVENTS.into())
+        .with_optional(true)
+        .into(),
+    ];
+
+    SlackView::Modal(
+        SlackModalView::new("AoC configuration".into(), blocks)
+            .with_callback_id(CONFIG_CALLBACK_ID.into())
+            .with_submit("Save".into())
+            .with_close("Cancel".into()),
+    )
+}
+
+/// Persist the settings submitted through the configuration modal.
+async fn save_config_submission(
+    submission: SlackInteractionViewSubmissionEvent,
+    client: &SlackHyperClient,
+) {
+    let is_config_modal = match &submission.view.view {
+        SlackView::Modal(modal) => modal
+            .callback_id
+            .as_ref()
+            .map_or(false, |id| id.0 == CONFIG_CALLBACK_ID),
+        _ => false,
+    };
+    if !is_config_modal {
+        return;
+    }
+    let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+    let session = client.open_session(&token);
+    if !is_admin(&session, &submission.user.id).await {
+        return;
+    }
+
+    let values = submission
+        .view
+        .state_params
+        .state
+        .map(|state| state.values)
+        .unwrap_or_default();
+    // Block and action ids are the same for all inputs of the modal.
+    let value = |id: &str| {
+        values
+            .get(&SlackBlockId(id.to_string()))
+            .and_then(|actions| actions.get(&SlackActionId(id.to_string())))
+    };
+
+    SETTINGS_STORE.update(|stored| {
+        stored.default_channel = value(CONFIG_DEFAULT_CHANNEL)
+            .and_then(|v| v.selected_conversation.as_ref())
+            .map(|channel| channel.0.to_string());
+        stored.monitoring_channel = value(CONFIG_MONITORING_CHANNEL)
+            .and_then(|v| v.selected_conversation.as_ref())
+            .map(|channel| channel.0.to_string());
+        stored.default_year = value(CONFIG_DEFAULT_YEAR)
+            .and_then(|v| v.selected_option.as_ref())
+            .and_then(|option| option.value.parse::<i32>().ok());
+        stored.muted_events = value(CONFIG_MUTED_EVENTS)
+            .and_then(|v| v.selected_options.as_ref())
+            .map(|options| options.iter().map(|o| o.value.to_string()).collect())
+            .unwrap_or_default();
+    });
+    info!("Configuration updated by {}.", submission.user.id);
+}
+
+async fn not_found_route(
+    _req: Request<Body>,
+) -> Result<Response<Body>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Body::empty())?)
+}
+
+/// Reactions to a completions announcement: a star for earned stars, a glowing star when both
+/// parts of a day were completed, and a trophy for daily firsts.
+fn completion_reactions(highlights: &Vec<DayHighlight>) -> Vec<&'static str> {
+    [
+        (REACTION_STAR, highlights.iter().any(|h| h.n_stars > 0)),
+        (
+            REACTION_BOTH_STARS,
+            highlights.iter().any(|h| h.n_stars > 1),
+        ),
+        (REACTION_FIRST, highlights.iter().any(|h| h.first)),
+    ]
+    .into_iter()
+    .filter_map(|(reaction, condition)| condition.then_some(reaction))
+    .collect()
+}
+
+/// Name of the Slack user, as displayed in their profile.
+async fn slack_user_names(
+    client: &SlackHyperClient,
+    user_id: &SlackUserId,
+) -> BotResult<Vec<String>> {
+    let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+    let session = client.open_session(&token);
+    let user = session
+        .users_info(&SlackApiUsersInfoRequest::new(user_id.clone()))
+        .await
+        .map_err(|e| BotError::Slack(e.to_string()))?
+        .user;
+    Ok(user
+        .profile
+        .map(|profile| {
+            [profile.display_name, profile.real_name]
+                .into_iter()
+                .flatten()
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// React to messages posted in the daily solutions thread by members who completed the day.
+/// Count a kudos reaction added to (or removed from) a solution post, unless added by its author.
+fn count_kudos(
+    reaction: &SlackReactionName,
+    item: &SlackReactionsItem,
+    user_id: &SlackUserId,
+    added: bool,
+) {
+    let ts = match item {
+        SlackReactionsItem::Message(message) => &message.origin.ts,
+        _ => return,
+    };
+    if !KUDOS_REACTIONS.contains(&reaction.0.as_str()) {
+        return;
+    }
+    KUDOS.update(|posts| match posts.get_mut(&ts.0) {
+        Some(post) if post.user != user_id.0 => {
+            post.kudos = match added {
+                true => post.kudos + 1,
+                false => post.kudos.saturating_sub(1),
+            };
+        }
+        _ => (),
+    });
+}
+
+async fn react_to_solution(
+    client: &SlackHyperClient,
+    env: &MyEnvironment,
+    channel_id: &SlackChannelId,
+    thread_ts: &SlackTs,
+    ts: &SlackTs,
+    user_id: &SlackUserId,
+) -> BotResult<()> {
+    let in_solutions_thread = env
+        .solutions_thread
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|(channel, thread)| channel == channel_id && thread == thread_ts);
+    if !in_solutions_thread {
+        return Ok(());
+    }
+
+    // Members are matched on their registered identity, or else on their Slack profile name.
+    let names = match identities::member_name(&user_id.0) {
+        Some(name) => vec![name],
+        None => slack_user_names(client, user_id).await?,
+    };
+    let (year, day) = current_year_day();
+    let (member, finished) = env
+        .cache
+        .read(move |data| {
+            let member = names.into_iter().find(|name| {
+                data.leaderboard
+                    .iter()
+                    .any(|e| e.year == year && *e.id.name == *name)
+            });
+            let finished = data.leaderboard.iter().any(|e| {
+                e.year == year
+                    && e.day == day
+                    && e.part == ProblemPart::SECOND
+                    && member.as_deref() == Some(&*e.id.name)
+            });
+            (member, finished)
+        })
+        .await;
+
+    // Solution posts of members are recorded, to count the kudos they receive.
+    if let Some(member) = member {
+        KUDOS.update(|posts| {
+            posts.entry(ts.0.to_string()).or_insert(SolutionPost {
+                member,
+                user: user_id.0.to_string(),
+                year,
+                day,
+                kudos: 0,
+            });
+        });
+    }
+
+    if finished {
+        let token: SlackApiToken = SlackApiToken::new(secrets::slack_token().into());
+        let session = client.open_session(&token);
+        let request = SlackApiReactionsAddRequest::new(
+            channel_id.clone(),
+            SlackReactionName(REACTION_STAR.to_string()),
+            ts.clone(),
+        );
+        session
+            .reactions_add(&request)
+            .await
+            .map_err(|e| BotError::Slack(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Build the command from a valid command input, and send it to be answered.
+#[instrument(
+    name = "command",
+    skip(states, channel_id, thread_ts, user_id),
+    fields(channel = %channel_id.0, user = user_id.as_ref().map(|u| u.0.as_str()))
+)]
+async fn send_command(
+    states: &SlackClientEventsUserState,
+    input: String,
+    channel_id: SlackChannelId,
+    thread_ts: SlackTs,
+    user_id: Option<SlackUserId>,
+) {
+    let states = states.read().await;
+    let state: Option<&MyEnvironment> = states.get_user_state::<MyEnvironment>();
+    if let Some(env) = state {
+        AUDIT.received(AuditEntry {
+            channel_id: channel_id.0.to_string(),
+            ts: thread_ts.0.to_string(),
+            user_id: user_id.as_ref().map(|u| u.0.to_string()),
+            input: input.to_string(),
+            received: Utc::now().timestamp_millis(),
+            result: None,
+            latency_ms: None,
+        });
+        let caller = user_id.as_ref().map(|u| u.0.to_string());
+        // Safe unwrap as we already know the input starts with a keyword
+        let cmd = env
+            .cache
+            .read(move |data| Command::build_from(input, data, caller.as_deref()).unwrap())
+            .await;
+
+        if let Err(e) = env
+            .sender
+            .send(Event::CommandReceived(channel_id, thread_ts, cmd, user_id))
+            .await
+        {
+            error!("{}", e);
+        };
+    };
 }
 
 async fn push_events_socket_mode_function(
     event: SlackPushEventCallback,
-    _client: Arc<SlackHyperClient>,
+    client: Arc<SlackHyperClient>,
     states: SlackClientEventsUserState,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // The App Home tab is refreshed each time it is opened.
+    if let SlackEventCallbackBody::AppHomeOpened(home) = &event.event {
+        if home.tab == APP_HOME_TAB {
+            let states = states.read().await;
+            if let Some(env) = states.get_user_state::<MyEnvironment>() {
+                if let Err(e) = publish_home(&client, env, &home.user).await {
+                    error!("{e}");
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Kudos reactions to solution posts are counted, except the ones added by the bot.
+    let kudos = match &event.event {
+        SlackEventCallbackBody::ReactionAdded(reaction) => {
+            Some((&reaction.reaction, &reaction.item, &reaction.user, true))
+        }
+        SlackEventCallbackBody::ReactionRemoved(reaction) => {
+            Some((&reaction.reaction, &reaction.item, &reaction.user, false))
+        }
+        _ => None,
+    };
+    if let Some((reaction, item, user_id, added)) = kudos {
+        let states = states.read().await;
+        if let Some(env) = states.get_user_state::<MyEnvironment>() {
+            if env.bot_user.as_ref() != Some(user_id) {
+                count_kudos(reaction, item, user_id, added);
+            }
+        }
+        return Ok(());
+    }
+
+    // Links to AoC puzzles are unfurled with the private leaderboard context.
+    if let SlackEventCallbackBody::LinkShared(link_shared) = &event.event {
+        let states = states.read().await;
+        if let Some(env) = states.get_user_state::<MyEnvironment>() {
+            if let Err(e) = unfurl_puzzle_links(&client, env, link_shared).await {
+                error!("{e}");
+            }
+        }
+        return Ok(());
+    }
+
+    // Mentions of the bot ("@elf board 2023") go through the same pipeline as commands.
+    if let SlackEventCallbackBody::AppMention(mention) = event.event {
+        if let Some(input) = mention
+            .content
+            .text
+            .as_deref()
+            .and_then(Command::from_mention)
+        {
+            send_command(
+                &states,
+                input,
+                mention.channel,
+                mention.origin.ts,
+                Some(mention.user),
+            )
+            .await;
+        }
+        return Ok(());
+    }
+
     if let SlackEventCallbackBody::Message(message) = event.event {
         // Only respond to messages from users (no bot_id) or allowed bots
         if message
@@ -179,30 +2195,27 @@ async fn push_events_socket_mode_function(
             // message from user, we will handle it if there is content and channel_id
             if let (Some(content), Some(channel_id)) = (message.content, message.origin.channel) {
                 if let Some(t) = content.text {
-                    if Command::is_command(&t) {
+                    if Command::is_command_like(&t) {
+                        let thread_ts = message.origin.ts; // to respond in thread
+                        send_command(&states, t, channel_id, thread_ts, message.sender.user).await;
+                    } else if let (Some(thread_ts), Some(user_id)) =
+                        (&message.origin.thread_ts, &message.sender.user)
+                    {
                         let states = states.read().await;
-                        let state: Option<&MyEnvironment> =
-                            states.get_user_state::<MyEnvironment>();
-                        if let Some(env) = state {
-                            let cache = env.cache.clone();
-                            let sender = env.sender.clone();
-
-                            let cmd = {
-                                let data = cache.data.lock().unwrap();
-                                // Safe unwrap as we already know it is a valid command
-                                Command::build_from(t, &data).unwrap()
-                            };
-
-                            let thread_ts = message.origin.ts; // to respond in thread
-
-                            if let Err(e) = sender
-                                .send(Event::CommandReceived(channel_id, thread_ts, cmd))
-                                .await
+                        if let Some(env) = states.get_user_state::<MyEnvironment>() {
+                            if let Err(e) = react_to_solution(
+                                &client,
+                                env,
+                                &channel_id,
+                                thread_ts,
+                                &message.origin.ts,
+                                user_id,
+                            )
+                            .await
                             {
-                                error!("{}", e);
-                            };
-                            // }
-                        };
+                                error!("{e}");
+                            }
+                        }
                     };
                 };
             };
@@ -1,4 +1,5 @@
 use crate::cli::Cli;
+use chrono_tz::Tz;
 use clap::Parser;
 use figment::{
     providers::{Env, Format, Serialized, Yaml},
@@ -6,10 +7,49 @@ use figment::{
 };
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 use tracing::Level;
 
 const TRACE_LEVELS: [&'static str; 5] = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+const LOG_FORMATS: [&'static str; 2] = ["text", "json"];
+const ANONYMOUS_POLICIES: [&'static str; 3] = ["show", "hide", "aggregate"];
+const SECRET_PROVIDERS: [&'static str; 2] = ["vault", "aws"];
+const PUZZLE_EVENTS: [&'static str; 2] = ["aoc", "scheduled"];
+
+#[derive(Debug, Clone, Copy)]
+pub enum LogFormat {
+    TEXT,
+    JSON,
+}
+
+// How anonymous members are presented in announcements.
+#[derive(Debug, Clone, Copy)]
+pub enum AnonymousPolicy {
+    // As "anonymous user #id", like other members
+    SHOW,
+    // Left out of announcements
+    HIDE,
+    // Counted together, e.g. "3 anonymous members"
+    AGGREGATE,
+}
+
+// Secret manager the secrets are loaded from, instead of the settings.
+#[derive(Debug, Clone, Copy)]
+pub enum SecretProvider {
+    // HashiCorp Vault, KV secrets engine
+    VAULT,
+    // AWS Secrets Manager
+    AWS,
+}
+
+// Puzzle event followed by the bot.
+#[derive(Debug, Clone, Copy)]
+pub enum PuzzleEventKind {
+    // Advent of Code
+    AOC,
+    // Another competition with a puzzle a day, released as configured by the `event_*` settings
+    SCHEDULED,
+}
 
 pub static SETTINGS: Lazy<Settings> = Lazy::new(|| Settings::new());
 
@@ -21,55 +61,319 @@ pub static SETTINGS: Lazy<Settings> = Lazy::new(|| Settings::new());
 pub struct Settings {
     #[serde(default = "default_trace_level")]
     trace_level: String,
+    // Format of the logs: "text" (default) or "json", one object per line for log aggregation.
+    #[serde(default = "default_log_format")]
+    log_format: String,
+    // How anonymous members are announced: "show" (default), "hide" or "aggregate".
+    #[serde(default = "default_anonymous_members")]
+    anonymous_members: String,
+    // Whether AoC going down and coming back is announced, once per outage.
+    #[serde(default = "default_announce_aoc_outages")]
+    pub announce_aoc_outages: bool,
+    // Whether members leaving the private leaderboard are announced (scores are recomputed anyway).
+    #[serde(default = "default_announce_members_left")]
+    pub announce_members_left: bool,
+    // AoC ids of the members not counted in group milestones, e.g. inactive or test accounts.
+    pub excluded_members: Option<Vec<u64>>,
+    // AoC ids of the members not named in announcements, who are announced like anonymous members.
+    pub quiet_members: Option<Vec<u64>>,
+    // Named groups of members by AoC ids, e.g. {"juniors": [123456, 234567]}, whose standings
+    // are shown by commands with "--group <name>".
+    pub member_groups: Option<HashMap<String, Vec<u64>>>,
+    // Timezone of the members (e.g. "Europe/Paris"), for the hours they usually solve puzzles at.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    // Timezones of the members living elsewhere, by AoC id, e.g. {"123456": "America/New_York"}
+    pub member_timezones: Option<HashMap<String, String>>,
+    // Thresholds of the completions announced, the others being shown by commands only.
+    // Completions of the members in the top N positions of the local standings.
+    pub significant_top_positions: Option<usize>,
+    // Completions of both parts with at most this many minutes between them.
+    pub significant_max_delta_min: Option<i64>,
+    // Completions within this many hours of the release of the puzzle.
+    pub significant_max_hours: Option<i64>,
+    // Total stars of the season collected by the private leaderboard which are announced.
+    #[serde(default = "default_star_milestones")]
+    pub star_milestones: Vec<usize>,
+    // Runs of the simulation of the remaining days giving the chances to win of the members in
+    // the weekly digest.
+    #[serde(default = "default_simulation_runs")]
+    pub simulation_runs: usize,
+    // Puzzle event followed by the bot: "aoc" (default) or "scheduled", for other competitions.
+    #[serde(default = "default_puzzle_event")]
+    puzzle_event: String,
+    // Name of the scheduled event, e.g. "Everybody Codes".
+    #[serde(default = "default_event_name")]
+    pub event_name: String,
+    // Number of puzzles of the scheduled event (at most 25), and of parts of each puzzle (1 or 2).
+    #[serde(default = "default_event_days")]
+    pub event_days: u8,
+    #[serde(default = "default_event_parts")]
+    pub event_parts: u8,
+    // Month, day of the month and hour the first puzzle of the scheduled event is released at each
+    // year, in `event_timezone`, the next ones being released every day at the same hour.
+    #[serde(default = "default_event_month")]
+    pub event_month: u32,
+    #[serde(default = "default_event_first_day")]
+    pub event_first_day: u32,
+    #[serde(default)]
+    pub event_unlock_hour: u32,
+    #[serde(default = "default_timezone")]
+    pub event_timezone: String,
+    // Link to the puzzles of the scheduled event, "{year}" and "{day}" being replaced.
+    pub event_puzzle_url: Option<String>,
+    // Private leaderboard of the scheduled event in the AoC JSON format, "{year}" being replaced.
+    // Fetched instead of the AoC private leaderboard.
+    pub event_leaderboard_url: Option<String>,
+    // GitHub API the repositories registered with `!github` are watched through.
+    #[serde(default = "default_github_api_url")]
+    pub github_api_url: String,
+    // Token of the GitHub API, raising its rate limit. May be loaded from the secret manager.
+    pub github_token: Option<String>,
+    // Pattern of the commit messages or file paths sharing the solution of a day, "{day}" being
+    // replaced by the day, e.g. "day05/main.rs" or "Solve day 5".
+    #[serde(default = "default_github_day_pattern")]
+    pub github_day_pattern: String,
+    // Secrets may be loaded from a secret manager instead, see `secrets_provider`.
+    #[serde(default)]
     pub slack_token: String,
-    pub slack_app_token: String,
+    // App-level token, required by Socket Mode
+    pub slack_app_token: Option<String>,
+    // How to receive events from Slack: "socket_mode" (default) or "events_api"
+    #[serde(default = "default_slack_transport")]
+    pub slack_transport: String,
+    // Signing secret used to verify Events API requests
+    pub slack_signing_secret: Option<String>,
+    #[serde(default = "default_slack_events_api_port")]
+    pub slack_events_api_port: u16,
+    // OTLP (gRPC) endpoint traces are exported to, e.g. "http://localhost:4317". Disabled if not set.
+    pub otlp_endpoint: Option<String>,
+    // Sentry DSN errors and panics are reported to, disabled if not set.
+    pub sentry_dsn: Option<String>,
+    // Number of events waiting to be handled before producers are slowed down.
+    #[serde(default = "default_event_bus_capacity")]
+    pub event_bus_capacity: usize,
+    // What producers do when the event bus is full: "block" (wait) or "drop" (drop non-critical
+    // announcements, such as global leaderboard updates, while other events wait).
+    #[serde(default = "default_event_bus_overflow")]
+    pub event_bus_overflow: String,
+    // Port serving the `/healthz` and `/readyz` endpoints, disabled if not set.
+    pub health_port: Option<u16>,
+    // Port serving the read-only standings API under `/api`, disabled if not set.
+    pub api_port: Option<u16>,
+    // Bearer token required by the admin endpoints of the API, which are disabled if not set.
+    pub api_admin_token: Option<String>,
     pub slack_default_channel: String,
     // Channel to reveive heartbeats and monitoring events
     pub slack_monitoring_channel: Option<String>,
+    // Whether the topic of the default channel shows the current leader of the private leaderboard.
+    #[serde(default = "default_slack_leader_topic")]
+    pub slack_leader_topic: bool,
+    // Announcement name => channel id the announcement is posted to, instead of the default channel.
+    pub slack_event_routes: Option<HashMap<String, String>>,
+    // Channels holding a pinned standings message, edited after each private leaderboard update.
+    // Defaults to the monitoring channel.
+    pub slack_standings_channels: Option<Vec<String>>,
     // Will not treat any message from bots users, unless specifically authorized.
     // If using matterbridge you might want to leverage this option.
     pub slack_bots_authorized_ids: Option<Vec<String>>,
+    // Urls announcements are posted to as JSON (name, payload and text), e.g. for custom integrations.
+    pub notifier_webhooks: Option<Vec<String>>,
+    // Discord channel webhook url announcements are posted to as well.
+    pub discord_webhook: Option<String>,
+    // Whether announcements are printed to stdout as well.
+    #[serde(default = "default_console_announcements")]
+    pub console_announcements: bool,
+    // Slack user ids allowed to edit the bot configuration with `/aoc config` and to run admin commands.
+    pub slack_admin_ids: Option<Vec<String>>,
+    // Slack user group id whose members are admins too.
+    pub slack_admin_group: Option<String>,
+    // AoC member name => Slack user id, so announcements can mention members.
+    pub slack_identities: Option<HashMap<String, String>>,
+    // Minutes before each puzzle unlock at which a countdown is posted.
+    #[serde(default = "default_countdown_minutes")]
+    pub countdown_minutes: Vec<u32>,
+    // Hour (UTC) at which subscribed members are nudged if they did not complete the current day.
+    #[serde(default = "default_nudge_hour")]
+    pub nudge_hour: u32,
+    // Puzzles solved within this window after their release, in minutes, are counted by `!early`.
+    #[serde(default = "default_early_window_min")]
+    pub early_window_min: u32,
+    // File persisting the settings edited at runtime by admins.
+    #[serde(default = "default_settings_store_file")]
+    pub settings_store_file: String,
+    // File persisting the Slack messages which could not be sent, to be replayed with `!replay`.
+    #[serde(default = "default_dead_letters_file")]
+    pub dead_letters_file: String,
+    // File persisting the solution posts of the daily threads and the kudos they received.
+    #[serde(default = "default_kudos_file")]
+    pub kudos_file: String,
+    // File persisting the audit log of the commands received.
+    #[serde(default = "default_audit_file")]
+    pub audit_file: String,
+    // File persisting the appearances of the private members on the global leaderboard.
+    #[serde(default = "default_heroes_file")]
+    pub heroes_file: String,
+    // File persisting the difficulty votes of the members and the global times of each day.
+    #[serde(default = "default_difficulty_file")]
+    pub difficulty_file: String,
+    // Weight of the votes of the members in the difficulty index of a day (0 to 1), the rest
+    // being given by the time the global leaderboard took to fill.
+    #[serde(default = "default_difficulty_vote_weight")]
+    pub difficulty_vote_weight: f64,
     #[serde(default = "default_global_leaderboard_polling_interval_sec")]
     pub global_leaderboard_polling_interval_sec: u64,
+    // Completions announced within this window are combined in one announcement, 0 to disable.
+    #[serde(default = "default_completions_window_sec")]
+    pub completions_window_sec: u64,
     #[serde(default = "default_aoc_base_url")]
     pub aoc_base_url: String,
     #[serde(default = "default_aoc_api_timeout_sec")]
     pub aoc_api_timeout_sec: u64,
+    // Time given to the messaging engine to send the pending messages when shutting down.
+    #[serde(default = "default_shutdown_timeout_sec")]
+    pub shutdown_timeout_sec: u64,
+    // File the runtime state is handed off through on restarts: written when shutting down,
+    // resumed from (then removed) at startup.
+    pub handoff_file: Option<String>,
+    // Maximum number of requests sent concurrently to AoC, to stay polite with the server.
+    #[serde(default = "default_aoc_max_concurrent_requests")]
+    pub aoc_max_concurrent_requests: usize,
     pub aoc_private_leaderboard_id: u64,
+    // Name of the private leaderboard in commands comparing leaderboards, e.g. "!versus".
+    #[serde(default = "default_aoc_private_leaderboard_name")]
+    pub aoc_private_leaderboard_name: String,
+    // Other private leaderboards of the organization by name, e.g. {"paris": 123456}, compared
+    // with commands. The member of the session cookie must be a member of each.
+    pub aoc_other_leaderboards: Option<HashMap<String, u64>>,
+    #[serde(default)]
     pub aoc_session_cookie: String,
+    // Secret manager the AoC session cookie and Slack tokens are loaded from: "vault" or "aws".
+    // Secrets are read from the settings when not set.
+    secrets_provider: Option<String>,
+    // How often secrets are fetched again from the secret manager, in minutes.
+    #[serde(default = "default_secrets_refresh_min")]
+    pub secrets_refresh_min: u64,
+    // Vault server, e.g. "https://vault.example.com:8200", token, and path of the secret
+    // (e.g. "secret/data/ceo" for a KV v2 engine mounted at "secret").
+    pub vault_addr: Option<String>,
+    pub vault_token: Option<String>,
+    pub vault_secret_path: Option<String>,
+    // Name or ARN of the AWS Secrets Manager secret, credentials and region being taken from
+    // the AWS environment.
+    pub aws_secret_id: Option<String>,
+    // Directory of AoC data files read instead of the AoC website, e.g. for demos or testing.
+    pub aoc_fixtures_dir: Option<String>,
     // Whether to load the private leaderboard for all the previous AOC events
     #[serde(default = "default_all_years")]
     pub all_years: bool,
+    // Past season replayed at accelerated speed instead of following the current one, e.g. for
+    // demos or testing outside December.
+    pub replay_year: Option<i32>,
+    // Duration of a day of the replayed season, in seconds.
+    #[serde(default = "default_replay_day_sec")]
+    pub replay_day_sec: u64,
+    // Channel the replayed season is announced to, instead of the default and routed channels.
+    pub replay_channel: Option<String>,
+    // Directory containing message templates overriding the built-in ones (e.g. help.txt).
+    // Templates are reloaded from this directory when the process receives a SIGHUP.
+    pub templates_dir: Option<String>,
+    // Default language of the bot messages ("en" or "fr").
+    #[serde(default = "default_language")]
+    pub language: String,
+    // Language of the bot messages per channel id, overriding the default language.
+    pub slack_channels_language: Option<HashMap<String, String>>,
+    // Template set used per channel id, the template sets being defined in the templates directory.
+    pub slack_channels_template_set: Option<HashMap<String, String>>,
+    // Verbosity of the bot messages per channel id ("compact" or "verbose"). Default is verbose.
+    pub slack_channels_verbosity: Option<HashMap<String, String>>,
+    // Where command responses are posted per channel id ("thread", "channel" or "output_thread").
+    // Default is in thread under the command message.
+    pub slack_channels_reply_mode: Option<HashMap<String, String>>,
+    // Commands answered with messages only visible to the requester (e.g. ["!me", "!help"]).
+    pub slack_ephemeral_commands: Option<Vec<String>>,
+    // Number of rows per page of long command outputs, navigated with buttons. No pagination if unset.
+    pub slack_page_size: Option<usize>,
+    // Emoji theme of the bot messages ("classic", "minimal" or "extra-festive").
+    #[serde(default = "default_theme")]
+    pub theme: String,
 }
 
 impl Settings {
     pub fn new() -> Self {
         let local_settings_yaml_file = ".env.local.yaml";
-        let settings: Settings = match Path::new(local_settings_yaml_file).exists() {
-            true => {
-                println!(
-                    "\n######################################\n\
+        let settings: Result<Settings, figment::Error> =
+            match Path::new(local_settings_yaml_file).exists() {
+                true => {
+                    println!(
+                        "\n######################################\n\
                        ##   Found '.env.local.yaml' file,  ##\n\
                        ##   loading local configuration.   ##\n\
                        ######################################\n\
                     "
-                );
-                Figment::new()
-                    .merge(Yaml::file(local_settings_yaml_file))
-                    .merge(Env::raw())
-                    .merge(Serialized::defaults(Cli::parse()))
-                    .extract()
-                    .unwrap()
-            }
-            false => Figment::new().merge(Env::raw()).extract().unwrap(),
-        };
-
-        settings
+                    );
+                    Figment::new()
+                        .merge(Yaml::file(local_settings_yaml_file))
+                        .merge(Env::raw())
+                        .merge(Serialized::defaults(Cli::parse()))
+                        .extract()
+                }
+                false => Figment::new().merge(Env::raw()).extract(),
+            };
+
+        // Names the setting at fault, e.g. a leaderboard id which is not a number.
+        settings.unwrap_or_else(|e| {
+            eprintln!("Invalid configuration. {e}");
+            std::process::exit(1)
+        })
     }
 
     pub fn get_trace_level(&self) -> Level {
         get_trace_level(&self.trace_level)
     }
+
+    pub fn get_log_format(&self) -> LogFormat {
+        match self.log_format.as_str() {
+            format if format == LOG_FORMATS[1] => LogFormat::JSON,
+            // Default log format
+            _ => LogFormat::TEXT,
+        }
+    }
+
+    pub fn get_anonymous_policy(&self) -> AnonymousPolicy {
+        match self.anonymous_members.as_str() {
+            policy if policy == ANONYMOUS_POLICIES[1] => AnonymousPolicy::HIDE,
+            policy if policy == ANONYMOUS_POLICIES[2] => AnonymousPolicy::AGGREGATE,
+            // Default policy
+            _ => AnonymousPolicy::SHOW,
+        }
+    }
+
+    /// Timezone of a member, the default one if not set or unknown.
+    pub fn get_member_timezone(&self, id: u64) -> Tz {
+        self.member_timezones
+            .as_ref()
+            .and_then(|timezones| timezones.get(&id.to_string()))
+            .unwrap_or(&self.timezone)
+            .parse()
+            .unwrap_or(Tz::UTC)
+    }
+
+    pub fn get_puzzle_event(&self) -> PuzzleEventKind {
+        match self.puzzle_event.as_str() {
+            event if event == PUZZLE_EVENTS[1] => PuzzleEventKind::SCHEDULED,
+            // Default event
+            _ => PuzzleEventKind::AOC,
+        }
+    }
+
+    pub fn get_secret_provider(&self) -> Option<SecretProvider> {
+        match self.secrets_provider.as_deref() {
+            Some(provider) if provider == SECRET_PROVIDERS[0] => Some(SecretProvider::VAULT),
+            Some(provider) if provider == SECRET_PROVIDERS[1] => Some(SecretProvider::AWS),
+            _ => None,
+        }
+    }
 }
 
 fn get_trace_level(level_str: &str) -> Level {
@@ -88,18 +392,175 @@ fn default_trace_level() -> String {
     "INFO".to_string()
 }
 
+fn default_log_format() -> String {
+    LOG_FORMATS[0].to_string()
+}
+
+fn default_anonymous_members() -> String {
+    ANONYMOUS_POLICIES[0].to_string()
+}
+
+fn default_announce_aoc_outages() -> bool {
+    false
+}
+
+fn default_announce_members_left() -> bool {
+    true
+}
+
 fn default_global_leaderboard_polling_interval_sec() -> u64 {
     300
 }
 
+fn default_completions_window_sec() -> u64 {
+    0
+}
+
+fn default_star_milestones() -> Vec<usize> {
+    vec![100, 250, 500]
+}
+
+fn default_simulation_runs() -> usize {
+    5000
+}
+
 fn default_aoc_api_timeout_sec() -> u64 {
     5
 }
 
+fn default_shutdown_timeout_sec() -> u64 {
+    10
+}
+
+fn default_aoc_max_concurrent_requests() -> usize {
+    4
+}
+
 fn default_aoc_base_url() -> String {
     "https://adventofcode.com".to_string()
 }
 
+fn default_puzzle_event() -> String {
+    PUZZLE_EVENTS[0].to_string()
+}
+
+fn default_event_name() -> String {
+    "Puzzle event".to_string()
+}
+
+fn default_event_days() -> u8 {
+    25
+}
+
+fn default_event_parts() -> u8 {
+    2
+}
+
+fn default_event_month() -> u32 {
+    12
+}
+
+fn default_event_first_day() -> u32 {
+    1
+}
+
+fn default_github_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+fn default_github_day_pattern() -> String {
+    r"(?i)\bday[ _-]?0?{day}\b".to_string()
+}
+
+fn default_aoc_private_leaderboard_name() -> String {
+    "main".to_string()
+}
+
+fn default_secrets_refresh_min() -> u64 {
+    60
+}
+
 fn default_all_years() -> bool {
     false
 }
+
+fn default_replay_day_sec() -> u64 {
+    60
+}
+
+fn default_language() -> String {
+    "en".to_string()
+}
+
+fn default_theme() -> String {
+    "classic".to_string()
+}
+
+fn default_slack_transport() -> String {
+    "socket_mode".to_string()
+}
+
+fn default_slack_events_api_port() -> u16 {
+    8080
+}
+
+fn default_settings_store_file() -> String {
+    "settings.json".to_string()
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_nudge_hour() -> u32 {
+    19
+}
+
+fn default_early_window_min() -> u32 {
+    60
+}
+
+fn default_dead_letters_file() -> String {
+    "dead_letters.json".to_string()
+}
+
+fn default_kudos_file() -> String {
+    "kudos.json".to_string()
+}
+
+fn default_audit_file() -> String {
+    "audit.json".to_string()
+}
+
+fn default_heroes_file() -> String {
+    "heroes.json".to_string()
+}
+
+fn default_difficulty_file() -> String {
+    "difficulty.json".to_string()
+}
+
+fn default_difficulty_vote_weight() -> f64 {
+    0.5
+}
+
+fn default_slack_leader_topic() -> bool {
+    true
+}
+
+fn default_event_bus_capacity() -> usize {
+    // Should be more than plenty to handle all the messages
+    64
+}
+
+fn default_event_bus_overflow() -> String {
+    "block".to_string()
+}
+
+fn default_console_announcements() -> bool {
+    false
+}
+
+fn default_countdown_minutes() -> Vec<u32> {
+    vec![10]
+}
@@ -1,6 +1,12 @@
 pub mod commands;
 pub mod display;
 pub mod events;
+pub mod i18n;
+pub mod identities;
 pub mod leaderboard;
+pub mod notifier;
+pub mod simulation;
+pub mod source;
 pub mod standings;
 pub mod templates;
+pub mod themes;
@@ -1,169 +1,683 @@
 use crate::{
+    config,
     core::{
-        display,
+        display::{self, GraphFormat},
+        identities,
         leaderboard::ScrapedLeaderboard,
-        standings::{standings_board, Jersey, Ranking, Scoring, Standing},
-        templates::invalid_year_day_message,
+        standings::{group_board, Jersey, Ranking, Scoring},
+        templates::{MessageTemplate, RenderOptions},
     },
-    utils::current_year_day,
+    storage::{AuditEntry, SETTINGS_STORE},
+    utils::{current_year_day, edit_distance, escape_slack},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+use minijinja::{context, value::Value};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::{collections::HashMap, iter::Iterator};
-
-const COMMANDS: [&'static str; 4] = ["!help", "!fast", "!board", "!tdf"];
-static REGEX_COMMANDS: Lazy<Regex> =
-    Lazy::new(|| {
-        let commands = COMMANDS.join(r"|^");
-        Regex::new(format!(
-            // <option> set at the end so all other matches have priority
-            r"(?<cmd>^{commands})|(?<year>\b\d{{4}}\b)|(?<day>\b\d{{1,2}}\b)|(?<option>\b[\S]+\b)"
-    ).as_str())
-    .unwrap()
-    });
+use std::{fmt, iter::Iterator};
+
+mod approve;
+mod audit;
+mod board;
+mod difficulty;
+mod early;
+mod fast;
+mod github;
+mod help;
+mod heroes;
+mod iam;
+mod me;
+mod nudge;
+mod quiet;
+mod replay;
+mod rivalry;
+mod snooze;
+mod tdf;
+mod unsnooze;
+mod versus;
+
+const COMMANDS: [&'static str; 19] = [
+    "!help",
+    "!fast",
+    "!board",
+    "!tdf",
+    "!rivalry",
+    "!iam",
+    "!me",
+    "!nudge",
+    "!replay",
+    "!snooze",
+    "!unsnooze",
+    "!audit",
+    "!heroes",
+    "!quiet",
+    "!approve",
+    "!versus",
+    "!difficulty",
+    "!early",
+    "!github",
+];
+// Scoring methods of the board, or all the private leaderboards merged (scored locally)
+const BOARD_OPTIONS: [&'static str; 3] = ["local", "stars", "combined"];
+const NUDGE_OPTIONS: [&'static str; 2] = ["on", "off"];
+const QUIET_OPTIONS: [&'static str; 2] = ["on", "off"];
+// Option restricting a command to a group of members, e.g. "--group juniors"
+const GROUP_OPTION: &'static str = "--group";
+// Option ordering the members tied in the standings, e.g. "--by time"
+const TIEBREAK_OPTION: &'static str = "--by";
+const TIEBREAK_OPTIONS: [&'static str; 1] = ["time"];
+// Slack user mentions, e.g. "<@U0123>" or "<@U0123|elf>"
+static REGEX_MENTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^<@([A-Z0-9]+)(\|[^>]*)?>$").unwrap());
+static REGEX_MENTIONS: Lazy<Regex> = Lazy::new(|| Regex::new(r"<@[^>]+>").unwrap());
+// Keywords looking like a command, known or not, e.g. "!standings"
+static REGEX_KEYWORD: Lazy<Regex> = Lazy::new(|| Regex::new(r"^![a-zA-Z]+$").unwrap());
+// Typos up to this number of characters get a suggestion of the closest command
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+// Commands answered by the bot, new commands being added to this list.
+static REGISTRY: Lazy<Vec<Box<dyn BotCommand>>> = Lazy::new(|| {
+    vec![
+        Box::new(help::Help),
+        Box::new(fast::Fast),
+        Box::new(board::Board),
+        Box::new(tdf::Tdf),
+        Box::new(rivalry::Rivalry),
+        Box::new(iam::Iam),
+        Box::new(me::Me),
+        Box::new(nudge::Nudge),
+        Box::new(replay::Replay),
+        Box::new(snooze::Snooze),
+        Box::new(unsnooze::Unsnooze),
+        Box::new(audit::Audit),
+        Box::new(heroes::Heroes),
+        Box::new(quiet::Quiet),
+        Box::new(approve::Approve),
+        Box::new(versus::Versus),
+        Box::new(difficulty::Difficulty),
+        Box::new(early::Early),
+        Box::new(github::GitHub),
+    ]
+});
+
+/// Command of the bot, registered in the command registry.
+pub trait BotCommand: Send + Sync {
+    /// Keyword of the command, e.g. `!board`.
+    fn name(&self) -> &'static str;
+
+    /// Other keywords the command answers to.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Usage of the command, e.g. `!board [local|stars] [year]`.
+    fn help(&self) -> &'static str;
+
+    /// Arguments accepted by the command, none by default.
+    fn signature(&self) -> Signature {
+        Signature::default()
+    }
+
+    /// Commands reserved to admins.
+    fn requires_admin(&self) -> bool {
+        false
+    }
+
+    /// Builds the command from its input, without doing what it asks yet: commands are also
+    /// built again for the pages of their answer. Invalid inputs give `Command::NotValid`.
+    fn parse(&self, input: &CommandInput) -> Command;
+
+    /// Does what the command built by `parse` asks (e.g. registering an identity), once the
+    /// caller is allowed to. Only received commands are applied, nothing to do by default.
+    fn apply(&self, cmd: Command, _caller: Option<&str>) -> Command {
+        cmd
+    }
+
+    /// Answer to the command, built by `parse`.
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String;
+}
+
+/// Option a command accepts.
+#[derive(Debug, Clone, Copy)]
+pub enum OptionKind {
+    // One of the values
+    OneOf(&'static [&'static str]),
+    // Any single word, described for error messages (e.g. "a duration like `2h`")
+    Word(&'static str),
+    // The rest of the input, spaces included
+    Text,
+}
+
+/// Arguments a command accepts, given in any order: a year (4 digits), a day (1 or 2 digits)
+/// and an option. Commands accepting a group are restricted to its members with `--group`,
+/// and commands accepting a tiebreak order tied members by total solve time with `--by time`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Signature {
+    pub option: Option<OptionKind>,
+    pub day: bool,
+    pub year: bool,
+    pub group: bool,
+    pub tiebreak: bool,
+}
+
+/// Argument of a command not matching its signature, explained to the user.
+#[derive(Debug)]
+pub struct ArgumentError {
+    // What the argument could have been
+    expected: Vec<String>,
+    got: String,
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.expected.is_empty() {
+            true => write!(f, "Unexpected argument '{}'.", escape_slack(&self.got)),
+            false => write!(
+                f,
+                "Expected {}, got '{}'.",
+                self.expected.join(" or "),
+                escape_slack(&self.got)
+            ),
+        }
+    }
+}
+
+/// Input of a command, with its arguments parsed according to the command signature.
+pub struct CommandInput<'a> {
+    option: Option<&'a str>,
+    day: Option<u8>,
+    year: Option<i32>,
+    pub leaderboard: &'a ScrapedLeaderboard,
+    // Slack user id of the member sending the command, if known
+    pub caller: Option<&'a str>,
+    // AoC ids of the members of the group the command is restricted to, if any
+    group: Option<&'static [u64]>,
+    // Whether tied members are ordered by their total solve time
+    by_time: bool,
+}
+
+impl<'a> CommandInput<'a> {
+    /// Parses the arguments following the keyword of the input.
+    pub fn parse(
+        input: &'a str,
+        signature: &Signature,
+        leaderboard: &'a ScrapedLeaderboard,
+        caller: Option<&'a str>,
+    ) -> Result<CommandInput<'a>, ArgumentError> {
+        let mut parsed = CommandInput {
+            option: None,
+            day: None,
+            year: None,
+            leaderboard,
+            caller,
+            group: None,
+            by_time: false,
+        };
+        let arguments = input
+            .trim()
+            .split_once(char::is_whitespace)
+            .map_or("", |(_keyword, arguments)| arguments.trim());
+        if arguments.is_empty() {
+            return Ok(parsed);
+        }
+        if let Some(OptionKind::Text) = signature.option {
+            parsed.option = Some(arguments);
+            return Ok(parsed);
+        }
+
+        for argument in arguments.split_whitespace() {
+            let is_number = argument.chars().all(|c| c.is_ascii_digit());
+            match argument.len() {
+                4 if is_number && signature.year && parsed.year.is_none() => {
+                    parsed.year = argument.parse().ok();
+                }
+                1 | 2 if is_number && signature.day && parsed.day.is_none() => {
+                    parsed.day = argument.parse().ok();
+                }
+                _ if !is_number && parsed.option.is_none() && accepts(signature, argument) => {
+                    parsed.option = Some(argument);
+                }
+                _ => return Err(parsed.unexpected(signature, argument)),
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn option(&self) -> Option<&'a str> {
+        self.option
+    }
+
+    /// Year given in the input, the default year otherwise.
+    pub fn year(&self) -> i32 {
+        self.year.unwrap_or_else(default_year)
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    pub fn group(&self) -> Option<&'static [u64]> {
+        self.group
+    }
+
+    pub fn by_time(&self) -> bool {
+        self.by_time
+    }
+
+    /// Row of the caller among the ranked members, if known and ranked.
+    pub fn caller_row<'b>(&self, mut names: impl Iterator<Item = &'b str>) -> Option<usize> {
+        let name = self.caller.and_then(identities::member_name)?;
+        names.position(|ranked| ranked == name)
+    }
+
+    /// Error for an argument matching none of the arguments still expected.
+    fn unexpected(&self, signature: &Signature, argument: &str) -> ArgumentError {
+        let mut expected = vec![];
+        match signature.option {
+            Some(OptionKind::OneOf(values)) if self.option.is_none() => expected.push(format!(
+                "one of {}",
+                values.iter().map(|value| format!("`{value}`")).join(", ")
+            )),
+            Some(OptionKind::Word(description)) if self.option.is_none() => {
+                expected.push(description.to_string())
+            }
+            _ => (),
+        }
+        if signature.day && self.day.is_none() {
+            expected.push("a day between 1 and 25".to_string());
+        }
+        if signature.year && self.year.is_none() {
+            expected.push(format!("a year between 2015 and {}", current_year_day().0));
+        }
+        ArgumentError {
+            expected,
+            got: argument.to_string(),
+        }
+    }
+}
+
+// Whether the argument is an option of the signature.
+fn accepts(signature: &Signature, argument: &str) -> bool {
+    match signature.option {
+        Some(OptionKind::OneOf(values)) => values.contains(&argument),
+        Some(OptionKind::Word(_)) => true,
+        _ => false,
+    }
+}
+
+/// Input without the named option (e.g. `--group`), and the value given to it, if any, either
+/// as the next word or after `=`.
+fn split_option(input: &str, option: &str) -> (String, Option<String>) {
+    let mut value = None;
+    let mut words = vec![];
+    let mut tokens = input.split_whitespace();
+    while let Some(token) = tokens.next() {
+        match token.strip_prefix(option) {
+            Some("") => value = Some(tokens.next().unwrap_or_default().to_string()),
+            Some(given) if given.starts_with('=') => value = Some(given[1..].to_string()),
+            _ => words.push(token),
+        }
+    }
+    (words.join(" "), value)
+}
+
+/// AoC ids of the members of the group, if configured.
+fn member_group(name: &str) -> Option<&'static [u64]> {
+    let settings = &config::SETTINGS;
+    settings
+        .member_groups
+        .as_ref()?
+        .get(name)
+        .map(|members| members.as_slice())
+}
+
+/// Answer to a keyword matching no command, suggesting the closest one for typos.
+fn unknown_command(keyword: &str) -> String {
+    let suggestion = REGISTRY
+        .iter()
+        .flat_map(|command| {
+            std::iter::once(command.name()).chain(command.aliases().iter().copied())
+        })
+        .map(|known| (edit_distance(keyword, known), known))
+        .filter(|(distance, _known)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min();
+    match suggestion {
+        Some((_distance, known)) => format!(
+            "Unknown command `{keyword}`, did you mean `{known}`? Commands are listed by `{}`.",
+            COMMANDS[0]
+        ),
+        None => format!(
+            "Unknown command `{keyword}`. Commands are listed by `{}`.",
+            COMMANDS[0]
+        ),
+    }
+}
+
+/// Command registered with the keyword, or one of its aliases.
+pub fn find(keyword: &str) -> Option<&'static dyn BotCommand> {
+    REGISTRY
+        .iter()
+        .find(|command| command.name() == keyword || command.aliases().contains(&keyword))
+        .map(|command| command.as_ref())
+}
 
 #[derive(Debug, Clone)]
 pub enum Command {
-    Help,
-    Ranking(i32, u8, Vec<(String, String)>, DateTime<Utc>, Ranking),
-    StandingTdf(i32, Option<u8>, String, DateTime<Utc>, Jersey),
-    LeaderboardDisplay(i32, String, DateTime<Utc>, Scoring),
+    // Usage of the command help was asked for, if any
+    Help(Option<String>),
+    // Rankings and standings end with the row of the caller, if ranked, added to compact outputs
+    Ranking(
+        i32,
+        u8,
+        Vec<(String, String)>,
+        DateTime<Utc>,
+        Ranking,
+        Option<usize>,
+    ),
+    StandingTdf(
+        i32,
+        Option<u8>,
+        String,
+        DateTime<Utc>,
+        Jersey,
+        Option<usize>,
+    ),
+    // year, board, time, scoring, whether all the private leaderboards are merged, and whether
+    // tied members are ordered by total solve time, and the row of the caller
+    LeaderboardDisplay(
+        i32,
+        String,
+        DateTime<Utc>,
+        Scoring,
+        bool,
+        bool,
+        Option<usize>,
+    ),
+    RivalryGraph(i32, String, DateTime<Utc>, GraphFormat),
+    // AoC member name and id the caller claimed to be, and the code to set as AoC name to verify it
+    Iam(String, u64, String),
+    // year, member name, (position, stars, score) if the member took part that year
+    // year, member, (position, stars, score), (best, median, worst) time to the first star,
+    // stars by hour of the day in the timezone of the member, time
+    Me(
+        i32,
+        String,
+        Option<(usize, usize, usize)>,
+        Option<(Duration, Duration, Duration)>,
+        [usize; 24],
+        DateTime<Utc>,
+    ),
+    // Whether the caller is now subscribed to evening nudges
+    Nudge(bool),
+    // Number of dead letters to send again
+    Replay(usize),
+    // Time until which announcements are snoozed in the channel of the command
+    Snooze(DateTime<Utc>),
+    Unsnooze,
+    // Latest commands received, newest first
+    Audit(Vec<AuditEntry>),
+    // year, (member, appearances, points, best rank) on the global leaderboard, most points first
+    Heroes(i32, Vec<(String, usize, usize, u16)>),
+    // Whether the caller is now left unnamed in announcements
+    Quiet(bool),
+    // (Slack user id, AoC member name) of the identity approved by an admin
+    Approve(String, String),
+    // Command restricted to the members of the named group
+    Grouped(String, Box<Command>),
+    // year, (leaderboard, members, average time for both stars, completion rate) of the two
+    // leaderboards compared, days both have completions, (member, leaderboards, score) over them
+    Versus(
+        i32,
+        Vec<(String, usize, Option<String>, String)>,
+        Vec<u8>,
+        Vec<(String, String, usize)>,
+    ),
+    // year, day, vote of the caller, and difficulty index of the day with the vote
+    Difficulty(i32, u8, u8, Option<f64>),
+    // year, window in minutes, (member, puzzles solved within the window after their release)
+    Early(i32, u32, Vec<(String, usize)>),
+    // GitHub repository the solutions of the caller are now linked from, if any
+    GitHub(Option<String>),
     NotValid(String),
 }
 
 impl Command {
-    pub fn parse_string(input: &str) -> HashMap<&str, &str> {
-        REGEX_COMMANDS
-            .captures_iter(input)
-            .flat_map(|caps| {
-                REGEX_COMMANDS
-                    .capture_names()
-                    .filter_map(|o| o.and_then(|n| Some((n, caps.name(n)?.as_str()))))
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            // if several matches for a capture type, we want the first iteration to prevail
-            .rev()
-            .collect()
+    /// Keyword of the command (e.g. `!help`), if valid.
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            Command::Help(..) => Some(COMMANDS[0]),
+            Command::Ranking(..) => Some(COMMANDS[1]),
+            Command::LeaderboardDisplay(..) => Some(COMMANDS[2]),
+            Command::StandingTdf(..) => Some(COMMANDS[3]),
+            Command::RivalryGraph(..) => Some(COMMANDS[4]),
+            Command::Iam(..) => Some(COMMANDS[5]),
+            Command::Me(..) => Some(COMMANDS[6]),
+            Command::Nudge(..) => Some(COMMANDS[7]),
+            Command::Replay(..) => Some(COMMANDS[8]),
+            Command::Snooze(..) => Some(COMMANDS[9]),
+            Command::Unsnooze => Some(COMMANDS[10]),
+            Command::Audit(..) => Some(COMMANDS[11]),
+            Command::Heroes(..) => Some(COMMANDS[12]),
+            Command::Quiet(..) => Some(COMMANDS[13]),
+            Command::Approve(..) => Some(COMMANDS[14]),
+            Command::Versus(..) => Some(COMMANDS[15]),
+            Command::Difficulty(..) => Some(COMMANDS[16]),
+            Command::Early(..) => Some(COMMANDS[17]),
+            Command::GitHub(..) => Some(COMMANDS[18]),
+            Command::Grouped(_, cmd) => cmd.keyword(),
+            Command::NotValid(..) => None,
+        }
     }
-    pub fn is_command(input: &str) -> bool {
-        Self::parse_string(input).get("cmd").is_some()
-    }
-
-    // Note that we call this command on matching command strings, so we know
-    // input string is a command. We might want to return Option<Command> later on.
-    pub fn build_from(input: String, leaderboard: &ScrapedLeaderboard) -> Option<Command> {
-        let parsed = Self::parse_string(&input);
-
-        match parsed.get("cmd") {
-            Some(cmd) if cmd == &COMMANDS[0] => Some(Command::Help),
-            Some(cmd) if cmd == &COMMANDS[1] => {
-                let ranking_str = parsed
-                    .get("option")
-                    .and_then(|o| Some(*o))
-                    .unwrap_or_else(|| Ranking::get_default_str());
-                let ranking = Ranking::from_string(ranking_str).unwrap_or(Ranking::DELTA);
-                let year = parsed
-                    .get("year")
-                    .and_then(|d| d.parse::<i32>().ok())
-                    .unwrap_or_else(|| current_year_day().0);
-                let day = parsed
-                    .get("day")
-                    .and_then(|d| d.parse::<u8>().ok())
-                    .unwrap_or_else(|| current_year_day().1);
-
-                if let Some(msg) = invalid_year_day_message(year, Some(day)) {
-                    Some(Command::NotValid(msg))
-                } else {
-                    let data = Standing::new(&leaderboard.leaderboard).by_time(&ranking, year, day);
-
-                    Some(Command::Ranking(
-                        year,
-                        day,
-                        data,
-                        leaderboard.timestamp,
-                        ranking,
-                    ))
-                }
-            }
-            Some(cmd) if cmd == &COMMANDS[2] => {
-                let scoring_str = parsed
-                    .get("option")
-                    .and_then(|o| Some(*o))
-                    .unwrap_or_else(|| &Scoring::get_default_str());
-                let scoring = Scoring::from_string(scoring_str).unwrap_or(Scoring::LOCAL);
-                let year = parsed
-                    .get("year")
-                    .and_then(|d| d.parse::<i32>().ok())
-                    .unwrap_or_else(|| current_year_day().0);
-
-                if let Some(msg) = invalid_year_day_message(year, None) {
-                    Some(Command::NotValid(msg))
-                } else {
-                    let data = standings_board(&scoring, &leaderboard.leaderboard, year);
-                    let formatted = display::board(data);
-                    Some(Command::LeaderboardDisplay(
-                        year,
-                        formatted,
-                        leaderboard.timestamp,
-                        scoring,
-                    ))
-                }
+
+    /// (file name, content) of the export generated by the command, uploaded as a file.
+    pub fn attachment(&self) -> Option<(String, String)> {
+        match self {
+            Command::RivalryGraph(year, graph, _, format) => Some((
+                format!("rivalries_{year}.{}", format.extension()),
+                graph.to_string(),
+            )),
+            Command::Me(year, _, _, _, hours, _) if hours.iter().any(|count| *count > 0) => Some((
+                format!("solve_hours_{year}.txt"),
+                display::hour_histogram(hours),
+            )),
+            Command::Grouped(_, cmd) => cmd.attachment(),
+            _ => None,
+        }
+    }
+
+    /// Commands reserved to admins.
+    pub fn requires_admin(&self) -> bool {
+        self.keyword()
+            .and_then(find)
+            .is_some_and(|command| command.requires_admin())
+    }
+
+    /// Does what the command asks, by the command it was built by. The caller is the Slack user id
+    /// of the member sending the command, if known.
+    pub fn apply(self, caller: Option<&str>) -> Command {
+        match self {
+            Command::Grouped(group, cmd) => Command::Grouped(group, Box::new(cmd.apply(caller))),
+            cmd => match cmd.keyword().and_then(find) {
+                Some(command) => command.apply(cmd, caller),
+                None => cmd,
+            },
+        }
+    }
+
+    /// Answer to the command, rendered by the command it was built by.
+    pub fn answer(&self, options: &RenderOptions) -> Option<String> {
+        match self {
+            Command::Grouped(group, cmd) => cmd.answer(options).map(|answer| {
+                MessageTemplate::Grouped
+                    .render_with(
+                        options,
+                        context! { group => group, answer => Value::from_safe_string(answer) },
+                    )
+                    .unwrap()
+            }),
+            _ => Some(find(self.keyword()?)?.execute(self, options)),
+        }
+    }
+
+    /// Number of rows of the command output, for pagination.
+    pub fn n_rows(&self) -> usize {
+        match self {
+            Command::Ranking(_, _, data, ..) => data.len(),
+            Command::LeaderboardDisplay(_, board, ..) => board.lines().count(),
+            Command::StandingTdf(_, _, standings, ..) => standings.lines().count(),
+            Command::Grouped(_, cmd) => cmd.n_rows(),
+            _ => 0,
+        }
+    }
+
+    /// Input string building the same command, for paginated commands and quick actions.
+    pub fn to_input(&self) -> Option<String> {
+        match self {
+            Command::Ranking(year, day, _, _, ranking, _) => {
+                Some(format!("{} {ranking} {day} {year}", COMMANDS[1]))
             }
-            Some(cmd) if cmd == &COMMANDS[3] => {
-                let jersey_str = parsed
-                    .get("option")
-                    .and_then(|o| Some(*o))
-                    .unwrap_or_else(|| &Jersey::get_default_str());
-                let jersey = Jersey::from_string(jersey_str).unwrap_or(Jersey::YELLOW);
-                let year = parsed
-                    .get("year")
-                    .and_then(|d| d.parse::<i32>().ok())
-                    .unwrap_or_else(|| current_year_day().0);
-                let day = parsed.get("day").and_then(|d| d.parse::<u8>().ok());
-
-                if let Some(msg) = invalid_year_day_message(year, day) {
-                    Some(Command::NotValid(msg))
-                } else {
-                    let formatted = match (&jersey, day) {
-                        // standing yearly, based on time
-                        (Jersey::YELLOW, None) => {
-                            let standings = Standing::new(&leaderboard.leaderboard);
-                            let data = standings.tdf_season(&jersey, year);
-                            display::tdf_time_yearly(&data)
-                        }
-                        // standing yearly, based on points
-                        (_, None) => {
-                            let standings = Standing::new(&leaderboard.leaderboard);
-                            let data = standings.tdf_season(&jersey, year);
-                            display::tdf_points_yearly(&data)
-                        }
-                        // santings daily, based on time
-                        (Jersey::YELLOW, Some(day)) => {
-                            let standings = Standing::new(&leaderboard.leaderboard);
-                            let data = standings.by_time(&Ranking::PART2, year, day);
-                            display::tdf_time_daily(&data)
-                        }
-                        // standings daily, base on points
-                        (_, Some(day)) => {
-                            let standings = Standing::new(&leaderboard.leaderboard);
-                            let data = standings.by_points(&jersey, year, day);
-                            display::tdf_points_daily(&data)
-                        }
-                    };
-
-                    Some(Command::StandingTdf(
-                        year,
-                        day,
-                        formatted,
-                        leaderboard.timestamp,
-                        jersey,
-                    ))
-                }
+            Command::LeaderboardDisplay(year, _, _, scoring, combined, by_time, _) => {
+                let option = match combined {
+                    true => BOARD_OPTIONS[2].to_string(),
+                    false => scoring.to_string(),
+                };
+                Some(match by_time {
+                    true => format!(
+                        "{} {option} {year} {TIEBREAK_OPTION} {}",
+                        COMMANDS[2], TIEBREAK_OPTIONS[0]
+                    ),
+                    false => format!("{} {option} {year}", COMMANDS[2]),
+                })
             }
+            Command::StandingTdf(year, day, _, _, jersey, _) => Some(match day {
+                Some(day) => format!("{} {jersey} {day} {year}", COMMANDS[3]),
+                None => format!("{} {jersey} {year}", COMMANDS[3]),
+            }),
+            Command::Nudge(subscribe) => Some(match subscribe {
+                true => format!("{} {}", COMMANDS[7], NUDGE_OPTIONS[0]),
+                false => format!("{} {}", COMMANDS[7], NUDGE_OPTIONS[1]),
+            }),
+            Command::Quiet(quiet) => Some(match quiet {
+                true => format!("{} {}", COMMANDS[13], QUIET_OPTIONS[0]),
+                false => format!("{} {}", COMMANDS[13], QUIET_OPTIONS[1]),
+            }),
+            Command::Grouped(group, cmd) => cmd
+                .to_input()
+                .map(|input| format!("{input} {GROUP_OPTION} {group}")),
             _ => None,
         }
     }
+
+    pub fn is_command(input: &str) -> bool {
+        input
+            .split_whitespace()
+            .next()
+            .is_some_and(|keyword| find(keyword).is_some())
+    }
+
+    /// Whether the input starts with a keyword, known or not, e.g. "!standings". Unknown ones are
+    /// answered with the closest command.
+    pub fn is_command_like(input: &str) -> bool {
+        input
+            .split_whitespace()
+            .next()
+            .is_some_and(|keyword| REGEX_KEYWORD.is_match(keyword))
+    }
+
+    /// Command input from a message mentioning the bot, e.g. "<@U0123> board 2023" gives "!board 2023".
+    pub fn from_mention(text: &str) -> Option<String> {
+        let text = REGEX_MENTIONS.replace_all(text, "");
+        let text = text.trim();
+        let input = match text.starts_with('!') {
+            true => text.to_string(),
+            false => format!("!{text}"),
+        };
+        Self::is_command_like(&input).then_some(input)
+    }
+
+    // Note that we call this command on strings looking like commands, unknown keywords giving
+    // `Command::NotValid` with a suggestion. None for empty inputs only.
+    // The caller is the Slack user id of the member sending the command, if known.
+    pub fn build_from(
+        input: String,
+        leaderboard: &ScrapedLeaderboard,
+        caller: Option<&str>,
+    ) -> Option<Command> {
+        let keyword = input.split_whitespace().next()?;
+        let command = match find(keyword) {
+            Some(command) => command,
+            None => return Some(Command::NotValid(unknown_command(keyword))),
+        };
+        let signature = command.signature();
+        let (input, group) = match signature.group {
+            true => split_option(&input, GROUP_OPTION),
+            false => (input, None),
+        };
+        let (input, tiebreak) = match signature.tiebreak {
+            true => split_option(&input, TIEBREAK_OPTION),
+            false => (input, None),
+        };
+        if let Some(tiebreak) = tiebreak
+            .as_deref()
+            .filter(|tiebreak| !TIEBREAK_OPTIONS.contains(tiebreak))
+        {
+            return Some(Command::NotValid(format!(
+                "Unknown tiebreak '{}', expected one of: {}. Usage: `{}`",
+                escape_slack(tiebreak),
+                TIEBREAK_OPTIONS.join(", "),
+                command.help()
+            )));
+        }
+
+        // Scores are computed within the group, as if its members were alone.
+        let grouped;
+        let (leaderboard, members) = match group.as_deref() {
+            None => (leaderboard, None),
+            Some(name) => {
+                let Some(members) = member_group(name) else {
+                    let groups = config::SETTINGS
+                        .member_groups
+                        .iter()
+                        .flat_map(|groups| groups.keys())
+                        .sorted()
+                        .join(", ");
+                    return Some(Command::NotValid(format!(
+                        "Unknown group '{}', known groups are: {groups}.",
+                        escape_slack(name)
+                    )));
+                };
+                grouped = ScrapedLeaderboard {
+                    timestamp: leaderboard.timestamp,
+                    leaderboard: group_board(&leaderboard.leaderboard, members),
+                };
+                (&grouped, Some(members))
+            }
+        };
+
+        match CommandInput::parse(&input, &signature, leaderboard, caller) {
+            Ok(mut parsed) => {
+                parsed.group = members;
+                parsed.by_time = tiebreak.is_some();
+                Some(match (group, command.parse(&parsed)) {
+                    (Some(group), cmd) if cmd.keyword().is_some() => {
+                        Command::Grouped(group, Box::new(cmd))
+                    }
+                    (_, cmd) => cmd,
+                })
+            }
+            Err(e) => Some(Command::NotValid(format!(
+                "{e} Usage: `{}`",
+                command.help()
+            ))),
+        }
+    }
+}
+
+/// Year used by commands when none is given, configurable by admins.
+pub fn default_year() -> i32 {
+    SETTINGS_STORE
+        .get()
+        .default_year
+        .unwrap_or_else(|| current_year_day().0)
 }
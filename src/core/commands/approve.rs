@@ -0,0 +1,70 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS, REGEX_MENTION};
+use crate::core::{
+    identities,
+    templates::{MessageTemplate, RenderOptions},
+};
+use minijinja::context;
+
+/// `!approve`: registers the identity claimed by a Slack user, without verification.
+pub struct Approve;
+
+impl BotCommand for Approve {
+    fn name(&self) -> &'static str {
+        COMMANDS[14]
+    }
+
+    fn help(&self) -> &'static str {
+        "!approve <@user>"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::Word("a Slack user mention like `@elf`")),
+            ..Default::default()
+        }
+    }
+
+    fn requires_admin(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let Some(user) = input
+            .option()
+            .and_then(|mention| REGEX_MENTION.captures(mention))
+            .and_then(|captures| captures.get(1))
+            .map(|user| user.as_str().to_string())
+        else {
+            return Command::NotValid(format!("Usage: `{}`.", self.help()));
+        };
+        match identities::claimed_member(&input.leaderboard.leaderboard, &user) {
+            Some(name) => Command::Approve(user, name),
+            None => no_pending_claim(&user),
+        }
+    }
+
+    fn apply(&self, cmd: Command, _caller: Option<&str>) -> Command {
+        match &cmd {
+            Command::Approve(user, name) if !identities::approve(user, name) => {
+                no_pending_claim(user)
+            }
+            _ => cmd,
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Approve(user, name) = cmd else {
+            unreachable!("{} only builds approvals", self.name())
+        };
+        MessageTemplate::Approve
+            .render_with(options, context! { user => user, name => name })
+            .unwrap()
+    }
+}
+
+fn no_pending_claim(user: &str) -> Command {
+    Command::NotValid(format!(
+        "<@{user}> has no pending claim, they can claim their AoC account with `{} <AoC name or id>`.",
+        COMMANDS[5]
+    ))
+}
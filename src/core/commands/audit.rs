@@ -0,0 +1,58 @@
+use super::{BotCommand, Command, CommandInput, COMMANDS};
+use crate::{
+    core::templates::{MessageTemplate, RenderOptions},
+    storage::AUDIT,
+};
+use chrono::{Local, TimeZone, Utc};
+use minijinja::{context, value::Value};
+
+// Number of audit log entries shown by `!audit`
+const AUDIT_ROWS: usize = 10;
+
+/// `!audit`: latest commands received.
+pub struct Audit;
+
+impl BotCommand for Audit {
+    fn name(&self) -> &'static str {
+        COMMANDS[11]
+    }
+
+    fn help(&self) -> &'static str {
+        "!audit"
+    }
+
+    fn requires_admin(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, _input: &CommandInput) -> Command {
+        Command::Audit(AUDIT.latest(AUDIT_ROWS))
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Audit(entries) = cmd else {
+            unreachable!("{} only builds audit logs", self.name())
+        };
+        let entries = entries
+            .iter()
+            .map(|entry| {
+                let time = Utc
+                    .timestamp_millis_opt(entry.received)
+                    .single()
+                    .map(|t| format!("{}", t.with_timezone(&Local).format("%d/%m %H:%M:%S")))
+                    .unwrap_or_default();
+                context! {
+                    time => time,
+                    user => entry.user_id,
+                    channel => entry.channel_id,
+                    input => entry.input,
+                    result => entry.result,
+                    latency => entry.latency_ms,
+                }
+            })
+            .collect::<Vec<Value>>();
+        MessageTemplate::Audit
+            .render_with(options, context! { entries => entries })
+            .unwrap()
+    }
+}
@@ -0,0 +1,122 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, BOARD_OPTIONS, COMMANDS};
+use crate::{
+    core::{
+        display,
+        events::visible_rows,
+        leaderboard::{Leaderboard, AOC_DAYS},
+        standings::{group_board, merged_board, standings_board, standings_board_by_time, Scoring},
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    },
+    storage::OTHER_BOARDS,
+    utils::escape_slack_entities,
+};
+use chrono::{Datelike, Local};
+use minijinja::{context, value::Value};
+
+/// `!board`: standings of the private leaderboard for a year, or of all the private
+/// leaderboards merged.
+pub struct Board;
+
+impl BotCommand for Board {
+    fn name(&self) -> &'static str {
+        COMMANDS[2]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["!standings"]
+    }
+
+    fn help(&self) -> &'static str {
+        "!board [local|stars|combined] [year] [--by time]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::OneOf(&BOARD_OPTIONS)),
+            day: false,
+            year: true,
+            group: true,
+            tiebreak: true,
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let scoring_str = input.option().unwrap_or(Scoring::get_default_str());
+        let scoring = Scoring::from_string(scoring_str).unwrap_or(Scoring::LOCAL);
+        let year = input.year();
+
+        let combined = scoring_str == BOARD_OPTIONS[2];
+        let by_time = input.by_time();
+        // Formatted board, with the row of the caller if ranked
+        let board = |scoring: &Scoring, leaderboard: &Leaderboard| {
+            let entries = match by_time {
+                true => standings_board_by_time(scoring, leaderboard, year),
+                false => standings_board(scoring, leaderboard, year),
+            };
+            let caller_row = input.caller_row(entries.iter().map(|(id, ..)| &*id.name));
+            (display::board(entries), caller_row)
+        };
+
+        if let Some(msg) = invalid_year_day_message(year, None) {
+            Command::NotValid(msg)
+        } else if combined {
+            // Members of several leaderboards are counted once, the scores being recomputed
+            // over all the members.
+            let others = OTHER_BOARDS.read().unwrap();
+            let boards = std::iter::once(&input.leaderboard.leaderboard)
+                .chain(others.values().map(|scraped| &scraped.leaderboard))
+                .collect::<Vec<&Leaderboard>>();
+            let days = (1..=AOC_DAYS).collect::<Vec<u8>>();
+            let mut merged = merged_board(&boards, year, &days);
+            if let Some(members) = input.group() {
+                merged = group_board(&merged, members);
+            }
+            let (board, caller_row) = board(&Scoring::LOCAL, &merged);
+            Command::LeaderboardDisplay(
+                year,
+                board,
+                input.leaderboard.timestamp,
+                Scoring::LOCAL,
+                true,
+                by_time,
+                caller_row,
+            )
+        } else {
+            let (board, caller_row) = board(&scoring, &input.leaderboard.leaderboard);
+            Command::LeaderboardDisplay(
+                year,
+                board,
+                input.leaderboard.timestamp,
+                scoring,
+                false,
+                by_time,
+                caller_row,
+            )
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::LeaderboardDisplay(year, board, time, method, combined, by_time, caller_row) =
+            cmd
+        else {
+            unreachable!("{} only builds boards", self.name())
+        };
+        let now = time.with_timezone(&Local);
+        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
+
+        MessageTemplate::LeaderboardDisplay
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    current_year => year == &now.year(),
+                    timestamp => timestamp,
+                    leaderboard => Value::from_safe_string(escape_slack_entities(&visible_rows(board, options, *caller_row))),
+                    scoring_method => method.to_string(),
+                    combined => combined,
+                    by_time => by_time,
+                },
+            )
+            .unwrap()
+    }
+}
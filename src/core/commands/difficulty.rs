@@ -0,0 +1,83 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    core::templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    storage::DIFFICULTY,
+    utils::current_year_day,
+};
+use minijinja::context;
+
+/// `!difficulty`: vote of the caller on the difficulty of a day of the current year.
+pub struct Difficulty;
+
+impl BotCommand for Difficulty {
+    fn name(&self) -> &'static str {
+        COMMANDS[16]
+    }
+
+    fn help(&self) -> &'static str {
+        "!difficulty <1-10> [day]"
+    }
+
+    fn signature(&self) -> Signature {
+        // The vote and the day are both 1 or 2 digits, told apart by their position.
+        Signature {
+            option: Some(OptionKind::Text),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let (year, today) = current_year_day();
+        let arguments = input
+            .option()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|argument| argument.parse::<u8>().ok())
+            .collect::<Vec<Option<u8>>>();
+        let (vote, day) = match arguments.as_slice() {
+            [Some(vote)] => (*vote, today),
+            [Some(vote), Some(day)] => (*vote, *day),
+            _ => (0, 0),
+        };
+        if !(1..=10).contains(&vote) || day == 0 {
+            return Command::NotValid(format!(
+                "Rate the day from 1 (easy) to 10 (hard): `{}`.",
+                self.help()
+            ));
+        }
+        if let Some(msg) = invalid_year_day_message(year, Some(day)) {
+            return Command::NotValid(msg);
+        }
+        if input.caller.is_none() {
+            return Command::NotValid("I can't tell who is voting.".to_string());
+        }
+        Command::Difficulty(year, day, vote, DIFFICULTY.index(year, day))
+    }
+
+    fn apply(&self, cmd: Command, caller: Option<&str>) -> Command {
+        match (cmd, caller) {
+            (Command::Difficulty(year, day, vote, _index), Some(caller)) => {
+                DIFFICULTY.vote(year, day, caller, vote);
+                Command::Difficulty(year, day, vote, DIFFICULTY.index(year, day))
+            }
+            (cmd, _) => cmd,
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Difficulty(year, day, vote, index) = cmd else {
+            unreachable!("{} only builds votes", self.name())
+        };
+        MessageTemplate::Difficulty
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    day => day,
+                    vote => vote,
+                    index => index.map(|index| format!("{index:.1}")),
+                },
+            )
+            .unwrap()
+    }
+}
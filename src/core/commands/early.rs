@@ -0,0 +1,65 @@
+use super::{BotCommand, Command, CommandInput, Signature, COMMANDS};
+use crate::{
+    config,
+    core::{
+        standings::solved_within,
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    },
+};
+use chrono::Duration;
+use minijinja::context;
+
+// Number of members shown in the leaderboard of the early birds
+const EARLY_ROWS: usize = 10;
+
+/// `!early`: members who solved the most puzzles shortly after their release.
+pub struct Early;
+
+impl BotCommand for Early {
+    fn name(&self) -> &'static str {
+        COMMANDS[17]
+    }
+
+    fn help(&self) -> &'static str {
+        "!early [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            year: true,
+            group: true,
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let year = input.year();
+
+        if let Some(msg) = invalid_year_day_message(year, None) {
+            return Command::NotValid(msg);
+        }
+        let window_min = config::SETTINGS.early_window_min;
+        let counts = solved_within(
+            &input.leaderboard.leaderboard,
+            year,
+            Duration::minutes(window_min as i64),
+        )
+        .into_iter()
+        .take(EARLY_ROWS)
+        .map(|(id, count)| (id.name.to_string(), count))
+        .collect();
+        Command::Early(year, window_min, counts)
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Early(year, window_min, counts) = cmd else {
+            unreachable!("{} only builds early birds", self.name())
+        };
+        MessageTemplate::Early
+            .render_with(
+                options,
+                context! { year => year, window_min => window_min, counts => counts },
+            )
+            .unwrap()
+    }
+}
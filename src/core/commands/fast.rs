@@ -0,0 +1,100 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    core::{
+        events::symbols_prefix,
+        standings::{Ranking, Standing, RANKING_METHODS},
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+        themes::Theme,
+    },
+    utils::current_year_day,
+};
+use chrono::{Datelike, Local};
+use minijinja::context;
+
+/// `!fast`: fastest members of a day.
+pub struct Fast;
+
+impl BotCommand for Fast {
+    fn name(&self) -> &'static str {
+        COMMANDS[1]
+    }
+
+    fn help(&self) -> &'static str {
+        "!fast [delta|p1|p2|limit] [day] [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::OneOf(&RANKING_METHODS)),
+            day: true,
+            year: true,
+            group: true,
+            tiebreak: false,
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let ranking_str = input.option().unwrap_or(Ranking::get_default_str());
+        let ranking = Ranking::from_string(ranking_str).unwrap_or(Ranking::DELTA);
+        let year = input.year();
+        let day = input.day().unwrap_or_else(|| current_year_day().1);
+
+        if let Some(msg) = invalid_year_day_message(year, Some(day)) {
+            Command::NotValid(msg)
+        } else {
+            let data = Standing::new(&input.leaderboard.leaderboard).by_time(&ranking, year, day);
+            let caller_row = input.caller_row(data.iter().map(|(name, _)| name.as_str()));
+            Command::Ranking(
+                year,
+                day,
+                data,
+                input.leaderboard.timestamp,
+                ranking,
+                caller_row,
+            )
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Ranking(year, day, data, time, method, caller_row) = cmd else {
+            unreachable!("{} only builds rankings", self.name())
+        };
+        let now = time.with_timezone(&Local);
+        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
+
+        // Prefix with medal or ranking
+        let prefixed = data
+            .iter()
+            .zip(symbols_prefix(Theme::current().table().medals).into_iter())
+            .map(|((name, score), prefix)| (prefix, name, format!("{:>9}", score)))
+            .collect::<Vec<(String, &String, String)>>();
+        let mut prefixed_data = prefixed
+            .iter()
+            .skip(options.visible_rows().0)
+            .take(options.visible_rows().1.unwrap_or(data.len()))
+            .cloned()
+            .collect::<Vec<(String, &String, String)>>();
+        // Compact rankings also show the caller, when ranked below the top rows.
+        if let Some(row) = options
+            .shown_caller_row(*caller_row)
+            .and_then(|row| prefixed.get(row))
+        {
+            prefixed_data.push(row.clone());
+        }
+
+        MessageTemplate::Ranking
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    day => day,
+                    current_day => year == &now.year() && *day as u32 == now.day(),
+                    timestamp => timestamp,
+                    ranking => prefixed_data,
+                    ranking_method => method.to_string(),
+                    is_limit => match method {Ranking::LIMIT => true, _ => false},
+                },
+            )
+            .unwrap()
+    }
+}
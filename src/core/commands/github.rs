@@ -0,0 +1,90 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    core::{
+        identities,
+        templates::{MessageTemplate, RenderOptions},
+    },
+    storage::SETTINGS_STORE,
+    utils::escape_slack,
+};
+use minijinja::context;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// GitHub repositories, e.g. "octocat/advent-of-code"
+static REGEX_REPOSITORY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9-]+/[A-Za-z0-9._-]+$").unwrap());
+
+/// `!github`: registers the GitHub repository the caller pushes their solutions to, or stops
+/// linking them without repository.
+pub struct GitHub;
+
+impl BotCommand for GitHub {
+    fn name(&self) -> &'static str {
+        COMMANDS[18]
+    }
+
+    fn help(&self) -> &'static str {
+        "!github [owner/repository]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::Word(
+                "a repository like `octocat/advent-of-code`",
+            )),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        // Repositories are also accepted as urls, e.g. "https://github.com/octocat/advent-of-code"
+        let repository = input.option().map(|repository| {
+            repository
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .trim_start_matches("https://")
+                .trim_start_matches("github.com/")
+                .trim_end_matches('/')
+                .trim_end_matches(".git")
+                .to_string()
+        });
+        if let Some(repository) = repository.as_ref() {
+            if !REGEX_REPOSITORY.is_match(repository) {
+                return Command::NotValid(format!(
+                    "'{}' is not a GitHub repository: `{}`.",
+                    escape_slack(repository),
+                    self.help()
+                ));
+            }
+        }
+        match input.caller.and_then(identities::member_name) {
+            Some(_) => Command::GitHub(repository),
+            None => Command::NotValid(format!(
+                "I don't know who you are yet, introduce yourself with `{} <AoC name or id>`.",
+                COMMANDS[5]
+            )),
+        }
+    }
+
+    fn apply(&self, cmd: Command, caller: Option<&str>) -> Command {
+        if let (Command::GitHub(repository), Some(caller)) = (&cmd, caller) {
+            SETTINGS_STORE.update(|stored| match repository {
+                Some(repository) => stored
+                    .github_repositories
+                    .insert(caller.to_string(), repository.to_string()),
+                None => stored.github_repositories.remove(caller),
+            });
+        }
+        cmd
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::GitHub(repository) = cmd else {
+            unreachable!("{} only builds repositories", self.name())
+        };
+        MessageTemplate::GitHub
+            .render_with(options, context! { repository => repository })
+            .unwrap()
+    }
+}
@@ -0,0 +1,43 @@
+use super::{find, BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::core::templates::{MessageTemplate, RenderOptions};
+use minijinja::{context, value::Value};
+
+/// `!help [command]`: the commands handbook, or the usage of a command.
+pub struct Help;
+
+impl BotCommand for Help {
+    fn name(&self) -> &'static str {
+        COMMANDS[0]
+    }
+
+    fn help(&self) -> &'static str {
+        "!help [command]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::Word("a command")),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let usage = input
+            .option()
+            .and_then(|name| find(&format!("!{}", name.trim_start_matches('!'))))
+            .map(|command| command.help().to_string());
+        Command::Help(usage)
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        match cmd {
+            Command::Help(Some(usage)) => MessageTemplate::CustomMessage
+                .render_with(
+                    options,
+                    context! { message => Value::from_safe_string(format!("`{usage}`")) },
+                )
+                .unwrap(),
+            _ => MessageTemplate::Help.render_with(options, {}).unwrap(),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use super::{BotCommand, Command, CommandInput, Signature, COMMANDS};
+use crate::{
+    core::templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    storage::HEROES,
+};
+use minijinja::context;
+
+/// `!heroes`: wall of fame of the members who made it to the global leaderboard.
+pub struct Heroes;
+
+impl BotCommand for Heroes {
+    fn name(&self) -> &'static str {
+        COMMANDS[12]
+    }
+
+    fn help(&self) -> &'static str {
+        "!heroes [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            year: true,
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let year = input.year();
+
+        if let Some(msg) = invalid_year_day_message(year, None) {
+            return Command::NotValid(msg);
+        }
+        Command::Heroes(year, HEROES.wall_of_fame(year))
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Heroes(year, heroes) = cmd else {
+            unreachable!("{} only builds walls of fame", self.name())
+        };
+        MessageTemplate::Heroes
+            .render_with(options, context! { year => year, heroes => heroes })
+            .unwrap()
+    }
+}
@@ -0,0 +1,68 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    core::{
+        identities,
+        templates::{MessageTemplate, RenderOptions},
+    },
+    utils::escape_slack,
+};
+use minijinja::context;
+
+/// `!iam`: claims the AoC member the caller is, registered once verified.
+pub struct Iam;
+
+impl BotCommand for Iam {
+    fn name(&self) -> &'static str {
+        COMMANDS[5]
+    }
+
+    fn help(&self) -> &'static str {
+        "!iam <AoC name or id>"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::Text),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        // AoC names can contain spaces, so the whole argument is used.
+        let name_or_id = input.option().unwrap_or_default();
+        let member = identities::find_member(&input.leaderboard.leaderboard, name_or_id);
+        match (input.caller, member) {
+            (_, None) => Command::NotValid(format!(
+                "No member named '{}' on the private leaderboard.",
+                escape_slack(name_or_id)
+            )),
+            (None, Some(_)) => {
+                Command::NotValid("Could not identify who you are on Slack.".to_string())
+            }
+            (Some(_), Some(id)) => Command::Iam(
+                id.name.to_string(),
+                id.numeric,
+                identities::verification_code(),
+            ),
+        }
+    }
+
+    fn apply(&self, cmd: Command, caller: Option<&str>) -> Command {
+        if let (Command::Iam(_name, member, code), Some(caller)) = (&cmd, caller) {
+            identities::claim(caller.to_string(), *member, code.to_string());
+        }
+        cmd
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Iam(name, _member, code) = cmd else {
+            unreachable!("{} only builds identity claims", self.name())
+        };
+        MessageTemplate::Iam
+            .render_with(
+                options,
+                context! { name => name, code => code, approve => COMMANDS[14] },
+            )
+            .unwrap()
+    }
+}
@@ -0,0 +1,115 @@
+use super::{BotCommand, Command, CommandInput, Signature, COMMANDS};
+use crate::{
+    config,
+    core::{
+        identities,
+        standings::{
+            first_star_latencies, member_standing, solve_hours, standings_board_iter, Scoring,
+        },
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    },
+    utils::format_duration,
+};
+use chrono::{Datelike, Local};
+use minijinja::context;
+
+/// `!me`: standing of the caller for a year, how fast they usually get the first star, and the
+/// hours of the day they get their stars at, charted in an attached file.
+pub struct Me;
+
+impl BotCommand for Me {
+    fn name(&self) -> &'static str {
+        COMMANDS[6]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["!stats"]
+    }
+
+    fn help(&self) -> &'static str {
+        "!me [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            year: true,
+            group: true,
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let year = input.year();
+
+        if let Some(msg) = invalid_year_day_message(year, None) {
+            return Command::NotValid(msg);
+        }
+        match input.caller.and_then(identities::member_name) {
+            None => Command::NotValid(format!(
+                "I don't know who you are yet, introduce yourself with `{} <AoC name or id>`.",
+                COMMANDS[5]
+            )),
+            Some(name) => {
+                let board =
+                    standings_board_iter(&Scoring::LOCAL, &input.leaderboard.leaderboard, year);
+                let standing = member_standing(board, &name);
+                let latencies = first_star_latencies(&input.leaderboard.leaderboard, year)
+                    .into_iter()
+                    .find(|(id, ..)| *id.name == *name)
+                    .map(|(_id, best, median, worst)| (best, median, worst));
+                let hours = input
+                    .leaderboard
+                    .leaderboard
+                    .iter()
+                    .find(|e| *e.id.name == *name)
+                    .map(|e| {
+                        let timezone = config::SETTINGS.get_member_timezone(e.id.numeric);
+                        solve_hours(&input.leaderboard.leaderboard, year, e.id.numeric, timezone)
+                    })
+                    .unwrap_or_default();
+                Command::Me(
+                    year,
+                    name,
+                    standing,
+                    latencies,
+                    hours,
+                    input.leaderboard.timestamp,
+                )
+            }
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Me(year, name, standing, latencies, hours, time) = cmd else {
+            unreachable!("{} only builds member standings", self.name())
+        };
+        let now = time.with_timezone(&Local);
+        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
+
+        MessageTemplate::Me
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    current_year => year == &now.year(),
+                    timestamp => timestamp,
+                    name => name,
+                    position => standing.map(|s| s.0),
+                    stars => standing.map(|s| s.1),
+                    score => standing.map(|s| s.2),
+                    peak_hour => hours
+                        .iter()
+                        .enumerate()
+                        .filter(|(_hour, count)| **count > 0)
+                        .max_by_key(|(hour, count)| (**count, std::cmp::Reverse(*hour)))
+                        .map(|(hour, _count)| hour),
+                    first_star => latencies.map(|(best, median, worst)| context! {
+                        best => format_duration(best),
+                        median => format_duration(median),
+                        worst => format_duration(worst),
+                    }),
+                },
+            )
+            .unwrap()
+    }
+}
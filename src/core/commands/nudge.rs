@@ -0,0 +1,61 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS, NUDGE_OPTIONS};
+use crate::{
+    core::{
+        identities,
+        templates::{MessageTemplate, RenderOptions},
+    },
+    storage::SETTINGS_STORE,
+};
+use minijinja::context;
+
+/// `!nudge`: (un)subscribes the caller to evening nudges.
+pub struct Nudge;
+
+impl BotCommand for Nudge {
+    fn name(&self) -> &'static str {
+        COMMANDS[7]
+    }
+
+    fn help(&self) -> &'static str {
+        "!nudge [on|off]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::OneOf(&NUDGE_OPTIONS)),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let subscribe = input.option() != Some(NUDGE_OPTIONS[1]);
+        match input.caller.and_then(identities::member_name) {
+            Some(_) => Command::Nudge(subscribe),
+            None => Command::NotValid(format!(
+                "I don't know who you are yet, introduce yourself with `{} <AoC name or id>`.",
+                COMMANDS[5]
+            )),
+        }
+    }
+
+    fn apply(&self, cmd: Command, caller: Option<&str>) -> Command {
+        if let (Command::Nudge(subscribe), Some(caller)) = (&cmd, caller) {
+            SETTINGS_STORE.update(|stored| {
+                stored.nudge_subscribers.retain(|user| user != caller);
+                if *subscribe {
+                    stored.nudge_subscribers.push(caller.to_string());
+                }
+            });
+        }
+        cmd
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Nudge(subscribed) = cmd else {
+            unreachable!("{} only builds subscriptions", self.name())
+        };
+        MessageTemplate::Nudge
+            .render_with(options, context! { subscribed => subscribed })
+            .unwrap()
+    }
+}
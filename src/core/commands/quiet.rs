@@ -0,0 +1,61 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS, QUIET_OPTIONS};
+use crate::{
+    core::{
+        identities,
+        templates::{MessageTemplate, RenderOptions},
+    },
+    storage::SETTINGS_STORE,
+};
+use minijinja::context;
+
+/// `!quiet`: stops (or starts again) naming the caller in announcements.
+pub struct Quiet;
+
+impl BotCommand for Quiet {
+    fn name(&self) -> &'static str {
+        COMMANDS[13]
+    }
+
+    fn help(&self) -> &'static str {
+        "!quiet [on|off]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::OneOf(&QUIET_OPTIONS)),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let quiet = input.option() != Some(QUIET_OPTIONS[1]);
+        match input.caller.and_then(identities::member_name) {
+            Some(_) => Command::Quiet(quiet),
+            None => Command::NotValid(format!(
+                "I don't know who you are yet, introduce yourself with `{} <AoC name or id>`.",
+                COMMANDS[5]
+            )),
+        }
+    }
+
+    fn apply(&self, cmd: Command, caller: Option<&str>) -> Command {
+        if let (Command::Quiet(quiet), Some(caller)) = (&cmd, caller) {
+            SETTINGS_STORE.update(|stored| {
+                stored.quiet_members.retain(|user| user != caller);
+                if *quiet {
+                    stored.quiet_members.push(caller.to_string());
+                }
+            });
+        }
+        cmd
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Quiet(quiet) = cmd else {
+            unreachable!("{} only builds preferences", self.name())
+        };
+        MessageTemplate::Quiet
+            .render_with(options, context! { quiet => quiet })
+            .unwrap()
+    }
+}
@@ -0,0 +1,36 @@
+use super::{BotCommand, Command, CommandInput, COMMANDS};
+use crate::{
+    core::templates::{MessageTemplate, RenderOptions},
+    storage::DEAD_LETTERS,
+};
+use minijinja::context;
+
+/// `!replay`: sends the undelivered messages again.
+pub struct Replay;
+
+impl BotCommand for Replay {
+    fn name(&self) -> &'static str {
+        COMMANDS[8]
+    }
+
+    fn help(&self) -> &'static str {
+        "!replay"
+    }
+
+    fn requires_admin(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, _input: &CommandInput) -> Command {
+        Command::Replay(DEAD_LETTERS.get().len())
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Replay(count) = cmd else {
+            unreachable!("{} only builds replays", self.name())
+        };
+        MessageTemplate::Replay
+            .render_with(options, context! { count => count })
+            .unwrap()
+    }
+}
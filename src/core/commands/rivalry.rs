@@ -0,0 +1,64 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    core::{
+        display::{self, GraphFormat, GRAPH_FORMATS},
+        standings::Standing,
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    },
+    utils::escape_slack_entities,
+};
+use minijinja::{context, value::Value};
+
+/// `!rivalry`: graph of the rivalries of a year, uploaded as a file.
+pub struct Rivalry;
+
+impl BotCommand for Rivalry {
+    fn name(&self) -> &'static str {
+        COMMANDS[4]
+    }
+
+    fn help(&self) -> &'static str {
+        "!rivalry [dot|mermaid] [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::OneOf(&GRAPH_FORMATS)),
+            day: false,
+            year: true,
+            group: true,
+            tiebreak: false,
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let format_str = input.option().unwrap_or(GraphFormat::get_default_str());
+        let format = GraphFormat::from_string(format_str).unwrap_or(GraphFormat::DOT);
+        let year = input.year();
+
+        if let Some(msg) = invalid_year_day_message(year, None) {
+            Command::NotValid(msg)
+        } else {
+            let standings = Standing::new(&input.leaderboard.leaderboard);
+            let rivalries = standings.rivalries(year);
+            let graph = display::rivalry_graph(&rivalries, &format);
+            Command::RivalryGraph(year, graph, input.leaderboard.timestamp, format)
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::RivalryGraph(year, graph, _time, format) = cmd else {
+            unreachable!("{} only builds graphs", self.name())
+        };
+        MessageTemplate::RivalryGraph
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    graph => Value::from_safe_string(escape_slack_entities(graph)),
+                    format => format.to_string()
+                },
+            )
+            .unwrap()
+    }
+}
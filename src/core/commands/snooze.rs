@@ -0,0 +1,72 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::core::templates::{MessageTemplate, RenderOptions};
+use chrono::{Duration, Local, Utc};
+use minijinja::context;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Snooze duration when none is given, and longest snooze
+const SNOOZE_DEFAULT_MINUTES: i64 = 60;
+const SNOOZE_MAX_MINUTES: i64 = 24 * 60;
+// Snooze durations, e.g. "2h" or "30m"
+static REGEX_SNOOZE_DURATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?<amount>\d+)(?<unit>[hm])$").unwrap());
+
+/// `!snooze`: holds the announcements in the channel for a while.
+pub struct Snooze;
+
+impl BotCommand for Snooze {
+    fn name(&self) -> &'static str {
+        COMMANDS[9]
+    }
+
+    fn help(&self) -> &'static str {
+        "!snooze [duration]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::Word("a duration like `2h` or `30m`")),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let minutes = match input.option() {
+            Some(option) => snooze_minutes(option),
+            None => Some(SNOOZE_DEFAULT_MINUTES),
+        };
+        match minutes {
+            Some(minutes) if minutes > 0 && minutes <= SNOOZE_MAX_MINUTES => {
+                Command::Snooze(Utc::now() + Duration::minutes(minutes))
+            }
+            _ => Command::NotValid(format!(
+                "Snooze for a duration like `2h` or `30m`, up to {}h.",
+                SNOOZE_MAX_MINUTES / 60
+            )),
+        }
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Snooze(until) = cmd else {
+            unreachable!("{} only builds snoozes", self.name())
+        };
+        let until = until.with_timezone(&Local);
+        MessageTemplate::Snooze
+            .render_with(
+                options,
+                context! { until => format!("{}", until.format("%H:%M")) },
+            )
+            .unwrap()
+    }
+}
+
+/// Minutes of a snooze duration, e.g. "2h" or "30m". None if too large to be counted.
+fn snooze_minutes(duration: &str) -> Option<i64> {
+    let caps = REGEX_SNOOZE_DURATION.captures(duration)?;
+    let amount = caps.name("amount")?.as_str().parse::<i64>().ok()?;
+    match caps.name("unit")?.as_str() {
+        "h" => amount.checked_mul(60),
+        _ => Some(amount),
+    }
+}
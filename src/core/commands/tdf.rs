@@ -0,0 +1,103 @@
+use super::{BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    core::{
+        display,
+        events::visible_rows,
+        standings::{Jersey, Ranking, Standing, JERSEY_COLORS},
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    },
+    utils::escape_slack_entities,
+};
+use chrono::{Datelike, Local};
+use minijinja::{context, value::Value};
+
+/// `!tdf`: Tour de France standings, for a year or a day.
+pub struct Tdf;
+
+impl BotCommand for Tdf {
+    fn name(&self) -> &'static str {
+        COMMANDS[3]
+    }
+
+    fn help(&self) -> &'static str {
+        "!tdf [yellow|green|combative] [day] [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::OneOf(&JERSEY_COLORS)),
+            day: true,
+            year: true,
+            group: true,
+            tiebreak: false,
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let jersey_str = input.option().unwrap_or(Jersey::get_default_str());
+        let jersey = Jersey::from_string(jersey_str).unwrap_or(Jersey::YELLOW);
+        let year = input.year();
+        let day = input.day();
+
+        if let Some(msg) = invalid_year_day_message(year, day) {
+            return Command::NotValid(msg);
+        }
+        let standings = Standing::new(&input.leaderboard.leaderboard);
+        let (formatted, caller_row) = match (&jersey, day) {
+            // standing yearly, based on time
+            (Jersey::YELLOW, None) => {
+                let data = standings.tdf_season(&jersey, year);
+                let caller_row = input.caller_row(data.iter().map(|(id, ..)| &*id.name));
+                (display::tdf_time_yearly(&data), caller_row)
+            }
+            // standing yearly, based on points
+            (_, None) => {
+                let data = standings.tdf_season(&jersey, year);
+                let caller_row = input.caller_row(data.iter().map(|(id, ..)| &*id.name));
+                (display::tdf_points_yearly(&data), caller_row)
+            }
+            // santings daily, based on time
+            (Jersey::YELLOW, Some(day)) => {
+                let data = standings.by_time(&Ranking::PART2, year, day);
+                let caller_row = input.caller_row(data.iter().map(|(name, _)| name.as_str()));
+                (display::tdf_time_daily(&data), caller_row)
+            }
+            // standings daily, base on points
+            (_, Some(day)) => {
+                let data = standings.by_points(&jersey, year, day);
+                let caller_row = input.caller_row(data.iter().map(|(id, _)| &*id.name));
+                (display::tdf_points_daily(&data), caller_row)
+            }
+        };
+        Command::StandingTdf(
+            year,
+            day,
+            formatted,
+            input.leaderboard.timestamp,
+            jersey,
+            caller_row,
+        )
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::StandingTdf(year, day, standings, time, jersey, caller_row) = cmd else {
+            unreachable!("{} only builds standings", self.name())
+        };
+        let now = time.with_timezone(&Local);
+        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
+
+        MessageTemplate::TdfStandings
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    day => day,
+                    current_year => year == &now.year(),
+                    timestamp => timestamp,
+                    standings => Value::from_safe_string(escape_slack_entities(&visible_rows(standings, options, *caller_row))),
+                    jersey => jersey.to_string()
+                },
+            )
+            .unwrap()
+    }
+}
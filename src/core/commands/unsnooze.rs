@@ -0,0 +1,26 @@
+use super::{BotCommand, Command, CommandInput, COMMANDS};
+use crate::core::templates::{MessageTemplate, RenderOptions};
+use minijinja::context;
+
+/// `!unsnooze`: ends the snooze of the channel.
+pub struct Unsnooze;
+
+impl BotCommand for Unsnooze {
+    fn name(&self) -> &'static str {
+        COMMANDS[10]
+    }
+
+    fn help(&self) -> &'static str {
+        "!unsnooze"
+    }
+
+    fn parse(&self, _input: &CommandInput) -> Command {
+        Command::Unsnooze
+    }
+
+    fn execute(&self, _cmd: &Command, options: &RenderOptions) -> String {
+        MessageTemplate::Unsnooze
+            .render_with(options, context! {})
+            .unwrap()
+    }
+}
@@ -0,0 +1,172 @@
+use super::{default_year, BotCommand, Command, CommandInput, OptionKind, Signature, COMMANDS};
+use crate::{
+    config,
+    core::{
+        leaderboard::{Leaderboard, ScrapedLeaderboard},
+        standings::{board_statistics, merged_board, shared_days, standings_board_iter, Scoring},
+        templates::{invalid_year_day_message, MessageTemplate, RenderOptions},
+    },
+    storage::OTHER_BOARDS,
+    utils::{current_year_day, escape_slack, format_duration},
+};
+use itertools::Itertools;
+use minijinja::context;
+use std::collections::HashMap;
+
+// Number of members shown in the combined ranking
+const VERSUS_ROWS: usize = 10;
+
+/// `!versus`: two private leaderboards compared head-to-head.
+pub struct Versus;
+
+impl BotCommand for Versus {
+    fn name(&self) -> &'static str {
+        COMMANDS[15]
+    }
+
+    fn help(&self) -> &'static str {
+        "!versus <leaderboard> <leaderboard> [year]"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature {
+            option: Some(OptionKind::Text),
+            ..Default::default()
+        }
+    }
+
+    fn parse(&self, input: &CommandInput) -> Command {
+        let arguments = input
+            .option()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<&str>>();
+        let (names, year) = match arguments.as_slice() {
+            [a, b] => ([*a, *b], Some(default_year())),
+            [a, b, year] => match year.parse::<i32>() {
+                Ok(year) => ([*a, *b], Some(year)),
+                Err(_) => {
+                    return Command::NotValid(format!(
+                        "Expected a year between 2015 and {}, got '{}'. Usage: `{}`",
+                        current_year_day().0,
+                        escape_slack(year),
+                        self.help()
+                    ))
+                }
+            },
+            _ => ([""; 2], None),
+        };
+        let Some(year) = year.filter(|_| names[0] != names[1]) else {
+            return Command::NotValid(format!(
+                "Pick two different leaderboards among {}: `{}`.",
+                known_boards().join(", "),
+                self.help()
+            ));
+        };
+        if let Some(msg) = invalid_year_day_message(year, None) {
+            return Command::NotValid(msg);
+        }
+
+        let others = OTHER_BOARDS.read().unwrap();
+        let boards = match (
+            find_board(names[0], &input.leaderboard.leaderboard, &others),
+            find_board(names[1], &input.leaderboard.leaderboard, &others),
+        ) {
+            (Ok(a), Ok(b)) => [a, b],
+            (Err(msg), _) | (_, Err(msg)) => return Command::NotValid(msg),
+        };
+
+        let statistics = names
+            .iter()
+            .zip(boards)
+            .map(|(name, board)| {
+                let (average, completion) = board_statistics(board, year);
+                let members = board
+                    .iter()
+                    .filter(|e| e.year == year)
+                    .map(|e| e.id.numeric)
+                    .unique()
+                    .count();
+                (
+                    name.to_string(),
+                    members,
+                    average.map(format_duration),
+                    format!("{:.0}%", 100.0 * completion),
+                )
+            })
+            .collect();
+
+        let days = shared_days(&boards, year);
+        let merged = merged_board(&boards, year, &days);
+        let members = boards.map(|board| board.members_ids());
+        let ranking = standings_board_iter(&Scoring::LOCAL, &merged, year)
+            .take(VERSUS_ROWS)
+            .map(|(id, _scores, score)| {
+                let member_of = names
+                    .iter()
+                    .zip(&members)
+                    .filter(|(_name, ids)| ids.contains(&id.numeric))
+                    .map(|(name, _ids)| *name)
+                    .join(" & ");
+                (id.name.to_string(), member_of, score)
+            })
+            .collect();
+
+        Command::Versus(year, statistics, days, ranking)
+    }
+
+    fn execute(&self, cmd: &Command, options: &RenderOptions) -> String {
+        let Command::Versus(year, boards, days, ranking) = cmd else {
+            unreachable!("{} only builds comparisons", self.name())
+        };
+        MessageTemplate::Versus
+            .render_with(
+                options,
+                context! {
+                    year => year,
+                    boards => boards,
+                    days => days,
+                    ranking => ranking,
+                },
+            )
+            .unwrap()
+    }
+}
+
+/// Names of the private leaderboards, the main one first.
+fn known_boards() -> Vec<String> {
+    let settings = &config::SETTINGS;
+    std::iter::once(settings.aoc_private_leaderboard_name.to_string())
+        .chain(
+            settings
+                .aoc_other_leaderboards
+                .iter()
+                .flatten()
+                .map(|(name, _id)| name.to_string())
+                .sorted(),
+        )
+        .collect()
+}
+
+/// Private leaderboard with the name, either the main one or one of the others.
+fn find_board<'a>(
+    name: &str,
+    main: &'a Leaderboard,
+    others: &'a HashMap<String, ScrapedLeaderboard>,
+) -> Result<&'a Leaderboard, String> {
+    let settings = &config::SETTINGS;
+    if name == settings.aoc_private_leaderboard_name {
+        return Ok(main);
+    }
+    match others.get(name) {
+        Some(scraped) => Ok(&scraped.leaderboard),
+        None if known_boards().iter().any(|known| known == name) => Err(format!(
+            "Leaderboard {name} was not retrieved yet, try again later."
+        )),
+        None => Err(format!(
+            "Unknown leaderboard '{}', pick one among {}.",
+            escape_slack(name),
+            known_boards().join(", ")
+        )),
+    }
+}
@@ -4,6 +4,57 @@ use crate::{
 };
 use chrono::Duration;
 use itertools::Itertools;
+use std::fmt;
+use unicode_width::UnicodeWidthStr;
+
+pub const GRAPH_FORMATS: [&'static str; 2] = ["dot", "mermaid"];
+
+#[derive(Debug, Clone)]
+pub enum GraphFormat {
+    DOT,
+    MERMAID,
+}
+
+impl GraphFormat {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            format if format == GRAPH_FORMATS[0] => Some(GraphFormat::DOT),
+            format if format == GRAPH_FORMATS[1] => Some(GraphFormat::MERMAID),
+            _ => None,
+        }
+    }
+    pub fn get_default_str() -> &'static str {
+        GRAPH_FORMATS[0]
+    }
+
+    /// Extension of the exported graph files.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::DOT => "dot",
+            GraphFormat::MERMAID => "mmd",
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphFormat::DOT => {
+                write!(f, "{}", GRAPH_FORMATS[0])
+            }
+            GraphFormat::MERMAID => {
+                write!(f, "{}", GRAPH_FORMATS[1])
+            }
+        }
+    }
+}
+
+/// Text padded with spaces up to the given width in columns. Wide characters (e.g. CJK or emoji)
+/// take two columns in code blocks, so padding by number of chars would misalign the tables.
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!("{text}{}", " ".repeat(padding))
+}
 
 pub fn tdf_time_yearly(entries: &Vec<(&Identifier, i64, i64)>) -> String {
     // calculate width for positions
@@ -11,10 +62,10 @@ pub fn tdf_time_yearly(entries: &Vec<(&Identifier, i64, i64)>) -> String {
     let width_pos = entries.len().to_string().len();
 
     // calculate width for names
-    // the length of the longest name, plus one for ':'
+    // the display width of the longest name, plus one for ':'
     let width_name = 1 + entries
         .iter()
-        .map(|(id, _, _)| id.name.len())
+        .map(|(id, _, _)| id.name.width())
         .max()
         .unwrap_or_default();
 
@@ -39,10 +90,10 @@ pub fn tdf_time_yearly(entries: &Vec<(&Identifier, i64, i64)>) -> String {
         .enumerate()
         .map(|(idx, (id, total_seconds, penalties))| {
             format!(
-                "{:>width_pos$}) {:<width_name$} {:>width_duration$} {:>width_delta_duration$} {:>width_penalties$}",
+                "{:>width_pos$}) {} {:>width_duration$} {:>width_delta_duration$} {:>width_penalties$}",
                 // idx is zero-based
                 idx + 1,
-                id.name,
+                pad_to_width(&id.name, width_name),
                 format_duration_with_days(Duration::seconds(*total_seconds)),
                 match idx == 0 {
                     true => "".to_string(),
@@ -67,10 +118,10 @@ pub fn tdf_points_yearly(entries: &Vec<(&Identifier, i64, i64)>) -> String {
     let width_pos = entries.len().to_string().len();
 
     // calculate width for names
-    // the length of the longest name, plus one for ':'
+    // the display width of the longest name, plus one for ':'
     let width_name = 1 + entries
         .iter()
-        .map(|(id, _, _)| id.name.len())
+        .map(|(id, _, _)| id.name.width())
         .max()
         .unwrap_or_default();
 
@@ -88,10 +139,10 @@ pub fn tdf_points_yearly(entries: &Vec<(&Identifier, i64, i64)>) -> String {
         .enumerate()
         .map(|(idx, (id, total_points, scored_days))| {
             format!(
-                "{:>width_pos$}) {:<width_name$} {:>width_points$} {:>width_scored$}",
+                "{:>width_pos$}) {} {:>width_points$} {:>width_scored$}",
                 // idx is zero-based
                 idx + 1,
-                id.name,
+                pad_to_width(&id.name, width_name),
                 total_points,
                 format!("(scored {:0>2} days)", scored_days),
             )
@@ -106,10 +157,10 @@ pub fn tdf_points_daily(entries: &Vec<(&Identifier, usize)>) -> String {
     let width_pos = entries.len().to_string().len();
 
     // calculate width for names
-    // the length of the longest name, plus one for ':'
+    // the display width of the longest name, plus one for ':'
     let width_name = 1 + entries
         .iter()
-        .map(|(id, _)| id.name.len())
+        .map(|(id, _)| id.name.width())
         .max()
         .unwrap_or_default();
 
@@ -118,10 +169,10 @@ pub fn tdf_points_daily(entries: &Vec<(&Identifier, usize)>) -> String {
         .enumerate()
         .map(|(idx, (id, points))| {
             format!(
-                "{:>width_pos$}) {:<width_name$} {points}",
+                "{:>width_pos$}) {} {points}",
                 // idx is zero-based
                 idx + 1,
-                id.name,
+                pad_to_width(&id.name, width_name),
             )
         })
         .join("\n")
@@ -134,10 +185,10 @@ pub fn tdf_time_daily(entries: &Vec<(String, String)>) -> String {
     let width_pos = entries.len().to_string().len();
 
     // calculate width for names
-    // the length of the longest name, plus one for ':'
+    // the display width of the longest name, plus one for ':'
     let width_name = 1 + entries
         .iter()
-        .map(|(name, _)| name.len())
+        .map(|(name, _)| name.width())
         .max()
         .unwrap_or_default();
 
@@ -146,15 +197,81 @@ pub fn tdf_time_daily(entries: &Vec<(String, String)>) -> String {
         .enumerate()
         .map(|(idx, (name, time))| {
             format!(
-                "{:>width_pos$}) {:<width_name$} {time}",
+                "{:>width_pos$}) {} {time}",
                 // idx is zero-based
                 idx + 1,
-                name,
+                pad_to_width(name, width_name),
             )
         })
         .join("\n")
 }
 
+/// Standings with the movement of each member since the previous standings, e.g. "▲2".
+pub fn movement_board(entries: &[(String, usize, String)]) -> String {
+    let width_pos = entries.len().to_string().len();
+    let width_name = entries
+        .iter()
+        .map(|(name, _score, _movement)| name.width())
+        .max()
+        .unwrap_or_default();
+    let width_score = entries
+        .iter()
+        .map(|(_name, score, _movement)| score.to_string().len())
+        .max()
+        .unwrap_or_default();
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(idx, (name, score, movement))| {
+            format!(
+                "{:>width_pos$}) {} {:>width_score$}  {movement}",
+                idx + 1,
+                pad_to_width(name, width_name),
+                score,
+            )
+            .trim_end()
+            .to_string()
+        })
+        .join("\n")
+}
+
+// Width of the longest bar of the hour histograms, in squares
+const HISTOGRAM_WIDTH: usize = 30;
+
+/// Histogram of counts by hour of the day, one bar per hour, scaled to the largest count.
+pub fn hour_histogram(hours: &[usize; 24]) -> String {
+    let max = hours.iter().max().copied().unwrap_or_default().max(1);
+    hours
+        .iter()
+        .enumerate()
+        .map(|(hour, count)| {
+            let width = (count * HISTOGRAM_WIDTH + max - 1) / max;
+            format!("{hour:02}h {:<HISTOGRAM_WIDTH$} {count}", "■".repeat(width))
+                .trim_end()
+                .to_string()
+        })
+        .join("\n")
+}
+
+// Width of the progress bars, in squares
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// Progress toward the maximum as a bar of squares, followed by the percentage.
+pub fn progress_bar(value: usize, max: usize) -> String {
+    let ratio = match max {
+        0 => 0.0,
+        max => (value as f64 / max as f64).min(1.0),
+    };
+    let filled = (ratio * PROGRESS_BAR_WIDTH as f64).round() as usize;
+    format!(
+        "{}{} {:.0}%",
+        "■".repeat(filled),
+        "□".repeat(PROGRESS_BAR_WIDTH - filled),
+        ratio * 100.0
+    )
+}
+
 // Display board from given entries
 pub fn board(entries: Vec<(&Identifier, [(u8, usize); 25], usize)>) -> String {
     // calculate width for positions
@@ -162,10 +279,10 @@ pub fn board(entries: Vec<(&Identifier, [(u8, usize); 25], usize)>) -> String {
     let width_pos = entries.len().to_string().len();
 
     // calculate width for names
-    // the length of the longest name, plus one for ':'
+    // the display width of the longest name, plus one for ':'
     let width_name = 1 + entries
         .iter()
-        .map(|(id, _scores, _total)| id.name.len())
+        .map(|(id, _scores, _total)| id.name.width())
         .max()
         .unwrap_or_default();
 
@@ -183,10 +300,10 @@ pub fn board(entries: Vec<(&Identifier, [(u8, usize); 25], usize)>) -> String {
         .enumerate()
         .map(|(idx, (id, scores, total))| {
             format!(
-                "{:>width_pos$}) {:<width_name$} {:>width_score$}  [{}]",
+                "{:>width_pos$}) {} {:>width_score$}  [{}]",
                 // idx is zero-based
                 idx + 1,
-                id.name,
+                pad_to_width(&id.name, width_name),
                 total,
                 scores
                     .iter()
@@ -201,3 +318,40 @@ pub fn board(entries: Vec<(&Identifier, [(u8, usize); 25], usize)>) -> String {
         })
         .join("\n")
 }
+
+// Graph of head-to-head rivalries, edges pointing from winner to loser
+pub fn rivalry_graph(
+    rivalries: &Vec<(&Identifier, &Identifier, usize, usize)>,
+    format: &GraphFormat,
+) -> String {
+    match format {
+        GraphFormat::DOT => {
+            let edges = rivalries
+                .iter()
+                .map(|(winner, loser, wins, losses)| {
+                    format!(
+                        "  \"{}\" -> \"{}\" [label=\"{wins}-{losses}\"];",
+                        winner.name.replace('"', "\\\""),
+                        loser.name.replace('"', "\\\""),
+                    )
+                })
+                .join("\n");
+            format!("digraph rivalries {{\n{edges}\n}}")
+        }
+        GraphFormat::MERMAID => {
+            let edges = rivalries
+                .iter()
+                .map(|(winner, loser, wins, losses)| {
+                    format!(
+                        "  m{}[\"{}\"] -->|{wins}-{losses}| m{}[\"{}\"]",
+                        winner.numeric,
+                        winner.name.replace('"', "#quot;"),
+                        loser.numeric,
+                        loser.name.replace('"', "#quot;"),
+                    )
+                })
+                .join("\n");
+            format!("graph LR\n{edges}")
+        }
+    }
+}
@@ -1,23 +1,54 @@
 use crate::{
+    config,
     core::{
         commands::Command,
-        leaderboard::{LeaderboardStatistics, ProblemPart},
-        standings::Ranking,
-        templates::MessageTemplate,
+        display,
+        i18n::Language,
+        leaderboard::{GlobalRank, LeaderboardStatistics, ProblemPart},
+        notifier::Announcement,
+        templates::{MessageTemplate, RenderOptions, Verbosity},
+        themes::Theme,
+    },
+    storage::SETTINGS_STORE,
+    utils::{
+        escape_slack_entities, format_duration, format_rank, ordinal_number_suffix, DayHighlight,
     },
-    utils::{current_year_day, format_duration, format_rank, ordinal_number_suffix, DayHighlight},
 };
-use chrono::{Datelike, Local};
+use chrono::{DateTime, Duration, Local, Utc};
 use itertools::Itertools;
-use minijinja::context;
-use slack_morphism::{SlackChannelId, SlackTs};
+use minijinja::{context, value::Value};
+use serde_json::json;
+use slack_morphism::{SlackChannelId, SlackTs, SlackUserId};
 use std::fmt;
+use strum::IntoStaticStr;
 use text_to_ascii_art::convert;
 
-const MEDALS: [&'static str; 3] = ["🥇", "🥈", "🥉"];
-const TROPHIES: [&'static str; 5] = ["🏆", "🥈", "🥉", "🍫", "🍬"];
+// Names of the announcements which can be muted by admins.
+pub const MUTABLE_EVENTS: [&'static str; 21] = [
+    "global_leaderboard_complete",
+    "global_leaderboard_hero",
+    "global_leaderboard_update",
+    "daily_challenge",
+    "daily_summary",
+    "new_entries",
+    "new_members",
+    "solutions_thread",
+    "countdown",
+    "welcome",
+    "weekly_digest",
+    "members_left",
+    "members_renamed",
+    "aoc_status",
+    "first_blood",
+    "everyone_finished",
+    "star_milestone",
+    "season_completed",
+    "spotlight",
+    "daily_standings",
+    "github_solution",
+];
 
-fn symbols_prefix<'a>(symbols: &'a [&'static str]) -> impl Iterator<Item = String> + 'a {
+pub(crate) fn symbols_prefix<'a>(symbols: &'a [&'static str]) -> impl Iterator<Item = String> + 'a {
     let num = symbols.len();
     symbols
         .iter()
@@ -30,45 +61,362 @@ fn symbols_prefix<'a>(symbols: &'a [&'static str]) -> impl Iterator<Item = Strin
         })
 }
 
-#[derive(Debug)]
+/// Keep only the visible rows of a formatted table, depending on verbosity and page. Compact
+/// outputs also show the row of the caller, when ranked below the top rows.
+pub(crate) fn visible_rows(
+    table: &str,
+    options: &RenderOptions,
+    caller_row: Option<usize>,
+) -> String {
+    let rows = match options.visible_rows() {
+        (skip, Some(n)) => table.lines().skip(skip).take(n).join("\n"),
+        (skip, None) => table.lines().skip(skip).join("\n"),
+    };
+    match options
+        .shown_caller_row(caller_row)
+        .and_then(|row| table.lines().nth(row))
+    {
+        Some(caller) => format!("{rows}\n{caller}"),
+        None => rows,
+    }
+}
+
+#[derive(Debug, IntoStaticStr)]
 pub enum Event {
     GlobalLeaderboardComplete((u8, LeaderboardStatistics)),
-    GlobalLeaderboardHeroFound((String, ProblemPart, u8)),
+    GlobalLeaderboardHeroFound((String, ProblemPart, GlobalRank)),
     GlobalLeaderboardUpdateMessage(u64, u64),
     DailyChallengeIsUp(u8, String),
+    // Countdown to the unlock of the puzzle (year, day, minutes before unlock)
+    DailyCountdown(i32, u8, u32),
+    // year, day, fastest members for part 1, part 2 and delta, (stars earned by the members, maximum),
+    // (difficulty index of the day, (day, index) of the hardest day so far)
     DailySummary(
         i32,
         u8,
         Vec<(String, String)>,
         Vec<(String, String)>,
         Vec<(String, String)>,
+        (usize, usize),
+        (Option<f64>, Option<(u8, f64)>),
     ),
     PrivateLeaderboardNewEntries(Vec<DayHighlight>),
     PrivateLeaderboardNewMembers(Vec<String>),
+    // Members who left the private leaderboard, the scores being recomputed without them
+    PrivateLeaderboardMembersLeft(Vec<String>),
+    // (former name, new name)
+    PrivateLeaderboardMembersRenamed(Vec<(String, String)>),
+    // First member of the private leaderboard to complete a part of a new day (name, day, part, time since release)
+    PrivateLeaderboardFirstBlood(String, u8, ProblemPart, String),
+    // Every member of the private leaderboard has both stars of a day (day, last finisher if shown,
+    // time taken by the group since release)
+    PrivateLeaderboardEveryoneFinished(u8, Option<String>, String),
+    // Total stars of the season collected by the private leaderboard crossed a milestone
+    // (milestone, total stars, maximum stars of the season)
+    PrivateLeaderboardStarMilestone(usize, usize, usize),
+    // Member who just earned all the stars of the year (name, finishing order among the members)
+    PrivateLeaderboardSeasonCompleted(String, usize),
+    // Member invited to walk through their solution in the daily thread (day, name)
+    DailySpotlight(u8, String),
+    // Standings at the end of a day (year, day, (name, local score, movement since the day before))
+    DailyStandings(i32, u8, Vec<(String, usize, String)>),
+    // Solution of a member pushed to their GitHub repository (name, day, repository, short sha, url)
+    GitHubSolution(String, u8, String, String, String),
+    // AoC went down (false) or came back (true)
+    AocStatusChanged(bool),
+    // First place of the private leaderboard changed: (name, local score, scrape time)
+    PrivateLeaderboardNewLeader(String, usize, DateTime<Utc>),
     DailySolutionsThreadToInitialize(u8),
-    CommandReceived(SlackChannelId, SlackTs, Command),
+    // Evening nudge of a subscribed member (Slack user id, AoC name, day) who did not complete the day
+    NudgeMember(String, String, u8),
+    // New member of the private leaderboard mapped to a Slack user (user, name), welcomed in DM
+    WelcomeMember(String, String),
+    // Identity claimed with `!iam` verified, the member having set their AoC name to the code (user, name)
+    IdentityVerified(String, String),
+    // Command received in a channel, with the ts of the command message and its sender
+    CommandReceived(SlackChannelId, SlackTs, Command, Option<SlackUserId>),
+    // Weekly digest: year, (member name, kudos received) ordered by kudos, and the wall of fame
+    // (member name, appearances, points, best rank) of the global leaderboard, and the chances
+    // (member name, percentage) of the favorites to win the season, the (day, difficulty index)
+    // of the hardest day so far, and (member name, median, best, worst) time to the first star
+    // of the quickest members
+    WeeklyDigest(
+        i32,
+        Vec<(String, usize)>,
+        Vec<(String, usize, usize, u16)>,
+        Vec<(String, usize)>,
+        Option<(u8, f64)>,
+        Vec<(String, String, String, String)>,
+    ),
+    // Snooze of the channel ended, at the given time, unless extended or ended in the meantime
+    SnoozeEnded(SlackChannelId, DateTime<Utc>),
+    // Another page of a command response was requested, the message at ts will be updated
+    CommandPageRequested(SlackChannelId, SlackTs, Command, usize),
+    // Replay of the dead letters requested out of Slack (e.g. from the admin API)
+    DeadLettersReplayRequested,
+    // The aggregation window of completions ended, completions held meanwhile are announced
+    CompletionsWindowEnded,
+    // Announcement rendered by the previous process and handed off before being posted (channel, text)
+    HandedOff(SlackChannelId, String),
     // Debug/Monitoring events
-    PrivateLeaderboardUpdated,
+    // Private leaderboard was scraped: (year, formatted standings, scrape time)
+    PrivateLeaderboardUpdated(i32, String, DateTime<Utc>),
+    // The layout of an AoC page changed, with the selector which broke
+    AocLayoutChanged(String),
     // GlobalLeaderboardPollingStarted,
     // FailedFetching,
 }
 
+/// Event rendered with specific rendering options (language, template set, verbosity).
+pub struct Localized<'a> {
+    event: &'a Event,
+    options: RenderOptions,
+}
+
+impl Event {
+    pub fn localized(&self, language: Language) -> Localized<'_> {
+        Localized {
+            event: self,
+            options: RenderOptions {
+                language,
+                ..RenderOptions::default()
+            },
+        }
+    }
+
+    /// Name of the announcement, for events which can be muted.
+    pub fn mutable_name(&self) -> Option<&'static str> {
+        match self {
+            Event::GlobalLeaderboardComplete(_) => Some(MUTABLE_EVENTS[0]),
+            Event::GlobalLeaderboardHeroFound(_) => Some(MUTABLE_EVENTS[1]),
+            Event::GlobalLeaderboardUpdateMessage(_, _) => Some(MUTABLE_EVENTS[2]),
+            Event::DailyChallengeIsUp(_, _) => Some(MUTABLE_EVENTS[3]),
+            Event::DailySummary(..) => Some(MUTABLE_EVENTS[4]),
+            Event::PrivateLeaderboardNewEntries(_) => Some(MUTABLE_EVENTS[5]),
+            Event::PrivateLeaderboardNewMembers(_) => Some(MUTABLE_EVENTS[6]),
+            Event::DailySolutionsThreadToInitialize(_) => Some(MUTABLE_EVENTS[7]),
+            Event::DailyCountdown(_, _, _) => Some(MUTABLE_EVENTS[8]),
+            Event::WelcomeMember(_, _) => Some(MUTABLE_EVENTS[9]),
+            Event::WeeklyDigest(..) => Some(MUTABLE_EVENTS[10]),
+            Event::PrivateLeaderboardMembersLeft(_) => Some(MUTABLE_EVENTS[11]),
+            Event::PrivateLeaderboardMembersRenamed(_) => Some(MUTABLE_EVENTS[12]),
+            Event::AocStatusChanged(_) => Some(MUTABLE_EVENTS[13]),
+            Event::PrivateLeaderboardFirstBlood(..) => Some(MUTABLE_EVENTS[14]),
+            Event::PrivateLeaderboardEveryoneFinished(..) => Some(MUTABLE_EVENTS[15]),
+            Event::PrivateLeaderboardStarMilestone(..) => Some(MUTABLE_EVENTS[16]),
+            Event::PrivateLeaderboardSeasonCompleted(..) => Some(MUTABLE_EVENTS[17]),
+            Event::DailySpotlight(..) => Some(MUTABLE_EVENTS[18]),
+            Event::DailyStandings(..) => Some(MUTABLE_EVENTS[19]),
+            Event::GitHubSolution(..) => Some(MUTABLE_EVENTS[20]),
+            _ => None,
+        }
+    }
+
+    /// Whether the event was muted by admins.
+    pub fn is_muted(&self) -> bool {
+        self.mutable_name().map_or(false, |name| {
+            SETTINGS_STORE
+                .get()
+                .muted_events
+                .iter()
+                .any(|muted| muted == name)
+        })
+    }
+
+    /// Non-critical announcements, held while the channel they are posted to is snoozed.
+    pub fn is_snoozable(&self) -> bool {
+        matches!(
+            self,
+            Event::GlobalLeaderboardComplete(_)
+                | Event::GlobalLeaderboardHeroFound(_)
+                | Event::GlobalLeaderboardUpdateMessage(_, _)
+                | Event::DailySummary(..)
+                | Event::PrivateLeaderboardNewEntries(_)
+                | Event::PrivateLeaderboardNewMembers(_)
+                | Event::PrivateLeaderboardMembersLeft(_)
+                | Event::PrivateLeaderboardMembersRenamed(_)
+                | Event::AocStatusChanged(_)
+                | Event::PrivateLeaderboardFirstBlood(..)
+                | Event::PrivateLeaderboardEveryoneFinished(..)
+                | Event::PrivateLeaderboardStarMilestone(..)
+                | Event::PrivateLeaderboardSeasonCompleted(..)
+                | Event::DailySpotlight(..)
+                | Event::DailyStandings(..)
+                | Event::GitHubSolution(..)
+                | Event::HandedOff(_, _)
+        )
+    }
+
+    /// Announcement sent to the other frontends, for public announcements.
+    pub fn announcement(&self) -> Option<Announcement> {
+        let payload = match self {
+            Event::GlobalLeaderboardComplete((day, stats)) => json!({
+                "day": day,
+                "part1_fastest_sec": stats.p1_fast.map(|d| d.num_seconds()),
+                "part1_slowest_sec": stats.p1_slow.map(|d| d.num_seconds()),
+                "part2_fastest_sec": stats.p2_fast.map(|d| d.num_seconds()),
+                "part2_slowest_sec": stats.p2_slow.map(|d| d.num_seconds()),
+            }),
+            Event::GlobalLeaderboardHeroFound((name, part, rank)) => {
+                json!({ "name": name, "part": part.to_string(), "rank": rank.0 })
+            }
+            Event::GlobalLeaderboardUpdateMessage(counter, interval_sec) => {
+                json!({ "counter": counter, "interval_sec": interval_sec })
+            }
+            Event::DailyChallengeIsUp(day, title) => json!({ "day": day, "title": title }),
+            Event::DailyCountdown(year, day, minutes) => {
+                json!({ "year": year, "day": day, "minutes": minutes })
+            }
+            Event::DailySummary(
+                year,
+                day,
+                part1,
+                part2,
+                delta,
+                (stars, max),
+                (difficulty, hardest),
+            ) => json!({
+                "year": year,
+                "day": day,
+                "part1": part1,
+                "part2": part2,
+                "delta": delta,
+                "stars": stars,
+                "max_stars": max,
+                "difficulty": difficulty,
+                "hardest": hardest.map(|(day, index)| json!({ "day": day, "difficulty": index })),
+            }),
+            Event::PrivateLeaderboardNewEntries(highlights) => json!({ "completions": highlights }),
+            Event::PrivateLeaderboardNewMembers(members) => json!({ "members": members }),
+            Event::PrivateLeaderboardMembersLeft(members) => json!({ "members": members }),
+            Event::PrivateLeaderboardMembersRenamed(renames) => json!({
+                "renames": renames
+                    .iter()
+                    .map(|(before, after)| json!({ "before": before, "after": after }))
+                    .collect::<Vec<_>>(),
+            }),
+            Event::AocStatusChanged(available) => json!({ "available": available }),
+            Event::PrivateLeaderboardFirstBlood(name, day, part, duration) => json!({
+                "name": name,
+                "day": day,
+                "part": part.to_string(),
+                "duration": duration,
+            }),
+            Event::PrivateLeaderboardEveryoneFinished(day, last, duration) => json!({
+                "day": day,
+                "last": last,
+                "duration": duration,
+            }),
+            Event::PrivateLeaderboardStarMilestone(milestone, total, max) => json!({
+                "milestone": milestone,
+                "total": total,
+                "max": max,
+            }),
+            Event::PrivateLeaderboardSeasonCompleted(name, order) => {
+                json!({ "name": name, "order": order })
+            }
+            Event::DailySpotlight(day, name) => json!({ "day": day, "name": name }),
+            Event::GitHubSolution(name, day, repository, sha, url) => json!({
+                "name": name,
+                "day": day,
+                "repository": repository,
+                "sha": sha,
+                "url": url,
+            }),
+            Event::DailyStandings(year, day, rows) => json!({
+                "year": year,
+                "day": day,
+                "standings": rows
+                    .iter()
+                    .map(|(name, score, movement)| json!({
+                        "name": name,
+                        "score": score,
+                        "movement": movement,
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+            Event::DailySolutionsThreadToInitialize(day) => json!({ "day": day }),
+            Event::WeeklyDigest(year, kudos, heroes, odds, hardest, first_stars) => json!({
+                "year": year,
+                "kudos": kudos,
+                "heroes": heroes
+                    .iter()
+                    .map(|(name, appearances, points, best_rank)| json!({
+                        "name": name,
+                        "appearances": appearances,
+                        "points": points,
+                        "best_rank": best_rank,
+                    }))
+                    .collect::<Vec<_>>(),
+                "odds": odds,
+                "hardest": hardest.map(|(day, index)| json!({ "day": day, "difficulty": index })),
+                "first_stars": first_stars
+                    .iter()
+                    .map(|(name, median, best, worst)| json!({
+                        "name": name,
+                        "median": median,
+                        "best": best,
+                        "worst": worst,
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+            // Other events are either private or specific to Slack.
+            _ => return None,
+        };
+        Some(Announcement {
+            name: self.mutable_name()?,
+            payload,
+            text: self.to_string(),
+        })
+    }
+
+    /// Event rendered with the rendering options configured for the channel.
+    pub fn for_channel(&self, channel_id: &str) -> Localized<'_> {
+        Localized {
+            event: self,
+            options: RenderOptions::for_channel(channel_id),
+        }
+    }
+}
+
+impl Localized<'_> {
+    pub fn with_page(self, page: usize) -> Self {
+        Localized {
+            options: self.options.with_page(page),
+            ..self
+        }
+    }
+}
+
 impl fmt::Display for Event {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
+        self.localized(Language::default()).fmt(f)
+    }
+}
+
+impl fmt::Display for Localized<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.event {
             Event::DailySolutionsThreadToInitialize(day) => {
                 write!(
                     f,
                     "{}",
                     MessageTemplate::DailySolutionThread
-                        .get()
-                        .render(context! { day => day })
+                        .render_with(&self.options, context! { day => day })
+                        .unwrap()
+                )
+            }
+            Event::DailyCountdown(_year, day, minutes) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::Countdown
+                        .render_with(&self.options, context! { day => day, minutes => minutes })
                         .unwrap()
                 )
             }
             Event::DailyChallengeIsUp(day, title) => {
-                let day = format!("Day {day}");
-                let header = match convert(day) {
+                let header = match convert(format!("Day {day}")) {
                     Ok(string) => string,
                     Err(_) => "".to_string(),
                 };
@@ -76,44 +424,68 @@ impl fmt::Display for Event {
                     f,
                     "{}",
                     MessageTemplate::DailyChallenge
-                        .get()
-                        .render(context! { header => header, title => title })
+                        .render_with(
+                            &self.options,
+                            context! { day => day, header => Value::from_safe_string(header), title => title }
+                        )
                         .unwrap()
                 )
             }
-            Event::DailySummary(year, day, p1_data, p2_data, delta_data) => {
+            Event::DailySummary(
+                year,
+                day,
+                p1_data,
+                p2_data,
+                delta_data,
+                (stars, max),
+                (difficulty, hardest),
+            ) => {
+                let top = self.options.verbosity.max_rows().unwrap_or(5);
+                // Rounded percentage of the possible stars earned
+                let percent = (stars * 100 + max / 2)
+                    .checked_div(*max)
+                    .unwrap_or_default();
                 // Prefix with medal or ranking
                 let prefixed_p1 = p1_data
                     .iter()
-                    .zip(symbols_prefix(&TROPHIES).into_iter())
+                    .zip(symbols_prefix(Theme::current().table().trophies).into_iter())
                     .map(|((name, score), prefix)| (prefix, name, format!("{:>9}", score)))
-                    .take(5)
+                    .take(top)
                     .collect::<Vec<(String, &String, String)>>();
                 let prefixed_p2 = p2_data
                     .iter()
-                    .zip(symbols_prefix(&TROPHIES).into_iter())
+                    .zip(symbols_prefix(Theme::current().table().trophies).into_iter())
                     .map(|((name, score), prefix)| (prefix, name, format!("{:>9}", score)))
-                    .take(5)
+                    .take(top)
                     .collect::<Vec<(String, &String, String)>>();
                 let prefixed_delta = delta_data
                     .iter()
-                    .zip(symbols_prefix(&TROPHIES).into_iter())
+                    .zip(symbols_prefix(Theme::current().table().trophies).into_iter())
                     .map(|((name, score), prefix)| (prefix, name, format!("{:>9}", score)))
-                    .take(5)
+                    .take(top)
                     .collect::<Vec<(String, &String, String)>>();
 
                 write!(
                     f,
                     "{}",
                     MessageTemplate::DailySummary
-                        .get()
-                        .render(context! {
-                            year => year,
-                            day => format!("{day}{}", ordinal_number_suffix(*day)),
-                            ranking_p1 => prefixed_p1,
-                            ranking_p2 => prefixed_p2,
-                            ranking_delta => prefixed_delta,
-                        })
+                        .render_with(
+                            &self.options,
+                            context! {
+                                year => year,
+                                day => format!("{day}{}", ordinal_number_suffix((*day).into())),
+                                day_number => day,
+                                ranking_p1 => prefixed_p1,
+                                ranking_p2 => prefixed_p2,
+                                ranking_delta => prefixed_delta,
+                                stars => stars,
+                                max_stars => max,
+                                progress => display::progress_bar(*stars, *max),
+                                percent => percent,
+                                difficulty => difficulty.map(|index| format!("{index:.1}")),
+                                hardest => hardest.map(|(day, index)| (day, format!("{index:.1}"))),
+                            }
+                        )
                         .unwrap()
                 )
             }
@@ -123,11 +495,13 @@ impl fmt::Display for Event {
                     f,
                     "{}",
                     MessageTemplate::HardChallenge
-                        .get()
-                        .render(context! {
-                            minutes => minutes,
-                            cycle => cycle
-                        })
+                        .render_with(
+                            &self.options,
+                            context! {
+                                minutes => minutes,
+                                cycle => cycle
+                            }
+                        )
                         .unwrap()
                 )
             }
@@ -135,21 +509,19 @@ impl fmt::Display for Event {
                 write!(
                     f,
                     "{}",
-                        MessageTemplate::GlobalStatistics.get()
-                        .render(context! {
+                        MessageTemplate::GlobalStatistics
+                        .render_with(&self.options, context! {
                             day => day,
                             p1_fast => statistics.p1_fast.map_or("N/A".to_string(), |d| format_duration(d)),
                             p1_slow => statistics.p1_slow.map_or("N/A".to_string(), |d| format_duration(d)),
                             p2_fast => statistics.p2_fast.map_or("N/A".to_string(), |d| format_duration(d)),
                             p2_slow => statistics.p2_slow.map_or("N/A".to_string(), |d| format_duration(d)),
-                            delta_fast => statistics.delta_fast.map_or("N/A".to_string(), |(d, rank)| {
-                                let rank = rank.unwrap_or_default();
-                                format!("*{}* ({})", format_duration(d), format_rank(rank))
-                            }),
-                            delta_slow => statistics.delta_slow.map_or("N/A".to_string(), |(d, rank)| {
-                                let rank = rank.unwrap_or_default();
-                                format!("*{}* ({})", format_duration(d), format_rank(rank))
-                            }),
+                            delta_fast => Value::from_safe_string(statistics.delta_fast.map_or("N/A".to_string(), |(d, rank)| {
+                                format_delta(d, rank)
+                            })),
+                            delta_slow => Value::from_safe_string(statistics.delta_slow.map_or("N/A".to_string(), |(d, rank)| {
+                                format_delta(d, rank)
+                            })),
                         })
                         .unwrap()
                 )
@@ -159,38 +531,81 @@ impl fmt::Display for Event {
                     f,
                     "{}",
                     MessageTemplate::Hero
-                        .get()
-                        .render(context! {
-                            name => hero,
-                            part => part.to_string(),
-                            rank => format_rank(*rank)
-                        })
+                        .render_with(
+                            &self.options,
+                            context! {
+                                name => hero,
+                                part => part.to_string(),
+                                rank => rank.to_string()
+                            }
+                        )
+                        .unwrap()
+                )
+            }
+            Event::WeeklyDigest(year, kudos, heroes, odds, hardest, first_stars) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::WeeklyDigest
+                        .render_with(
+                            &self.options,
+                            context! {
+                                year => year,
+                                kudos => kudos,
+                                heroes => heroes,
+                                odds => odds,
+                                hardest => hardest.map(|(day, index)| (day, format!("{index:.1}"))),
+                                first_stars => first_stars,
+                            }
+                        )
                         .unwrap()
                 )
             }
-            Event::PrivateLeaderboardUpdated => {
+            Event::SnoozeEnded(_channel_id, _until) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::SnoozeEnded
+                        .render_with(&self.options, context! {})
+                        .unwrap()
+                )
+            }
+            // Nothing is announced, the dead letters are posted again.
+            Event::DeadLettersReplayRequested | Event::CompletionsWindowEnded => Ok(()),
+            Event::HandedOff(_channel_id, text) => write!(f, "{}", text),
+            Event::AocLayoutChanged(details) => write!(
+                f,
+                "⚠️ The layout of AoC pages seems to have changed, statistics may be missing \
+                until the bot is updated. {details}"
+            ),
+            Event::PrivateLeaderboardUpdated(year, board, time) => {
+                let now = time.with_timezone(&Local);
+                let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
+
                 write!(
                     f,
                     "{}",
                     MessageTemplate::PrivateLeaderboardUpdated
-                        .get()
-                        .render({})
+                        .render_with(
+                            &self.options,
+                            context! {
+                                year => year,
+                                timestamp => timestamp,
+                                leaderboard => Value::from_safe_string(escape_slack_entities(board)),
+                            }
+                        )
                         .unwrap()
                 )
             }
             Event::PrivateLeaderboardNewEntries(entries) => {
-                let (year, today) = current_year_day();
-
-                let is_today_entries = entries
-                    .iter()
-                    .into_group_map_by(|h| h.year == year && h.day == today);
+                // Completions of the day are the ones made before the next puzzle was released.
+                let is_today_entries = entries.iter().into_group_map_by(|h| h.on_time);
 
                 let mut output = String::new();
                 if let Some(today_entries) = is_today_entries.get(&true) {
                     output.push_str(
                         &MessageTemplate::NewEntriesToday
-                            .get()
-                            .render(context! {completions => today_entries})
+                            .render_with(&self.options, context! {completions => today_entries})
                             .unwrap(),
                     );
                 };
@@ -200,109 +615,249 @@ impl fmt::Display for Event {
                     };
                     output.push_str(
                         &MessageTemplate::NewEntriesLate
-                            .get()
-                            .render(context! {completions => late_entries})
+                            .render_with(&self.options, context! {completions => late_entries})
                             .unwrap(),
                     );
                 };
 
                 write!(f, "{}", output)
             }
+            Event::PrivateLeaderboardNewLeader(name, score, time) => {
+                let now = time.with_timezone(&Local);
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::LeaderTopic
+                        .render_with(
+                            &self.options,
+                            context! {
+                                name => name,
+                                score => score,
+                                time => format!("{}", now.format("%H:%M")),
+                            }
+                        )
+                        .unwrap()
+                )
+            }
+            Event::NudgeMember(_user, name, day) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::NudgeReminder
+                        .render_with(&self.options, context! { name => name, day => day })
+                        .unwrap()
+                )
+            }
+            Event::IdentityVerified(_user, name) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::IdentityVerified
+                        .render_with(&self.options, context! { name => name })
+                        .unwrap()
+                )
+            }
+            Event::WelcomeMember(_user, name) => {
+                let settings = &config::SETTINGS;
+                let channel = SETTINGS_STORE
+                    .get()
+                    .default_channel
+                    .unwrap_or_else(|| settings.slack_default_channel.to_string());
+                // Summary of the commands
+                let help = MessageTemplate::Help
+                    .render_with(
+                        &RenderOptions {
+                            verbosity: Verbosity::COMPACT,
+                            ..self.options.clone()
+                        },
+                        context! {},
+                    )
+                    .unwrap();
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::Welcome
+                        .render_with(
+                            &self.options,
+                            context! { name => name, channel => channel, help => help }
+                        )
+                        .unwrap()
+                )
+            }
             Event::PrivateLeaderboardNewMembers(members) => {
                 write!(
                     f,
                     "{}",
                     MessageTemplate::LeaderboardMemberJoin
-                        .get()
-                        .render(context! {members => members})
+                        .render_with(&self.options, context! {members => members})
                         .unwrap()
                 )
             }
-            Event::CommandReceived(_channel_id, _ts, cmd) => {
-                match cmd {
-                    Command::NotValid(reason) => {
-                        write!(
-                            f,
-                            "{}",
-                            MessageTemplate::CustomMessage
-                                .get()
-                                .render(context! {
-                                message => reason})
-                                .unwrap()
+            Event::PrivateLeaderboardMembersLeft(members) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::LeaderboardMemberLeave
+                        .render_with(&self.options, context! {members => members})
+                        .unwrap()
+                )
+            }
+            Event::PrivateLeaderboardFirstBlood(name, day, part, duration) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::FirstBlood
+                        .render_with(
+                            &self.options,
+                            context! {
+                                name => name,
+                                day => day,
+                                part => part.to_string(),
+                                duration => duration
+                            }
                         )
-                    }
-                    Command::Help => {
-                        write!(f, "{}", MessageTemplate::Help.get().render({}).unwrap())
-                    }
-                    Command::Ranking(year, day, data, time, method) => {
-                        let now = time.with_timezone(&Local);
-                        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
-
-                        // Prefix with medal or ranking
-                        let prefixed_data = data
-                            .iter()
-                            .zip(symbols_prefix(&MEDALS).into_iter())
-                            .map(|((name, score), prefix)| (prefix, name, format!("{:>9}", score)))
-                            .collect::<Vec<(String, &String, String)>>();
-
-                        write!(
-                            f,
-                            "{}",
-                            MessageTemplate::Ranking
-                                .get()
-                                .render(context! {
-                                    year => year,
-                                    day => day,
-                                    current_day => year == &now.year() && *day as u32 == now.day(),
-                                    timestamp => timestamp,
-                                    ranking => prefixed_data,
-                                    ranking_method => method.to_string(),
-                                    is_limit => match method {Ranking::LIMIT => true, _ => false},
-                                })
-                                .unwrap()
+                        .unwrap()
+                )
+            }
+            Event::PrivateLeaderboardEveryoneFinished(day, last, duration) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::EveryoneFinished
+                        .render_with(
+                            &self.options,
+                            context! {
+                                day => day,
+                                last => last,
+                                duration => duration
+                            }
                         )
-                    }
-                    Command::LeaderboardDisplay(year, board, time, method) => {
-                        let now = time.with_timezone(&Local);
-                        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
-
-                        write!(
-                            f,
-                            "{}",
-                            MessageTemplate::LeaderboardDisplay
-                                .get()
-                                .render(context! {
-                                    year => year,
-                                    current_year => year == &now.year(),
-                                    timestamp => timestamp,
-                                    leaderboard => board,
-                                    scoring_method => method.to_string()
-                                })
-                                .unwrap()
+                        .unwrap()
+                )
+            }
+            Event::PrivateLeaderboardStarMilestone(milestone, total, max) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::StarMilestone
+                        .render_with(
+                            &self.options,
+                            context! {
+                                milestone => milestone,
+                                total => total,
+                                max => max,
+                                progress => display::progress_bar(*total, *max)
+                            }
                         )
-                    }
-                    Command::StandingTdf(year, day, standings, time, jersey) => {
-                        let now = time.with_timezone(&Local);
-                        let timestamp = format!("{}", now.format("%d/%m/%Y %H:%M:%S"));
-
-                        write!(
-                            f,
-                            "{}",
-                            MessageTemplate::TdfStandings
-                                .get()
-                                .render(context! {
-                                    year => year,
-                                    day => day,
-                                    current_year => year == &now.year(),
-                                    timestamp => timestamp,
-                                    standings => standings,
-                                    jersey => jersey.to_string()
-                                })
-                                .unwrap()
+                        .unwrap()
+                )
+            }
+            Event::PrivateLeaderboardSeasonCompleted(name, order) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::SeasonCompleted
+                        .render_with(
+                            &self.options,
+                            context! {
+                                name => name,
+                                order => order,
+                                rank => format_rank(*order as u16)
+                            }
                         )
-                    }
-                }
+                        .unwrap()
+                )
+            }
+            Event::DailySpotlight(day, name) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::Spotlight
+                        .render_with(&self.options, context! {day => day, name => name})
+                        .unwrap()
+                )
+            }
+            Event::DailyStandings(year, day, rows) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::DailyStandings
+                        .render_with(
+                            &self.options,
+                            context! {
+                                year => year,
+                                day => day,
+                                standings => Value::from_safe_string(escape_slack_entities(
+                                    &display::movement_board(rows)
+                                )),
+                            }
+                        )
+                        .unwrap()
+                )
+            }
+            Event::GitHubSolution(name, day, repository, sha, url) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::GitHubSolution
+                        .render_with(
+                            &self.options,
+                            context! {
+                                name => name,
+                                day => day,
+                                repository => repository,
+                                sha => sha,
+                                url => url,
+                            }
+                        )
+                        .unwrap()
+                )
+            }
+            Event::AocStatusChanged(available) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::AocStatus
+                        .render_with(&self.options, context! {available => available})
+                        .unwrap()
+                )
             }
+            Event::PrivateLeaderboardMembersRenamed(renames) => {
+                write!(
+                    f,
+                    "{}",
+                    MessageTemplate::LeaderboardMemberRename
+                        .render_with(&self.options, context! {renames => renames})
+                        .unwrap()
+                )
+            }
+            Event::CommandReceived(_channel_id, _ts, cmd, _)
+            | Event::CommandPageRequested(_channel_id, _ts, cmd, _) => match cmd {
+                // Reasons may hold markup (e.g. mentions), the user input they quote being escaped
+                // where they are built.
+                Command::NotValid(reason) => {
+                    write!(
+                        f,
+                        "{}",
+                        MessageTemplate::CustomMessage
+                            .render_with(
+                                &self.options,
+                                context! {
+                                message => Value::from_safe_string(reason.to_string())}
+                            )
+                            .unwrap()
+                    )
+                }
+                cmd => write!(f, "{}", cmd.answer(&self.options).unwrap_or_default()),
+            },
         }
     }
 }
+
+/// Delta time of a member, with its final rank on the global leaderboard if ranked.
+fn format_delta(delta: Duration, rank: Option<GlobalRank>) -> String {
+    match rank {
+        Some(rank) => format!("*{}* ({})", format_duration(delta), rank),
+        None => format!("*{}*", format_duration(delta)),
+    }
+}
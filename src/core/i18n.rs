@@ -0,0 +1,551 @@
+use crate::{config, core::templates::MessageTemplate};
+use std::fmt;
+use strum::EnumIter;
+
+const LANGUAGES: [&'static str; 2] = ["en", "fr"];
+
+#[derive(Debug, Clone, PartialEq, EnumIter)]
+pub enum Language {
+    EN,
+    FR,
+}
+
+impl Language {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            lang if lang == LANGUAGES[0] => Some(Language::EN),
+            lang if lang == LANGUAGES[1] => Some(Language::FR),
+            _ => None,
+        }
+    }
+
+    /// Language configured for the given channel, or the default language of the bot.
+    pub fn for_channel(channel_id: &str) -> Self {
+        let settings = &config::SETTINGS;
+        settings
+            .slack_channels_language
+            .as_ref()
+            .and_then(|languages| languages.get(channel_id))
+            .and_then(|lang| Language::from_string(lang))
+            .unwrap_or_else(Language::default)
+    }
+
+    /// Languages to try in order when rendering a template, ending with english.
+    pub fn fallback_chain(&self) -> Vec<Language> {
+        match self {
+            Language::EN => vec![Language::EN],
+            Language::FR => vec![Language::FR, Language::EN],
+        }
+    }
+
+    /// Name of the template in the templating environment. English templates are registered
+    /// under their bare name, other languages are prefixed with the language code.
+    pub fn template_name(&self, template: &MessageTemplate) -> String {
+        match self {
+            Language::EN => template.name().to_string(),
+            _ => format!("{}/{}", self, template.name()),
+        }
+    }
+
+    /// Source of the compact template for this language, if translated.
+    pub fn compact_template(&self, template: &MessageTemplate) -> Option<&'static str> {
+        match self {
+            Language::EN => template.compact_template(),
+            Language::FR => french_compact(template),
+        }
+    }
+
+    /// Source of the template for this language, if translated.
+    pub fn template(&self, template: &MessageTemplate) -> Option<&'static str> {
+        match self {
+            Language::EN => Some(template.template()),
+            Language::FR => french(template),
+        }
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        let settings = &config::SETTINGS;
+        Language::from_string(&settings.language).unwrap_or(Language::EN)
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Language::EN => {
+                write!(f, "{}", LANGUAGES[0])
+            }
+            Language::FR => {
+                write!(f, "{}", LANGUAGES[1])
+            }
+        }
+    }
+}
+
+fn french(template: &MessageTemplate) -> Option<&'static str> {
+    // Same conventions as the english templates: \n\ creates a line break and discards the
+    // indentation of the next line, \x20 preserves a leading space.
+    match template {
+        MessageTemplate::Help => Some(
+            "🗒️ Bravo, vous avez trouvé le *manuel des commandes du CEO*.\n\
+            Le système d'analyse des arguments est une merveille d'ingénierie regex, \
+            l'ordre des arguments optionnels n'a donc pas (ou du moins ne devrait pas avoir...) d'importance.\n\n\
+            👉 🆘 *Mode d'emploi*\n\
+            ```!help```\n\
+            Liste et explique les commandes du bot. Vous êtes en train de le lire.\n\n\
+            👉 🏎️ *Les plus rapides de l'Ouest !*\n\
+            ```!fast [méthode de classement] [jour] [année]```\n\
+            Meilleur(s) temps du jour. Par défaut, le classement est basé sur le temps `delta` du jour, \
+            mais les classements `p1` et `p2` sont aussi disponibles. La méthode `limit` donne le classement \
+            des arrivées les plus proches de la limite (le moins de temps restant avant la publication du puzzle suivant), \
+            utilisé pour attribuer les points du maillot `!tdf combative`. \
+            Sans jour et/ou année, le jour et/ou l'année en cours sont utilisés.\n\n\
+            👉 📊 *Montre-moi le tableau !*\n\
+            ```!board [méthode de classement] [année] [--by time]```\n\
+            Score et étoiles de l'année, sous forme d'un joli tableau ascii. Le classement par défaut est le score `local` \
+            de l'année en cours, mais le classement par nombre d'étoiles (`stars`) est aussi disponible, ainsi que le \
+            classement combiné (`combined`) de tous nos classements privés. Avec `--by time`, les membres à égalité de points \
+            ou d'étoiles sont départagés par leur temps total de résolution, ce qui récompense la régularité. \
+            Aussi disponible en `!standings`.\n\n\
+            👉 🚴 *La grande boucle !*\n\
+            ```!tdf [couleur du maillot] [jour] [année]```\n\
+            Classements façon Tour de France ! Rejoignez le peloton pour décrocher le maillot `yellow`, \
+            ou accumulez des points pour les convoités maillots `green` ou `combative`. \
+            Le classement par défaut est celui du maillot jaune pour l'année en cours.\n\
+            - le maillot `yellow` est basé sur le temps cumulé de résolution complète (partie 2) de chaque jour (une pénalité de \
+            7 jours est appliquée pour chaque jour non résolu, ou résolu en plus de temps que la pénalité).\n\
+            - les points du maillot `green` se gagnent chaque jour en fonçant entre la partie 1 et la partie 2 ! Les points attribués \
+            sont ceux du maillot vert officiel du Tour de France.\n\
+            - les points du maillot `combative` récompensent chaque jour les âmes courageuses qui n'abandonnent pas et \
+            terminent un jour avant la publication du suivant ... Plus on est proche de la limite, plus on gagne de points !\n\n\
+            👉 🕸️ *Qui bat qui ?*\n\
+            ```!rivalry [dot|mermaid] [année]```\n\
+            Graphe des duels de l'année, au format `dot` (par défaut) ou `mermaid`. Chaque flèche va du vainqueur \
+            au perdant d'un duel, avec le nombre de jours terminés en premier par chacun. \
+            Le graphe est envoyé en fichier.\n\n\
+            👉 🪪 *Qui suis-je ?*\n\
+            ```!iam <nom ou id AoC>```\n\
+            Indique au bot qui vous êtes dans le classement privé, pour être mentionné dans les annonces.\n\n\
+            👉 🙋 *Où en suis-je ?*\n\
+            ```!me [année]```\n\
+            Votre position, vos étoiles et votre score local pour l'année, avec vos temps médian, meilleur et pire \
+            pour la première étoile des jours, et un graphique des heures de la journée où vous résolvez les puzzles. \
+            Aussi disponible en `!stats`.\n\n\
+            👉 🔔 *Motivez-moi !*\n\
+            ```!nudge [on|off]```\n\
+            Recevez un petit rappel privé le soir si vous n'avez pas encore terminé le jour.\n\n\
+            👉 😴 *Ne pas déranger*\n\
+            ```!snooze [durée]\n!unsnooze```\n\
+            Met en pause les annonces du canal pour un moment (par ex. `2h` ou `30m`, une heure par défaut), \
+            pendant un incident ou une réunion. Les annonces retenues sont postées à la fin de la pause.\n\n\
+            👉 🏆 *Le panthéon*\n\
+            ```!heroes [année]```\n\
+            Apparitions des membres dans le classement mondial de l'année, avec les points gagnés.\n\n\
+            👉 🤫 *Restons discrets*\n\
+            ```!quiet [on|off]```\n\
+            Ne plus être nommé(e) dans les annonces, vos étoiles comptant toujours au classement.\n\n\
+            👉 ⚔️ *Duel de classements*\n\
+            ```!versus <classement> <classement> [année]```\n\
+            Compare deux classements privés : temps moyens, taux de complétion et classement combiné des jours terminés par les deux.\n\n\
+            👉 🧗 *C'était dur ?*\n\
+            ```!difficulty <1-10> [jour]```\n\
+            Notez la difficulté d'un jour de l'année, aujourd'hui par défaut. Les votes sont combinés au temps mis par le \
+            classement mondial pour se remplir en un indice de difficulté du jour.\n\n\
+            👉 ⏰ *Les lève-tôt*\n\
+            ```!early [année]```\n\
+            Nombre de puzzles résolus par chacun peu après leur publication (dans l'heure par défaut), pour les \
+            plus matinaux.\n\n\
+            👉 🐙 *Partager depuis GitHub*\n\
+            ```!github [propriétaire/dépôt]```\n\
+            Enregistrez le dépôt GitHub où vous poussez vos solutions, elles seront partagées dans le fil des solutions \
+            du jour dès qu'elles sont poussées. Sans dépôt, le partage s'arrête.\n\n\
+            👥 Ajoutez `--group <nom>` à `!fast`, `!board`, `!tdf`, `!rivalry`, `!me` ou `!early` pour obtenir le classement \
+            au sein d'un groupe de membres, par exemple `!standings --group juniors`.",
+        ),
+        MessageTemplate::CustomMessage => Some("🙅 {{message}}"),
+        MessageTemplate::HardChallenge => Some(
+            "😱 *{{minutes}} minutes* se sont déjà écoulées et il reste encore des places dans le classement mondial ...\n\
+            {% if cycle == 5 -%}
+                Je ne sais pas pour vous, mais j'ai l'impression que la température 🤒 monte soudainement...
+            {% elif cycle == 8 -%}
+                C'est sûrement le bon moment pour garder des mouchoirs à portée de main au cas où 😭.
+            {% elif cycle == 11 -%}
+                Pas d'inquiétude, l'envie d'appeler ☎️  un ami à l'aide 🆘 est tout à fait normale aujourd'hui.
+            {% else -%}
+                Il est temps de hisser le drapeau de l'espoir 🏴 ... Je vous souhaite bonne chance 🤞, vous en aurez besoin ...
+            {% endif %}",
+        ),
+        MessageTemplate::DailyChallenge => Some(
+            "```{{header}}```\n\
+            {{ theme.party }} Le défi du jour est en ligne !\n\
+                \x20 *{{title}}*\n\
+            🔫 Foncez et amusez-vous, {{ theme.timer }} le temps presse !",
+        ),
+        MessageTemplate::DailySolutionThread => Some(
+            "👇 *Fil de discussion du jour {{day}}*\n\
+                \x20   Retenez-vous de l'ouvrir avant d'avoir terminé la partie 2 !\n\
+             🚨 *Attention spoilers* :rotating_light:",
+        ),
+        MessageTemplate::DailySummary => Some(
+            "{{ theme.calendar }} *{{day}} décembre {{year}}*\n\
+            ----- 🥁 *Résumé du jour* 🗞️ -----\n\
+            Voici comment les choses se sont passées en tête du peloton aujourd'hui :\n\
+            ___________________________________________________________________\n\
+            Top 5 *PARTIE 1* {{ theme.finish }}\n\
+            {%- for (prefix, name, time) in ranking_p1 %}\n\
+                {{prefix}} en {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
+            {%- endfor %}\n\
+            ___________________________________________________________________\n\
+            Top 5 *PARTIE 2* {{ theme.finish }}\n\
+            {%- for (prefix, name, time) in ranking_p2 %}\n\
+                {{prefix}} en {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
+            {%- endfor %}\n\
+            ___________________________________________________________________\n\
+            Top 5 *DELTA* {{ theme.finish }}\n\
+            {%- for (prefix, name, time) in ranking_delta %}\n\
+                {{prefix}} en {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
+            {%- endfor %}\n\
+            ___________________________________________________________________\n\
+            🎄 {{ stars }}/{{ max_stars }} étoiles pour l'instant : `{{ progress }}`
+            {%- if difficulty %}\n\
+                🧗 Difficulté du jour : *{{ difficulty }}/10*
+                {%- if hardest and hardest[0] != day_number %}, le jour {{ hardest[0] }} reste le plus difficile : *{{ hardest[1] }}/10*
+                {%- elif hardest %}, le plus difficile jusqu'ici
+                {%- endif %}
+            {%- endif %}",
+        ),
+        MessageTemplate::PrivateLeaderboardUpdated => Some(
+            "📌 *Classement {{ year }}* par score local :\n\
+            ```{{ leaderboard }}```\n\
+            🔁 _Dernière mise à jour : {{ timestamp }}_",
+        ),
+        MessageTemplate::LeaderboardMemberJoin => Some(
+            "{%- for name in members %}\n\
+                {{ theme.welcome }} Un nouveau joueur rejoint l'arène de Noël ! Bienvenue à bord *{{name|mention}}* !
+             {%- endfor %}",
+        ),
+        MessageTemplate::LeaderboardMemberLeave => Some(
+            "👋 {{ members|join(', ') }} {{ 'ont' if members|length > 1 else 'a' }} quitté le classement. Les points dépendent \
+            du nombre de membres, les scores de l'année ont donc été recalculés.",
+        ),
+        MessageTemplate::LeaderboardMemberRename => Some(
+            "{%- for (before, after) in renames %}\n\
+                🏷️ {{ before }} s'appelle désormais *{{ after|mention }}*.
+             {%- endfor %}",
+        ),
+        MessageTemplate::AocStatus => Some(
+            "{% if available %}✅ AoC est de retour, le classement est de nouveau à jour.\
+            {% else %}🚧 AoC semble indisponible, nouvelle tentative bientôt...{% endif %}",
+        ),
+        MessageTemplate::FirstBlood => Some(
+            "🩸 Premier sang ! *{{ name|mention }}* est le premier à obtenir {{ 'la première étoile' if part == '1' else 'les deux étoiles' }} \
+            du jour {{ day }}, en {{ theme.timer }} {{ duration }} !",
+        ),
+        MessageTemplate::EveryoneFinished => Some(
+            "🎉 Tout le monde a les deux étoiles du jour {{ day }} ! Il aura fallu {{ theme.timer }} {{ duration }} au groupe.\
+            {% if last %} Bravo à *{{ last|mention }}* qui a bouclé la boucle !{% endif %}",
+        ),
+        MessageTemplate::StarMilestone => Some(
+            "🌟 Le classement vient de dépasser *{{ milestone }} étoiles* cette saison, {{ total }} sur {{ max }} possibles !\n\
+            `{{ progress }}`",
+        ),
+        MessageTemplate::Spotlight => Some(
+            "🔦 Aujourd'hui, les projecteurs sont sur *{{ name|mention }}* ! Peux-tu nous présenter ta solution du jour {{ day }} ? \
+            Ton approche, l'astuce qui a tout débloqué, ou le bug qui t'a piégé.",
+        ),
+        MessageTemplate::DailyStandings => Some(
+            "📓 Classement à la fin du jour {{ day }} de {{ year }}, et son évolution depuis la veille :\n\
+            ```{{ standings }}```",
+        ),
+        MessageTemplate::GitHubSolution => Some(
+            "🐙 *{{ name|mention }}* a poussé sa solution du jour {{ day }} : <{{ url }}|{{ repository }}@{{ sha }}>",
+        ),
+        MessageTemplate::SeasonCompleted => Some(
+            "🎊🎉🎊 *{{ name|mention }}* vient de gagner sa *50e étoile* et a terminé toute la saison ! 🎊🎉🎊\n\
+            {{ theme.star ~ theme.star ~ theme.star }} {{ 'Premier' if order == 1 else order ~ 'e' }} membre du classement à finir l'année ! {{ theme.star ~ theme.star ~ theme.star }}",
+        ),
+        MessageTemplate::NewEntriesToday => Some(
+            "{%- for entry in completions %}\n\
+                {% with both = entry.parts_duration|length > 1, double = theme.star ~ theme.star, single = theme.star %}\
+                {{ theme.announce }} {{entry.name|mention}} vient de gagner *{{entry.n_stars}}* étoile{{ 's' if entry.n_stars > 1 }} de plus pour le jour {{entry.day}} ({% if both %}{{double}} *<-> {{entry.delta}} *{% else %}{{single}}{% endif %}) +{{entry.new_points}}pts
+                {%- endwith %}
+             {%- endfor %}\n",
+        ),
+        MessageTemplate::NewEntriesLate => Some(
+            "{%- for entry in completions %}\n\
+                {% with both = entry.parts_duration|length > 1, double = theme.check, single = theme.check_single %}\
+                {{ theme.late }}  {{entry.name|mention}} vient de rattraper *{{entry.n_stars}}* étoile{{ 's' if entry.n_stars > 1 }} pour le jour {{entry.day}} ({% if both %}{{double}} les deux parties terminées ! *<-> {{entry.delta}} *{% else %}{{single}}{% endif %}) +{{entry.new_points}}pts
+                {%- endwith %}
+             {%- endfor %}",
+        ),
+        MessageTemplate::GlobalStatistics => Some(
+            "🌍 Le classement mondial est complet pour le *jour {{day}}* ! Voici comment ça s'est passé pour les cadors :\n\
+                \x20 • Temps partie 1 : 🔥 *{{p1_fast}}* - *{{p1_slow}}* ❄️\n\
+                \x20 • Temps partie 2 : 🔥 *{{p2_fast}}* - *{{p2_slow}}* ❄️\n\
+                \x20 • Deltas : 🏃‍♀️ {{delta_fast}} - {{delta_slow}} 🚶‍♀️",
+        ),
+        MessageTemplate::Ranking => Some(
+            "{%- if current_day -%}
+                Temps *{{ ranking_method }}* {{'les plus rapides' if not is_limit else 'les plus proches'}} du jour (au {{timestamp}}) :
+            {%- else -%}
+                Temps *{{ ranking_method }}* {{'les plus rapides' if not is_limit else 'les plus proches'}} du {{ day }}/12/{{ year }} :
+            {%- endif %}\n\
+            {%- for (prefix, name, time) in ranking %}\n\
+                {{prefix}} en {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
+            {%- endfor %}",
+        ),
+        MessageTemplate::Hero => Some(
+            "{{ theme.party }} 🥳 Notre *{{ name|mention }}* est entré(e) dans le classement mondial sur la partie *{{ part }}* ! (*{{ rank }}*) 🙌",
+        ),
+        MessageTemplate::LeaderboardDisplay => Some(
+            "{%- if current_year -%}
+                📓 Classement actuel {{ 'combiné ' if combined }}par {{ '*score local*' if scoring_method == 'local' else \"*nombre d'étoiles*\" }}{{ ', égalités par *temps total de résolution*' if by_time }} au {{timestamp}} :
+            {%- else -%}
+                📓 Classement {{ 'combiné ' if combined }}par {{ '*score local*' if scoring_method == 'local' else \"*nombre d'étoiles*\" }}{{ ', égalités par *temps total de résolution*' if by_time }} de l'édition {{ year }} :
+            {%- endif %}\n\
+            ```{{ leaderboard }}```",
+        ),
+        MessageTemplate::RivalryGraph => Some(
+            "🕸️ Duels de l'édition {{ year }}, en graphe {{ format }} dans le fichier joint.",
+        ),
+        MessageTemplate::Iam => Some(
+            "🪪 Enchanté *{{ name }}* ! Pour prouver que c'est vous, mettez `{{ code }}` comme nom d'affichage AoC \
+            un moment, et je vous mentionnerai dès la prochaine mise à jour du classement. Vous pourrez remettre \
+            votre nom juste après. Pas accès à vos réglages AoC ? Un admin peut se porter garant avec `{{ approve }}`.",
+        ),
+        MessageTemplate::IdentityVerified => Some(
+            "🪪 Vérifié, vous êtes *{{ name }}* ! Je vous mentionnerai désormais. \
+            Vous pouvez remettre votre nom d'affichage AoC quand vous voulez, je suivrai.",
+        ),
+        MessageTemplate::Approve => Some("🪪 <@{{ user }}> est désormais *{{ name }}*."),
+        MessageTemplate::GitHub => Some(
+            "{%- if repository -%}
+                🐙 C'est noté, je partagerai vos solutions poussées sur *{{ repository }}* dans le fil des solutions du jour. \
+                Mentionnez le jour dans le message de commit ou dans le chemin des fichiers, par exemple `day05/main.rs`.
+            {%- else -%}
+                🐙 Vos solutions ne seront plus partagées depuis GitHub.
+            {%- endif %}",
+        ),
+        MessageTemplate::Early => Some(
+            "{%- if counts -%}
+                ⏰ Puzzles résolus moins de {{ window_min }} minutes après leur publication en {{ year }}, place aux lève-tôt :\n\
+                {%- for (name, count) in counts %}\n\
+                    {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name|mention }}* : \
+                    {{ count }} puzzle{{ 's' if count > 1 }}
+                {%- endfor %}
+            {%- else -%}
+                Personne n'a encore résolu de puzzle moins de {{ window_min }} minutes après sa publication en {{ year }}. Réglez vos réveils !
+            {%- endif %}",
+        ),
+        MessageTemplate::Difficulty => Some(
+            "🧗 Merci, votre vote pour le jour {{ day }} est enregistré : *{{ vote }}/10*.\
+            {%- if index %} Indice de difficulté du jour : *{{ index }}/10*.{% endif %}",
+        ),
+        MessageTemplate::Grouped => Some("👥 Groupe *{{ group }}* :\n{{ answer }}"),
+        MessageTemplate::Versus => Some(
+            "⚔️ *{{ boards[0][0] }}* contre *{{ boards[1][0] }}* en {{ year }} :\n\
+            {%- for (name, members, average, completion) in boards %}\n\
+                {{ theme.pointer }} *{{ name }}* : {{ members }} membre{{ 's' if members > 1 }}, {{ completion }} des étoiles, \
+                {{ average ~ ' en moyenne pour les deux étoiles' if average else 'aucun jour terminé' }}
+            {%- endfor %}
+            {%- if days %}\n\
+                {{ theme.party }} Classement combiné sur {{ days|length }} jour{{ 's' if days|length > 1 }} en commun ({{ days|join(', ') }}) :\n\
+                {%- for (name, boards, score) in ranking %}\n\
+                    {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name }}* ({{ boards }}) : {{ score }} pts
+                {%- endfor %}
+            {%- else %}\n\
+                Aucun jour n'a encore été terminé sur les deux classements.
+            {%- endif %}",
+        ),
+        MessageTemplate::Me => Some(
+            "{%- if position -%}
+                🙋 *{{ name }}*, vous êtes *n°{{ position }}* avec {{ stars }} {{ theme.star }} et {{ score }} points \
+                {{- ' au ' ~ timestamp if current_year else \" à l'édition \" ~ year }}.
+                {%- if first_star %}\n\
+                    ⏱️ Première étoile en *{{ first_star.median }}* (médiane), au mieux {{ first_star.best }}, au pire {{ first_star.worst }}.
+                {%- endif %}
+                {%- if peak_hour is not none %}\n\
+                    🕰️ Vos étoiles arrivent surtout vers *{{ peak_hour }}h*.
+                {%- endif %}
+            {%- else -%}
+                🙋 *{{ name }}*, pas encore d'étoile {{ 'cette année' if current_year else \"à l'édition \" ~ year }}.
+            {%- endif %}",
+        ),
+        MessageTemplate::Quiet => Some(
+            "{%- if quiet -%}
+                🤫 C'est noté, vous ne serez plus nommé(e) dans les annonces, vos étoiles comptent toujours au classement. \
+                Utilisez `!quiet off` pour être de nouveau nommé(e).
+            {%- else -%}
+                📣 Vous serez de nouveau nommé(e) dans les annonces. Utilisez `!quiet on` pour rester discret(e).
+            {%- endif %}",
+        ),
+        MessageTemplate::Nudge => Some(
+            "{%- if subscribed -%}
+                🔔 C'est noté ! Je vous enverrai un petit rappel le soir si vous n'avez pas encore terminé le jour. \
+                Utilisez `!nudge off` pour arrêter à tout moment.
+            {%- else -%}
+                🔕 Plus de rappels, vous voilà livré(e) à vous-même. Utilisez `!nudge on` si je vous manque !
+            {%- endif %}",
+        ),
+        MessageTemplate::Replay => Some(
+            "{%- if count -%}
+                🔁 Renvoi de {{ count }} message{{ 's' if count > 1 }} qui n'ont pas pu être délivrés.
+            {%- else -%}
+                ✅ Aucun message non délivré à renvoyer.
+            {%- endif %}",
+        ),
+        MessageTemplate::Heroes => Some(
+            "{%- if heroes -%}
+                {{ theme.party }} Tableau d'honneur du classement mondial en {{ year }} :\n\
+                {%- for (name, appearances, points, best_rank) in heroes %}\n\
+                    {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name|mention }}* : \
+                    {{ appearances }} apparition{{ 's' if appearances > 1 }}, {{ points }} pts, meilleur rang {{ best_rank }}
+                {%- endfor %}
+            {%- else -%}
+                Aucun membre n'a encore atteint le classement mondial en {{ year }}. Accrochez-vous !
+            {%- endif %}",
+        ),
+        MessageTemplate::Audit => Some(
+            "{%- if entries -%}
+                📋 {{ entries|length }} dernière{{ 's' if entries|length > 1 }} commande{{ 's' if entries|length > 1 }} reçue{{ 's' if entries|length > 1 }} :\n\
+                {%- for entry in entries %}\n\
+                    {{ theme.pointer }} {{ entry.time }} {{ '<@' ~ entry.user ~ '>' if entry.user else \"Quelqu'un\" }} dans <#{{ entry.channel }}> : `{{ entry.input }}` → \
+                    {{ entry.result ~ ' (' ~ entry.latency ~ ' ms)' if entry.result else 'non traitée' }}
+                {%- endfor %}
+            {%- else -%}
+                📋 Aucune commande reçue pour l'instant.
+            {%- endif %}",
+        ),
+        MessageTemplate::LeaderTopic => Some("🥇 {{ name }} — {{ score }} pts (mis à jour à {{ time }})"),
+        MessageTemplate::AppHome => Some(
+            "*Advent of Code {{ year }}* {{ theme.calendar }}\n\
+            {% if not name %}\
+                🙋 Je ne sais pas encore qui vous êtes, présentez-vous avec `!iam <nom ou id AoC>`.\
+            {% elif position %}\
+                🙋 *{{ name }}*, vous êtes *n°{{ position }}* avec {{ stars }} {{ theme.star }} et {{ score }} points.\
+            {% else %}\
+                🙋 *{{ name }}*, pas encore d'étoile cette année.\
+            {% endif %}\n\
+            {% if name and day %}\
+                {% if parts == 2 %}\
+                    {{ theme.check }} Jour {{ day }} terminé, bravo !\
+                {% elif parts == 1 %}\
+                    {{ theme.check_single }} Jour {{ day }} : partie 1 faite, encore la partie 2.\
+                {% else %}\
+                    {{ theme.timer }} Le jour {{ day }} vous attend.\
+                {% endif %}\n\
+            {% endif %}\
+            \n\
+            *Top 10* {{ theme.finish }}\n\
+            ```{{ board }}```\n\
+            _Mis à jour le {{ timestamp }}_",
+        ),
+        MessageTemplate::PuzzleUnfurl => Some(
+            "*{{ title }}* {{ theme.calendar }} {{ year }}\n\
+            {{ theme.star }} Résolu par *{{ solved }}* membre{{ 's' if solved > 1 }} du leaderboard privé\
+            {%- if fastest %}, le plus rapide en {{ theme.timer }} *{{ fastest }}*{% endif %}.",
+        ),
+        MessageTemplate::Welcome => Some(
+            "{{ theme.welcome }} Bienvenue dans le leaderboard privé, *{{ name }}* !\n\
+            Les annonces et résultats sont postés dans <#{{ channel }}>, venez dire bonjour.\n\n\
+            {{ help }}\n\
+            Tapez `!help` pour le manuel complet.\n\n\
+            {{ theme.pointer }} Si je me suis trompé de compte AoC, associez le bon avec `!iam <nom ou id AoC>`.",
+        ),
+        MessageTemplate::Snooze => Some(
+            "😴 Chut... les annonces sont en pause dans ce canal jusqu'à *{{ until }}*. \
+            Elles seront postées à la fin de la pause, ou avant avec `!unsnooze`.",
+        ),
+        MessageTemplate::Unsnooze => Some("⏰ Fin de la pause, les annonces reprennent dans ce canal !"),
+        MessageTemplate::SnoozeEnded => Some("⏰ La pause est terminée, les annonces reprennent dans ce canal !"),
+        MessageTemplate::WeeklyDigest => Some(
+            "{{ theme.calendar }} *Résumé de la semaine* - Advent of Code {{ year }}\n\
+            {%- if kudos %}\n\
+                Kudos {{ theme.star }} reçus par les solutions partagées dans les fils du jour :\n\
+                {%- for (name, count) in kudos %}\n\
+                    {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name }}* : {{ count }}
+                {%- endfor %}
+            {%- endif %}
+            {%- if heroes %}\n\
+                {{ theme.party }} Tableau d'honneur du classement mondial cette saison :\n\
+                {%- for (name, appearances, points, best_rank) in heroes %}\n\
+                    {{ theme.pointer }} *{{ name }}* : {{ appearances }} apparition{{ 's' if appearances > 1 }}, {{ points }} pts, meilleur rang {{ best_rank }}
+                {%- endfor %}
+            {%- endif %}
+            {%- if odds %}\n\
+                🎲 Chances de remporter la saison, en simulant les jours restants : \
+                {%- for (name, percent) in odds %} *{{ name }}* {{ percent }} %{{ ',' if not loop.last }}{%- endfor %}
+            {%- endif %}
+            {%- if hardest %}\n\
+                🧗 Le jour {{ hardest[0] }} a été le plus difficile jusqu'ici : *{{ hardest[1] }}/10*
+            {%- endif %}
+            {%- if first_stars %}\n\
+                ⏱️ Les plus rapides pour la première étoile des jours (médiane, meilleur, pire) :\n\
+                {%- for (name, median, best, worst) in first_stars %}\n\
+                    {{ theme.pointer }} *{{ name }}* : {{ median }}, {{ best }}, {{ worst }}
+                {%- endfor %}
+            {%- endif %}",
+        ),
+        MessageTemplate::Countdown => Some(
+            "{{ theme.timer }} Le jour {{ day }} arrive dans *{{ minutes }} minute{{ 's' if minutes > 1 }}* ! Un café ☕ et en place.",
+        ),
+        MessageTemplate::NudgeReminder => Some(
+            "🌙 Coucou *{{ name }}*, le jour {{ day }} vous attend toujours ! Pas de pression, chaque étoile compte \
+            {{ theme.star }} - vous allez y arriver 💪\n\
+            _Répondez `!nudge off` pour arrêter ces rappels._",
+        ),
+        MessageTemplate::TdfStandings => Some(
+            "{%- if current_year and not day -%}
+                🚴 Classement actuel du maillot {{ '🟡 Jaune 🛵' if jersey=='yellow' else ('🟢 Vert 🍏' if jersey=='green' else '⚫Combatif 🥋')}} au {{timestamp}} :
+            {%- elif not day -%}
+                🚴 Classement du maillot {{ '🟡 Jaune 🛵' if jersey=='yellow' else ('🟢 Vert 🍏' if jersey=='green' else '⚫Combatif 🥋')}} de l'édition *{{year}}* :
+            {%- else -%}
+                🚴 Classement du maillot {{ '🟡 Jaune 🛵' if jersey=='yellow' else ('🟢 Vert 🍏' if jersey=='green' else '⚫Combatif 🥋')}} du *jour {{day}}* de l'édition {{year}} :
+            {%- endif %}\n\
+            ```{{ standings }}```",
+        ),
+    }
+}
+
+fn french_compact(template: &MessageTemplate) -> Option<&'static str> {
+    match template {
+        MessageTemplate::Help => Some(
+            "🗒️ Commandes : `!help` • `!fast [delta|p1|p2|limit] [jour] [année]` • `!board [local|stars|combined] [année] [--by time]` • \
+            `!tdf [yellow|green|combative] [jour] [année]` • `!rivalry [dot|mermaid] [année]` • \
+            `!iam <nom ou id AoC>` • `!me [année]` • `!nudge [on|off]` • `!snooze [durée]` • `!unsnooze` • \
+            `!heroes [année]` • `!quiet [on|off]` • `!versus <classement> <classement> [année]` • \
+            `!difficulty <1-10> [jour]` • `!early [année]` • `!github [propriétaire/dépôt]`",
+        ),
+        MessageTemplate::DailyChallenge => Some("{{ theme.party }} Le jour {{day}} est en ligne : *{{title}}* {{ theme.timer }}"),
+        MessageTemplate::DailySummary => Some(
+            "{{ theme.calendar }} Jour {{day}} {{ theme.finish }} P1 : \
+            {%- for (prefix, name, time) in ranking_p1 %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %} \
+            | P2 : {%- for (prefix, name, time) in ranking_p2 %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %} \
+            | Δ : {%- for (prefix, name, time) in ranking_delta %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %} \
+            | 🎄 {{ stars }}/{{ max_stars }} étoiles, {{ percent }}%\
+            {%- if difficulty %} | 🧗 {{ difficulty }}/10{% endif %}",
+        ),
+        MessageTemplate::GlobalStatistics => Some(
+            "🌍 Classement mondial complet pour le jour {{day}} : P1 *{{p1_fast}}* - *{{p1_slow}}* • P2 *{{p2_fast}}* - *{{p2_slow}}*",
+        ),
+        MessageTemplate::NewEntriesToday => Some(
+            "{{ theme.announce }} {% for entry in completions %}{{entry.name|mention}} +{{entry.n_stars}}{{ theme.star }} (jour {{entry.day}}, +{{entry.new_points}}pts){{ ', ' if not loop.last }}{% endfor %}",
+        ),
+        MessageTemplate::NewEntriesLate => Some(
+            "{{ theme.late }} {% for entry in completions %}{{entry.name|mention}} +{{entry.n_stars}}{{ theme.check_single }} (jour {{entry.day}}, +{{entry.new_points}}pts){{ ', ' if not loop.last }}{% endfor %}",
+        ),
+        MessageTemplate::Ranking => Some(
+            "{{ theme.timer }} *{{ ranking_method }}* jour {{ day }} : \
+            {%- for (prefix, name, time) in ranking %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %}",
+        ),
+        MessageTemplate::LeaderboardDisplay => Some(
+            "📓 Top {{ 'combiné' if combined else ('score local' if scoring_method == 'local' else 'étoiles') }}{{ ' (égalités au temps)' if by_time }} {{ year }} : ```{{ leaderboard }}```",
+        ),
+        MessageTemplate::TdfStandings => Some(
+            "🚴 {{ '🟡' if jersey=='yellow' else ('🟢' if jersey=='green' else '⚫')}} {{ year }}{{ ' jour ' ~ day if day }} : ```{{ standings }}```",
+        ),
+        _ => None,
+    }
+}
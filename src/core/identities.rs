@@ -0,0 +1,176 @@
+use crate::{
+    config,
+    core::leaderboard::{Identifier, Leaderboard},
+    storage::{StoredSettings, SETTINGS_STORE},
+};
+use itertools::Itertools;
+use minijinja::value::Value;
+use rand::{thread_rng, Rng};
+use std::collections::HashSet;
+
+// AoC name members set for a moment to verify the identity they claimed, e.g. "elf-0042"
+const VERIFICATION_CODE_PREFIX: &str = "elf-";
+
+/// Slack user id of the AoC member, if known. Identities registered with `!iam` take precedence
+/// over the configured ones.
+pub fn slack_user(name: &str) -> Option<String> {
+    let settings = &config::SETTINGS;
+    SETTINGS_STORE
+        .get()
+        .identities
+        .get(name)
+        .cloned()
+        .or_else(|| {
+            settings
+                .slack_identities
+                .as_ref()
+                .and_then(|identities| identities.get(name).cloned())
+        })
+}
+
+/// AoC member name of the Slack user, if known.
+pub fn member_name(slack_user: &str) -> Option<String> {
+    let settings = &config::SETTINGS;
+    let registered = SETTINGS_STORE
+        .get()
+        .identities
+        .into_iter()
+        .find_map(|(name, user)| (user == slack_user).then_some(name));
+    registered.or_else(|| {
+        settings.slack_identities.as_ref().and_then(|identities| {
+            identities
+                .iter()
+                .find_map(|(name, user)| (user == slack_user).then(|| name.to_string()))
+        })
+    })
+}
+
+/// AoC ids of the members of the leaderboard who asked not to be named in announcements, in the
+/// configuration or with `!quiet`.
+pub fn quiet_members(leaderboard: &Leaderboard) -> HashSet<u64> {
+    let configured = config::SETTINGS.quiet_members.clone().unwrap_or_default();
+    let opted_out = SETTINGS_STORE.get().quiet_members;
+    leaderboard
+        .iter()
+        .map(|e| &e.id)
+        .unique()
+        .filter(|id| {
+            configured.contains(&id.numeric)
+                || slack_user(&id.name).is_some_and(|user| opted_out.contains(&user))
+        })
+        .map(|id| id.numeric)
+        .collect()
+}
+
+/// Leaderboard member matching an AoC name or numeric id.
+pub fn find_member(leaderboard: &Leaderboard, name_or_id: &str) -> Option<Identifier> {
+    leaderboard
+        .iter()
+        .map(|e| &e.id)
+        .find(|id| &*id.name == name_or_id || id.numeric.to_string() == name_or_id)
+        .cloned()
+}
+
+/// New code for a member to set as AoC name, verifying the identity they claimed.
+pub fn verification_code() -> String {
+    format!(
+        "{VERIFICATION_CODE_PREFIX}{:04}",
+        thread_rng().gen_range(0..10000)
+    )
+}
+
+/// Records the claim of the Slack user to be the AoC member, verified once the member sets the
+/// code as AoC name. Replaces any previous claim of the user.
+pub fn claim(slack_user: String, member: u64, code: String) {
+    SETTINGS_STORE.update(|stored| stored.identity_claims.insert(slack_user, (member, code)));
+}
+
+/// Registers the claims of the members whose AoC name is now their verification code, giving
+/// the (Slack user, name) verified. The identity follows the member when the name is set back.
+pub fn verify_claims(leaderboard: &Leaderboard) -> Vec<(String, String)> {
+    let is_verified = |(member, code): &(u64, String)| {
+        leaderboard
+            .iter()
+            .any(|e| e.id.numeric == *member && &*e.id.name == code)
+    };
+    if !SETTINGS_STORE
+        .get()
+        .identity_claims
+        .values()
+        .any(is_verified)
+    {
+        return vec![];
+    }
+    SETTINGS_STORE.update(|stored| {
+        let verified = stored
+            .identity_claims
+            .iter()
+            .filter(|(_user, claim)| is_verified(*claim))
+            .map(|(user, (_member, code))| (user.to_string(), code.to_string()))
+            .collect::<Vec<(String, String)>>();
+        for (user, name) in verified.iter() {
+            stored.identity_claims.remove(user);
+            register_in(stored, name.to_string(), user.to_string());
+        }
+        verified
+    })
+}
+
+/// Name of the AoC member the Slack user claimed to be. None if the user claimed no identity, or
+/// the member left the leaderboard.
+pub fn claimed_member(leaderboard: &Leaderboard, slack_user: &str) -> Option<String> {
+    let (member, _code) = SETTINGS_STORE
+        .get()
+        .identity_claims
+        .get(slack_user)
+        .cloned()?;
+    leaderboard
+        .iter()
+        .map(|e| &e.id)
+        .find(|id| id.numeric == member)
+        .map(|id| id.name.to_string())
+}
+
+/// Registers the claim of the Slack user to be the named AoC member without verification.
+/// Returns whether the claim was still pending.
+pub fn approve(slack_user: &str, name: &str) -> bool {
+    SETTINGS_STORE.update(|stored| {
+        if stored.identity_claims.remove(slack_user).is_none() {
+            return false;
+        }
+        register_in(stored, name.to_string(), slack_user.to_string());
+        true
+    })
+}
+
+/// Whether the name is a verification code, set for a moment by a member verifying a claim.
+pub fn is_verification_code(name: &str) -> bool {
+    name.strip_prefix(VERIFICATION_CODE_PREFIX)
+        .is_some_and(|digits| digits.len() == 4 && digits.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Associate the Slack user to the AoC member, replacing any previous association of the user.
+fn register_in(stored: &mut StoredSettings, name: String, slack_user: String) {
+    stored.identities.retain(|_name, user| user != &slack_user);
+    stored.identities.insert(name, slack_user);
+}
+
+/// Moves the Slack user registered for a renamed AoC member to its new name.
+pub fn rename(before: &str, after: &str) {
+    if !SETTINGS_STORE.get().identities.contains_key(before) {
+        return;
+    }
+    SETTINGS_STORE.update(|stored| {
+        if let Some(slack_user) = stored.identities.remove(before) {
+            stored.identities.insert(after.to_string(), slack_user);
+        }
+    });
+}
+
+/// Template filter mentioning the Slack user of an AoC member, or keeping the name if unknown.
+pub fn mention(name: String) -> Value {
+    match slack_user(&name) {
+        Some(user) => Value::from_safe_string(format!("<@{user}>")),
+        None => Value::from(name),
+    }
+}
@@ -1,19 +1,39 @@
-use crate::error::{BotError, BotResult};
+use crate::{
+    core::source::PUZZLE_EVENT,
+    error::{BotError, BotResult},
+    utils::format_rank,
+};
 use chrono::{naive::NaiveDateTime, DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
 use itertools::{Itertools, MinMaxResult};
+use once_cell::sync::OnceCell;
 use scraper::{Node, Selector};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Ordering,
     collections::{HashMap, HashSet},
     fmt,
+    hash::{Hash, Hasher},
     iter::Iterator,
-    ops::{Deref, DerefMut},
+    ops::Deref,
+    sync::Arc,
 };
-
-static AOC_PUZZLE_UTC_STARTING_HOUR: u32 = 5;
-static AOC_MONTH: u32 = 12;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize)]
+use tracing::warn;
+
+// Puzzles are released at midnight, AoC time.
+pub(crate) static AOC_TIMEZONE: Tz = chrono_tz::US::Eastern;
+pub(crate) static AOC_MONTH: u32 = 12;
+// Number of puzzles of Advent of Code, and the most days of a puzzle event
+pub const AOC_DAYS: u8 = 25;
+// Name given to the members who did not make their name public, followed by their id
+const ANONYMOUS_NAME_PREFIX: &'static str = "anonymous user #";
+// Selectors of the global leaderboard entries, checked for layout changes when fetching
+pub(crate) const SELECTOR_RANK: &'static str = ".leaderboard-position";
+pub(crate) const SELECTOR_TIME: &'static str = ".leaderboard-time";
+// Labels displayed next to the names of supporters in the global leaderboard
+const BADGE_LABELS: [&'static str; 2] = ["(AoC++)", "(Sponsor)"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum ProblemPart {
     FIRST,
     SECOND,
@@ -21,27 +41,81 @@ pub enum ProblemPart {
 
 // Leaderboard entry parsed from AoC API.
 // Year and day fields match corresponding components of DateTime<Utc>.
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize)]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize)]
 pub struct Entry {
     pub timestamp: DateTime<Utc>,
     pub year: i32,
     pub day: u8,
     pub part: ProblemPart,
     pub id: Identifier,
-    pub rank: Option<u8>,
+    // Rank on the global leaderboard of the part, for stars scraped from it
+    pub rank: Option<GlobalRank>,
 }
 
+/// Rank of a star on the global leaderboard of its part, the first being 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GlobalRank(pub u16);
+
 // unique identifier for a participant on this leaderboard
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize)]
+// The name is shared by all the entries of the member, see `Leaderboard::insert`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Identifier {
-    pub name: String,
+    pub name: Arc<str>,
     pub numeric: u64,
 }
 
-type Entries = HashSet<Entry>;
+// Members are identified by their AoC id, their display name may change.
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.numeric == other.numeric
+    }
+}
+
+impl Eq for Identifier {}
+
+impl Hash for Identifier {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numeric.hash(state);
+    }
+}
+
+// Members are listed by name, the id breaking ties between homonyms.
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.numeric == other.numeric {
+            return Ordering::Equal;
+        }
+        (&self.name, self.numeric).cmp(&(&other.name, other.numeric))
+    }
+}
 
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+type Entries = Vec<Entry>;
+// Positions of entries in the leaderboard
+type Positions = Vec<usize>;
+
+// (member, [(n_stars, daily score) for the 25 days]) per year
+type StarsAndScores = HashMap<i32, Vec<(Identifier, [(u8, usize); 25])>>;
+
+/// Unique entries, indexed by member, by year and by (year, day, part) as they are inserted.
 #[derive(Debug)]
-pub struct Leaderboard(Entries);
+pub struct Leaderboard {
+    entries: Entries,
+    by_member: HashMap<u64, Positions>,
+    by_year: HashMap<i32, Positions>,
+    by_year_day_part: HashMap<(i32, u8, ProblemPart), Positions>,
+    // Computed on first use, and dropped whenever entries are inserted.
+    stars_and_scores: OnceCell<StarsAndScores>,
+    // Former display names of the members, oldest first
+    previous_names: HashMap<u64, Vec<Arc<str>>>,
+    // Members of the private leaderboard for each year, with or without stars
+    roster: HashMap<i32, HashSet<u64>>,
+}
 
 #[derive(Debug)]
 pub struct ScrapedLeaderboard {
@@ -56,8 +130,8 @@ pub struct LeaderboardStatistics {
     pub p2_fast: Option<Duration>,
     pub p2_slow: Option<Duration>,
     // (Delta,final rank (part 2))
-    pub delta_fast: Option<(Duration, Option<u8>)>,
-    pub delta_slow: Option<(Duration, Option<u8>)>,
+    pub delta_fast: Option<(Duration, Option<GlobalRank>)>,
+    pub delta_slow: Option<(Duration, Option<GlobalRank>)>,
 }
 
 impl fmt::Display for ProblemPart {
@@ -73,6 +147,31 @@ impl fmt::Display for ProblemPart {
     }
 }
 
+impl Identifier {
+    /// Name of an anonymous member.
+    pub fn anonymous_name(numeric: u64) -> String {
+        format!("{ANONYMOUS_NAME_PREFIX}{numeric}")
+    }
+
+    /// Whether the name is the one given to anonymous members.
+    pub fn is_anonymous_name(name: &str) -> bool {
+        name.starts_with(ANONYMOUS_NAME_PREFIX)
+    }
+}
+
+impl GlobalRank {
+    /// Global score earned with the rank, 100 points for the first down to 1 for the 100th.
+    pub fn points(&self) -> usize {
+        101usize.saturating_sub(self.0.into())
+    }
+}
+
+impl fmt::Display for GlobalRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_rank(self.0))
+    }
+}
+
 impl ProblemPart {
     pub fn from(input: usize) -> Self {
         match input {
@@ -84,6 +183,24 @@ impl ProblemPart {
     }
 }
 
+/// Index of a puzzle day in the daily arrays, if a valid day.
+pub fn day_index(day: u8) -> Option<usize> {
+    (1..=AOC_DAYS).contains(&day).then(|| (day - 1) as usize)
+}
+
+/// (part 1, part 2) entries of a member for a day. Should a part be there more than once, its
+/// earliest completion is taken.
+pub fn daily_parts<'a>(daily_entries: &[&'a Entry]) -> (Option<&'a Entry>, Option<&'a Entry>) {
+    let earliest = |part: ProblemPart| {
+        daily_entries
+            .iter()
+            .copied()
+            .filter(|e| e.part == part)
+            .min_by_key(|e| e.timestamp)
+    };
+    (earliest(ProblemPart::FIRST), earliest(ProblemPart::SECOND))
+}
+
 impl Entry {
     /// Parsing of global leaderboard HTML nodes.
     pub fn from_html(
@@ -92,8 +209,8 @@ impl Entry {
         day: u8,
         part: ProblemPart,
     ) -> Option<Self> {
-        let rank_selector = Selector::parse(r#".leaderboard-position"#).unwrap();
-        let time_selector = Selector::parse(r#".leaderboard-time"#).unwrap();
+        let rank_selector = Selector::parse(SELECTOR_RANK).unwrap();
+        let time_selector = Selector::parse(SELECTOR_TIME).unwrap();
 
         let id = entry
             .value()
@@ -109,12 +226,11 @@ impl Entry {
                 Node::Element(el) => match el.name() {
                     // Name wrapped into <a> tags to link to user's github.
                     "a" => {
-                        let text = node.last_child().unwrap().value();
-                        let text = text.as_text().unwrap().trim();
-                        // We ignore <a> tags related to (AoC++) or (Sponsor) labels.
-                        match (text.starts_with("("), text.ends_with(")")) {
-                            (false, false) => Some(text),
-                            (_, _) => None,
+                        let text = node.last_child()?.value().as_text()?.trim();
+                        // We ignore <a> tags of the badges, names may have parentheses too.
+                        match BADGE_LABELS.contains(&text) {
+                            false => Some(text),
+                            true => None,
                         }
                     }
                     _ => None,
@@ -129,7 +245,8 @@ impl Entry {
                 Some(t) => t
                     .split(")")
                     .next()
-                    .and_then(|rank| rank.trim().parse::<u8>().ok()),
+                    .and_then(|rank| rank.trim().parse::<u16>().ok())
+                    .map(GlobalRank),
                 None => None,
             },
             None => None,
@@ -144,11 +261,9 @@ impl Entry {
                         NaiveDateTime::parse_from_str(&with_year, "%Y %b %d  %H:%M:%S").ok();
                     naive_datetime
                 })
-                .map(|d| {
-                    // Global leaderboard entries are starting at 00:00:00, so we need to offset by
-                    // 5 hours to get real UTC time.
-                    Utc.from_utc_datetime(&d) + Duration::hours(AOC_PUZZLE_UTC_STARTING_HOUR.into())
-                })
+                // Global leaderboard times are given in AoC time, the puzzle starting at 00:00:00.
+                .filter_map(|d| AOC_TIMEZONE.from_local_datetime(&d).single())
+                .map(|d| d.with_timezone(&Utc))
                 .last(),
             None => None,
         };
@@ -158,8 +273,8 @@ impl Entry {
                 id: Identifier {
                     // Name of anonymous user will be None
                     name: name
-                        .map_or(format!("anonymous user #{}", id), |n| n.to_string())
-                        .to_string(),
+                        .map_or(Identifier::anonymous_name(id), |n| n.to_string())
+                        .into(),
                     numeric: id,
                 },
                 rank: Some(rank),
@@ -172,34 +287,32 @@ impl Entry {
         }
     }
 
-    /// Time of the release of the corresponding puzzle.
+    /// Time of the release of the corresponding puzzle, as scheduled by the puzzle event.
     pub fn puzzle_unlock(year: i32, day: u8) -> BotResult<DateTime<Utc>> {
-        // Problems are released at 05:00:00 UTC
-        Utc.with_ymd_and_hms(
-            year,
-            AOC_MONTH,
-            day.into(),
-            AOC_PUZZLE_UTC_STARTING_HOUR,
-            0,
-            0,
-        )
-        .single()
-        .ok_or(BotError::Parse)
+        PUZZLE_EVENT.unlock(year, day)
     }
 
     /// generate key from entry
     pub fn to_key(&self) -> String {
-        format!(
-            "{}_{}_{}",
-            self.id.numeric,
-            self.part,
-            self.rank.unwrap_or_default()
-        )
+        match self.rank {
+            Some(GlobalRank(rank)) => format!("{}_{}_{}", self.id.numeric, self.part, rank),
+            None => format!("{}_{}", self.id.numeric, self.part),
+        }
     }
 
+    /// Time taken to complete the part. Completions stamped before the release of the puzzle
+    /// (e.g. skewed clocks) are flagged and clamped to zero, rather than giving negative times.
     pub fn duration_since_release(&self) -> BotResult<Duration> {
         let release_time = Entry::puzzle_unlock(self.year, self.day)?;
-        Ok(self.timestamp - release_time)
+        let duration = self.timestamp - release_time;
+        if duration < Duration::zero() {
+            warn!(
+                "Completion of {} for day {} of {} stamped {} before the release.",
+                self.id.name, self.day, self.year, -duration
+            );
+            return Ok(Duration::zero());
+        }
+        Ok(duration)
     }
 
     pub fn duration_until_next_release(&self) -> BotResult<Duration> {
@@ -211,7 +324,91 @@ impl Entry {
 
 impl Leaderboard {
     pub fn new() -> Leaderboard {
-        Leaderboard(Entries::new())
+        Leaderboard {
+            entries: Entries::new(),
+            by_member: HashMap::new(),
+            by_year: HashMap::new(),
+            by_year_day_part: HashMap::new(),
+            stars_and_scores: OnceCell::new(),
+            previous_names: HashMap::new(),
+            roster: HashMap::new(),
+        }
+    }
+
+    /// Adds the entry, unless its part is already in the leaderboard. Returns whether it was
+    /// added. A part is completed once by a member: when found again (e.g. in duplicate scrapes),
+    /// the earliest completion is kept. The name of a known member is shared with its other entries,
+    /// or replaces their name if the member was renamed.
+    pub fn insert(&mut self, mut entry: Entry) -> bool {
+        // Daily scores are indexed by day, invalid days are dropped here once and for all.
+        if day_index(entry.day).is_none() {
+            warn!(
+                "Ignoring completion of {} for invalid day {} of {}.",
+                entry.id.name, entry.day, entry.year
+            );
+            return false;
+        }
+        if let Some(positions) = self.by_member.get(&entry.id.numeric) {
+            let known = &self.entries[positions[0]].id.name;
+            if *known == entry.id.name {
+                entry.id.name = Arc::clone(known);
+            } else {
+                self.previous_names
+                    .entry(entry.id.numeric)
+                    .or_default()
+                    .push(Arc::clone(known));
+                for &i in positions {
+                    self.entries[i].id.name = Arc::clone(&entry.id.name);
+                }
+                self.stars_and_scores.take();
+            }
+            let same_part = positions.iter().copied().find(|&i| {
+                let known = &self.entries[i];
+                (known.year, known.day, known.part) == (entry.year, entry.day, entry.part)
+            });
+            if let Some(i) = same_part {
+                if entry.timestamp >= self.entries[i].timestamp {
+                    return false;
+                }
+                self.entries[i] = entry;
+                self.stars_and_scores.take();
+                return true;
+            }
+        }
+
+        let position = self.entries.len();
+        self.by_member
+            .entry(entry.id.numeric)
+            .or_default()
+            .push(position);
+        self.by_year.entry(entry.year).or_default().push(position);
+        self.by_year_day_part
+            .entry((entry.year, entry.day, entry.part))
+            .or_default()
+            .push(position);
+        self.entries.push(entry);
+        self.stars_and_scores.take();
+        true
+    }
+
+    fn entries_at<'a>(
+        &'a self,
+        positions: Option<&'a Positions>,
+    ) -> impl Iterator<Item = &'a Entry> {
+        positions
+            .into_iter()
+            .flatten()
+            .map(move |&position| &self.entries[position])
+    }
+
+    fn entries_for_year(&self, year: i32) -> impl Iterator<Item = &Entry> {
+        self.entries_at(self.by_year.get(&year))
+    }
+
+    fn entries_for_year_day(&self, year: i32, day: u8) -> impl Iterator<Item = &Entry> {
+        [ProblemPart::FIRST, ProblemPart::SECOND]
+            .into_iter()
+            .flat_map(move |part| self.entries_at(self.by_year_day_part.get(&(year, day, part))))
     }
 
     pub fn is_global_complete(&self) -> bool {
@@ -226,8 +423,7 @@ impl Leaderboard {
         year: i32,
         day: u8,
     ) -> HashMap<&Identifier, Vec<&Entry>> {
-        self.iter()
-            .filter(|s| s.year == year && s.day == day)
+        self.entries_for_year_day(year, day)
             .into_group_map_by(|e| &e.id)
     }
 
@@ -252,24 +448,57 @@ impl Leaderboard {
                         // unwrap is safe here as we know the year exists
                         let star_score = n_members_per_year.get(&year).unwrap() - rank_minus_one;
                         let day_scores = acc.entry((*year, id)).or_insert([0; 25]);
-                        day_scores[(*day - 1) as usize] += star_score;
+                        if let Some(score) = day_index(*day).map(|i| &mut day_scores[i]) {
+                            *score += star_score;
+                        }
                     });
                 acc
             },
         )
     }
 
-    /// id => [score per day for that year]
+    /// id => [(n_stars, score) per day for that year]
+    /// Computed for all years at once on first use, then reused until entries change.
     pub fn daily_stars_and_scores_per_member_for_year(
         &self,
         year: i32,
+    ) -> HashMap<&Identifier, [(u8, usize); 25]> {
+        self.iter_daily_stars_and_scores_for_year(year)
+            .map(|(id, scores)| (id, *scores))
+            .collect()
+    }
+
+    /// Same as `daily_stars_and_scores_per_member_for_year`, borrowing from the cached scores
+    /// instead of copying them.
+    pub fn iter_daily_stars_and_scores_for_year(
+        &self,
+        year: i32,
+    ) -> impl Iterator<Item = (&Identifier, &[(u8, usize); 25])> {
+        let stars_and_scores = self.stars_and_scores.get_or_init(|| {
+            self.by_year
+                .keys()
+                .map(|&year| {
+                    let members = self
+                        .compute_daily_stars_and_scores_per_member_for_year(year)
+                        .into_iter()
+                        .map(|(id, scores)| (id.clone(), scores))
+                        .collect();
+                    (year, members)
+                })
+                .collect()
+        });
+        stars_and_scores
+            .get(&year)
+            .into_iter()
+            .flat_map(|members| members.iter().map(|(id, scores)| (id, scores)))
+    }
+
+    fn compute_daily_stars_and_scores_per_member_for_year(
+        &self,
+        year: i32,
     ) -> HashMap<&Identifier, [(u8, usize); 25]> {
         // Max point earned for each star is number of members in leaderboard
-        let members_solutions = self
-            .iter()
-            .filter(|e| e.year == year)
-            .into_group_map_by(|a| &a.id);
-        let n_members = members_solutions.len();
+        let n_members = self.entries_for_year(year).map(|e| &e.id).unique().count();
 
         let standings_per_challenge = self.ranked_members_per_day_part_for_year(year);
         standings_per_challenge
@@ -281,8 +510,12 @@ impl Leaderboard {
                     .for_each(|(rank_minus_one, id)| {
                         let star_score = n_members - rank_minus_one;
                         let day_stars_scores = acc.entry(id).or_insert([(0, 0); 25]);
-                        day_stars_scores[(*day - 1) as usize].0 += 1;
-                        day_stars_scores[(*day - 1) as usize].1 += star_score;
+                        if let Some((stars, score)) =
+                            day_index(*day).map(|i| &mut day_stars_scores[i])
+                        {
+                            *stars += 1;
+                            *score += star_score;
+                        }
                     });
                 acc
             })
@@ -309,15 +542,9 @@ impl Leaderboard {
         let members_delta = entries_per_day_member
             .into_iter()
             // only keep completed days (2 parts)
-            .filter_map(|((day, id), entries)| match entries.len() {
-                1 => None,
-                2 => {
-                    let mut sorted = entries.into_iter().sorted_unstable();
-                    let (p1, p2) = (sorted.next().unwrap(), sorted.next().unwrap());
-                    let delta = p2.timestamp - p1.timestamp;
-                    Some((day, id, delta))
-                }
-                _ => unreachable!(),
+            .filter_map(|((day, id), entries)| match daily_parts(&entries) {
+                (Some(p1), Some(p2)) => Some((day, id, p2.timestamp - p1.timestamp)),
+                _ => None,
             })
             .collect::<Vec<(u8, &Identifier, Duration)>>();
 
@@ -330,7 +557,7 @@ impl Leaderboard {
                     day,
                     deltas
                         .into_iter()
-                        .sorted_unstable_by_key(|(_day, _id, delta)| delta)
+                        .sorted_unstable_by_key(|(_day, id, delta)| (*delta, *id))
                         .map(|(_day, id, delta)| (id, delta))
                         .collect::<Vec<_>>(),
                 )
@@ -344,17 +571,22 @@ impl Leaderboard {
                         let delta_score = n_members - rank_minus_one;
                         let day_scores =
                             acc.entry(*id).or_insert([(Duration::seconds(0), 0, 0); 25]);
-                        day_scores[(*day - 1) as usize] =
-                            (**delta, rank_minus_one + 1, delta_score);
+                        if let Some(i) = day_index(*day) {
+                            day_scores[i] = (**delta, rank_minus_one + 1, delta_score);
+                        }
                     });
                 acc
             })
     }
 
     pub fn get_common_members_with(&self, other: &Leaderboard) -> Vec<&Entry> {
-        let other_members_ids = other.members_ids();
-        self.iter()
-            .filter(|entry| other_members_ids.contains(&entry.id.numeric))
+        self.get_entries_of_members(&other.members_ids())
+    }
+
+    pub fn get_entries_of_members(&self, members_ids: &HashSet<u64>) -> Vec<&Entry> {
+        members_ids
+            .iter()
+            .flat_map(|id| self.entries_at(self.by_member.get(id)))
             .collect::<Vec<&Entry>>()
     }
 
@@ -364,9 +596,7 @@ impl Leaderboard {
     ) -> HashMap<(u8, ProblemPart), (DateTime<Utc>, DateTime<Utc>)> {
         // Compute max time for each part, in order to infer deltas for members who only scored
         // one part of the global leaderboard that day.
-        self.iter()
-            .filter(|s| s.year == year)
-            .into_group_map_by(|s| (s.day, s.part))
+        self.entries_per_day_part_for_year(year)
             .into_iter()
             .map(
                 |(chal, entries)| match entries.iter().minmax_by_key(|s| s.timestamp) {
@@ -378,31 +608,30 @@ impl Leaderboard {
             .collect()
     }
 
+    /// Statistics of the global leaderboard of the day. They are partial while only one part has
+    /// been scraped: times of the missing part are left out, and so are deltas without part 1.
     pub fn statistics_for_year_day(&self, year: i32, day: u8) -> BotResult<LeaderboardStatistics> {
+        let challenge_start_time = Entry::puzzle_unlock(year, day)?;
         let minmax_by_part = self.min_max_times_for_year_day(year, day);
-        let (p1_fast, p1_slow) =
+        let times = |part: ProblemPart| {
             minmax_by_part
-                .get(&ProblemPart::FIRST)
-                .ok_or(BotError::Compute(
-                    "Could not retrieve minmax for part 1".to_string(),
-                ))?;
-        let (p2_fast, p2_slow) =
-            minmax_by_part
-                .get(&ProblemPart::SECOND)
-                .ok_or(BotError::Compute(
-                    "Could not retrieve minmax for part 2".to_string(),
-                ))?;
+                .get(&part)
+                .map(|(fast, slow)| (*fast - challenge_start_time, *slow - challenge_start_time))
+        };
+        let (p1_fast, p1_slow) = times(ProblemPart::FIRST).unzip();
+        let (p2_fast, p2_slow) = times(ProblemPart::SECOND).unzip();
 
-        let sorted_deltas = self.standings_by_delta_for_year_day(year, day)?;
+        // Deltas can not be computed without part 1.
+        let sorted_deltas = self
+            .standings_by_delta_for_year_day(year, day)
+            .unwrap_or_default();
         let mut sorted_deltas_iter = sorted_deltas.iter();
 
-        let challenge_start_time = Entry::puzzle_unlock(year, day)?;
-
         let stats = LeaderboardStatistics {
-            p1_fast: Some(*p1_fast - challenge_start_time),
-            p1_slow: Some(*p1_slow - challenge_start_time),
-            p2_fast: Some(*p2_fast - challenge_start_time),
-            p2_slow: Some(*p2_slow - challenge_start_time),
+            p1_fast,
+            p1_slow,
+            p2_fast,
+            p2_slow,
             delta_fast: sorted_deltas_iter
                 .next()
                 .and_then(|(_name, duration, rank)| Some((*duration, *rank))),
@@ -419,27 +648,85 @@ impl Leaderboard {
 
     /// (year, day, part) => (unordered) stars
     fn entries_per_year_day_part(&self) -> HashMap<(i32, u8, ProblemPart), Vec<&Entry>> {
-        self.iter().into_group_map_by(|a| (a.year, a.day, a.part))
+        self.by_year_day_part
+            .iter()
+            .map(|(challenge, positions)| (*challenge, self.entries_at(Some(positions)).collect()))
+            .collect()
     }
 
     fn entries_per_day_part_for_year(&self, year: i32) -> HashMap<(u8, ProblemPart), Vec<&Entry>> {
-        self.iter()
-            .filter(|e| e.year == year)
-            .into_group_map_by(|a| (a.day, a.part))
+        self.by_year_day_part
+            .iter()
+            .filter(|((y, _day, _part), _positions)| *y == year)
+            .map(|((_year, day, part), positions)| {
+                ((*day, *part), self.entries_at(Some(positions)).collect())
+            })
+            .collect()
     }
 
     pub fn entries_per_day_member_for_year(
         &self,
         year: i32,
     ) -> HashMap<(u8, &Identifier), Vec<&Entry>> {
-        self.iter()
-            .filter(|e| e.year == year)
+        self.entries_for_year(year)
             .into_group_map_by(|a| (a.day, &a.id))
     }
 
     /// all members ids
-    fn members_ids(&self) -> HashSet<u64> {
-        self.iter().map(|e| e.id.numeric).collect()
+    pub fn members_ids(&self) -> HashSet<u64> {
+        self.by_member.keys().copied().collect()
+    }
+
+    /// Adds the member to the roster of the year, e.g. a member of the private leaderboard who
+    /// has no star yet.
+    pub fn add_member(&mut self, year: i32, id: u64) {
+        self.roster.entry(year).or_default().insert(id);
+    }
+
+    /// Members of the year: those of the private leaderboard, with or without stars, and those
+    /// with entries for the year.
+    pub fn members_for_year(&self, year: i32) -> HashSet<u64> {
+        let mut members = self.roster.get(&year).cloned().unwrap_or_default();
+        members.extend(self.entries_for_year(year).map(|e| e.id.numeric));
+        members
+    }
+
+    /// Removes all the entries of the members, e.g. members who left the leaderboard, the
+    /// indices and cached scores being rebuilt.
+    pub fn remove_members(&mut self, ids: &HashSet<u64>) {
+        let entries = std::mem::take(&mut self.entries);
+        let mut previous_names = std::mem::take(&mut self.previous_names);
+        previous_names.retain(|id, _| !ids.contains(id));
+        let mut roster = std::mem::take(&mut self.roster);
+        roster
+            .values_mut()
+            .for_each(|members| members.retain(|id| !ids.contains(id)));
+        *self = Leaderboard::new();
+        self.extend(entries.into_iter().filter(|e| !ids.contains(&e.id.numeric)));
+        self.previous_names = previous_names;
+        self.roster = roster;
+    }
+
+    /// Former display names of the member, oldest first.
+    pub fn previous_names(&self, id: u64) -> &[Arc<str>] {
+        self.previous_names.get(&id).map_or(&[], |names| names)
+    }
+
+    /// Former display names of all the members, and members of the private leaderboard for each
+    /// year, e.g. handed off to the next process.
+    pub fn members_history(&self) -> (HashMap<u64, Vec<Arc<str>>>, HashMap<i32, HashSet<u64>>) {
+        (self.previous_names.clone(), self.roster.clone())
+    }
+
+    /// Restores the former display names and the members of each year, e.g. handed off by the
+    /// previous process, once the entries are inserted.
+    pub fn restore_members_history(
+        &mut self,
+        previous_names: HashMap<u64, Vec<Arc<str>>>,
+        roster: HashMap<i32, HashSet<u64>>,
+    ) {
+        self.previous_names = previous_names;
+        self.roster = roster;
     }
 
     /// (year, day, part) => [ordered members]
@@ -492,8 +779,7 @@ impl Leaderboard {
     ) -> HashMap<ProblemPart, (DateTime<Utc>, DateTime<Utc>)> {
         // Compute max time for each part, in order to infer deltas for members who only scored
         // one part of the global leaderboard that day.
-        self.iter()
-            .filter(|s| s.year == year && s.day == day)
+        self.entries_for_year_day(year, day)
             .into_group_map_by(|s| s.part)
             .iter()
             .map(
@@ -511,7 +797,7 @@ impl Leaderboard {
         &self,
         year: i32,
         day: u8,
-    ) -> BotResult<Vec<(&String, Duration, Option<u8>)>> {
+    ) -> BotResult<Vec<(&str, Duration, Option<GlobalRank>)>> {
         // We will use max time of part 1 to infer deltas for members who only scored
         // the second part on that day.
         let max_time_first_part = self
@@ -525,34 +811,26 @@ impl Leaderboard {
         let standings = self
             .entries_per_member_for_year_day(year, day)
             .into_iter()
-            .filter_map(|(id, solutions_for_day)| match solutions_for_day.len() {
-                1 => {
-                    // unwrap is safe as len == 1
-                    let entry = solutions_for_day.last().unwrap();
-                    match entry.part {
-                        ProblemPart::FIRST => None,
-                        ProblemPart::SECOND => {
-                            // Overtimed on first part, but came back strong to score second part
-                            // Duration is > (part.1, - max first part). We'll substract 1 sec.
-                            Some((
-                                &id.name,
-                                entry.timestamp - max_time_first_part - Duration::seconds(1),
-                                entry.rank,
-                            ))
-                        }
+            .filter_map(
+                |(id, solutions_for_day)| match daily_parts(&solutions_for_day) {
+                    (None, Some(second)) => {
+                        // Overtimed on first part, but came back strong to score second part
+                        // Duration is > (part.1, - max first part). We'll substract 1 sec.
+                        Some((
+                            &*id.name,
+                            second.timestamp - max_time_first_part - Duration::seconds(1),
+                            second.rank,
+                        ))
                     }
-                }
-                2 => {
-                    let mut ordered_parts = solutions_for_day.iter().sorted_by_key(|s| s.timestamp);
-                    // safe unwrap since len == 2
-                    let (first, second) =
-                        (ordered_parts.next().unwrap(), ordered_parts.next().unwrap());
-                    Some((&id.name, second.timestamp - first.timestamp, second.rank))
-                }
-                _ => unreachable!(),
-            })
-            .sorted_by_key(|r| r.1)
-            .collect::<Vec<(&String, Duration, Option<u8>)>>();
+                    (Some(first), Some(second)) => {
+                        Some((&*id.name, second.timestamp - first.timestamp, second.rank))
+                    }
+                    (_, None) => None,
+                },
+            )
+            // ties are ordered by name, for reproducible statistics
+            .sorted_by_key(|r| (r.1, r.0))
+            .collect::<Vec<(&str, Duration, Option<GlobalRank>)>>();
         Ok(standings)
     }
 }
@@ -561,13 +839,15 @@ impl Deref for Leaderboard {
     type Target = Entries;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.entries
     }
 }
 
-impl DerefMut for Leaderboard {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+impl Extend<Entry> for Leaderboard {
+    fn extend<T: IntoIterator<Item = Entry>>(&mut self, entries: T) {
+        for entry in entries {
+            self.insert(entry);
+        }
     }
 }
 
@@ -581,12 +861,68 @@ impl ScrapedLeaderboard {
 
     pub fn merge_with(&mut self, other: ScrapedLeaderboard) {
         self.timestamp = other.timestamp;
-        // TODO: if a member changes his/her name, this will be flagged as a new member ...
-        // We need to handle this by checking on unique id. Maybe replace the full year with updated
-        // leaderboard if we find duplicates for same id ?
+        // Renamed members get their new name on all their entries, see `Leaderboard::insert`.
+        self.leaderboard.extend(other.leaderboard.entries);
+        for (year, members) in other.leaderboard.roster {
+            self.leaderboard
+                .roster
+                .entry(year)
+                .or_default()
+                .extend(members);
+        }
+    }
+}
 
-        // Cloning the leaderboard is expensive, but this operation is only done every 15min
-        self.leaderboard
-            .extend(other.leaderboard.clone().into_iter());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Html;
+
+    // Entries of a global leaderboard page: a name without link, names linked to GitHub, and the
+    // badges of supporters and sponsors following the names.
+    const GLOBAL_ENTRIES: &'static str = r#"
+        <div class="leaderboard-entry" data-user-id="101"><span class="leaderboard-position">  1)</span> <span class="leaderboard-time">Dec 01  00:00:53</span> <span class="leaderboard-userphoto"></span>(λx.x)</div>
+        <div class="leaderboard-entry" data-user-id="102"><span class="leaderboard-position">  2)</span> <span class="leaderboard-time">Dec 01  00:01:02</span> <a href="https://github.com/foo" target="_blank"><span class="leaderboard-userphoto"></span>foo (bar)</a> <a href="/2023/support" class="supporter-badge" title="Advent of Code Supporter">(AoC++)</a></div>
+        <div class="leaderboard-entry" data-user-id="103"><span class="leaderboard-position">  3)</span> <span class="leaderboard-time">Dec 01  00:01:15</span> <a href="https://github.com/baz" target="_blank"><span class="leaderboard-userphoto"></span>baz</a> <a href="/2023/sponsors" class="sponsor-badge" title="Member of sponsor">(Sponsor)</a></div>
+    "#;
+
+    fn parse_entries(html: &str) -> Vec<Entry> {
+        let document = Html::parse_fragment(html);
+        let selector = Selector::parse(".leaderboard-entry").unwrap();
+        document
+            .select(&selector)
+            .filter_map(|entry| Entry::from_html(entry, 2023, 1, ProblemPart::FIRST))
+            .collect()
+    }
+
+    #[test]
+    fn from_html_keeps_names_with_parentheses() {
+        let entries = parse_entries(GLOBAL_ENTRIES);
+        let names = entries
+            .iter()
+            .map(|e| e.id.name.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["(λx.x)", "foo (bar)", "baz"]);
+    }
+
+    #[test]
+    fn from_html_skips_badges() {
+        let entries = parse_entries(GLOBAL_ENTRIES);
+        assert_eq!(entries.len(), 3);
+        for entry in entries.iter() {
+            assert!(!BADGE_LABELS.contains(&&*entry.id.name));
+        }
+        let ranks = entries
+            .iter()
+            .map(|e| (e.id.numeric, e.rank))
+            .collect::<Vec<(u64, Option<GlobalRank>)>>();
+        assert_eq!(
+            ranks,
+            vec![
+                (101, Some(GlobalRank(1))),
+                (102, Some(GlobalRank(2))),
+                (103, Some(GlobalRank(3))),
+            ]
+        );
     }
 }
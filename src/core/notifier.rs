@@ -0,0 +1,22 @@
+use crate::error::BotResult;
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// Announcement of the bot, with a structured payload so frontends are free to present it
+/// their own way, and the text rendered with the default templates.
+#[derive(Debug, Serialize)]
+pub struct Announcement {
+    // Name of the announcement, as listed in `MUTABLE_EVENTS`
+    pub name: &'static str,
+    pub payload: serde_json::Value,
+    pub text: String,
+}
+
+/// Frontend announcements are sent to, next to Slack (e.g. Discord, webhook, console).
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Name of the frontend, for logging.
+    fn name(&self) -> &'static str;
+
+    async fn notify(&self, announcement: &Announcement) -> BotResult<()>;
+}
@@ -0,0 +1,113 @@
+use crate::{
+    core::{
+        leaderboard::{Entry, Identifier, Leaderboard, ProblemPart},
+        source::PUZZLE_EVENT,
+        standings::{standings_board_iter, Scoring},
+    },
+    utils::now,
+};
+use itertools::Itertools;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Probability of each member to top the local standings of the year once all the days are
+/// released, most likely first, estimated with Monte Carlo runs of the remaining days.
+/// In each run, every member gets the outcome of a star released so far, drawn at random: the
+/// rank they finished it at, or a missed star. Empty if no day is left or none was released.
+pub fn win_probabilities(leaderboard: &Leaderboard, year: i32, runs: usize) -> Vec<(String, f64)> {
+    let now = now();
+    let days = PUZZLE_EVENT.days();
+    let remaining = (1..=days)
+        .filter(|day| Entry::puzzle_unlock(year, *day).is_ok_and(|unlock| unlock > now))
+        .count();
+    if runs == 0 || remaining == 0 || remaining == days as usize {
+        return vec![];
+    }
+
+    let scores = standings_board_iter(&Scoring::LOCAL, leaderboard, year)
+        .map(|(id, _scores, total)| (id, total))
+        .collect::<Vec<(&Identifier, usize)>>();
+    let history = rank_history(leaderboard, year);
+    let n_members = scores.len();
+
+    let mut rng = thread_rng();
+    let mut wins = vec![0; n_members];
+    for _ in 0..runs {
+        let mut totals = scores
+            .iter()
+            .map(|(_id, total)| *total)
+            .collect::<Vec<usize>>();
+        for _ in 0..PUZZLE_EVENT.parts() as usize * remaining {
+            // Members finishing the star, ordered by the rank drawn, ties broken at random
+            let mut finishers = scores
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, (id, _total))| {
+                    let rank = history.get(&id.numeric)?.choose(&mut rng).copied()??;
+                    Some((rank, rng.gen::<u32>(), idx))
+                })
+                .collect::<Vec<(usize, u32, usize)>>();
+            finishers.sort_unstable();
+            for (position, (_rank, _tiebreak, idx)) in finishers.into_iter().enumerate() {
+                totals[idx] += n_members - position;
+            }
+        }
+        // The first member in the standings wins ties, as when sorting the board
+        if let Some((winner, _total)) = totals
+            .iter()
+            .enumerate()
+            .max_by_key(|(idx, total)| (**total, Reverse(*idx)))
+        {
+            wins[winner] += 1;
+        }
+    }
+
+    scores
+        .iter()
+        .zip(wins)
+        .filter(|(_score, n_wins)| *n_wins > 0)
+        .map(|((id, _total), n_wins)| (id.name.to_string(), n_wins as f64 / runs as f64))
+        .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+        .collect()
+}
+
+/// For each member, the rank they finished each star released during the year at, `None` for
+/// the stars they missed.
+fn rank_history(leaderboard: &Leaderboard, year: i32) -> HashMap<u64, Vec<Option<usize>>> {
+    let now = now();
+    let ranks = leaderboard
+        .iter()
+        .filter(|e| e.year == year)
+        .into_group_map_by(|e| (e.day, e.part))
+        .into_values()
+        .flat_map(|entries| {
+            entries
+                .into_iter()
+                .sorted_unstable_by_key(|e| e.timestamp)
+                .enumerate()
+                .map(|(rank, e)| ((e.id.numeric, e.day, e.part), rank + 1))
+        })
+        .collect::<HashMap<(u64, u8, ProblemPart), usize>>();
+
+    let parts = [ProblemPart::FIRST, ProblemPart::SECOND]
+        .into_iter()
+        .take(PUZZLE_EVENT.parts() as usize)
+        .collect::<Vec<ProblemPart>>();
+    let released = (1..=PUZZLE_EVENT.days())
+        .filter(|day| Entry::puzzle_unlock(year, *day).is_ok_and(|unlock| unlock <= now))
+        .cartesian_product(parts)
+        .collect::<Vec<(u8, ProblemPart)>>();
+    leaderboard
+        .iter()
+        .filter(|e| e.year == year)
+        .map(|e| e.id.numeric)
+        .unique()
+        .map(|member| {
+            let outcomes = released
+                .iter()
+                .map(|(day, part)| ranks.get(&(member, *day, *part)).copied())
+                .collect();
+            (member, outcomes)
+        })
+        .collect()
+}
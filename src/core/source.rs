@@ -0,0 +1,216 @@
+use crate::{
+    config::{self, PuzzleEventKind},
+    core::leaderboard::{ScrapedLeaderboard, AOC_DAYS, AOC_MONTH, AOC_TIMEZONE},
+    error::{BotError, BotResult},
+    utils::now,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use futures::{stream, StreamExt};
+use once_cell::sync::Lazy;
+
+// Puzzle event followed by the bot, as configured.
+pub static PUZZLE_EVENT: Lazy<Box<dyn PuzzleEvent>> =
+    Lazy::new(|| match config::SETTINGS.get_puzzle_event() {
+        PuzzleEventKind::AOC => Box::new(AdventOfCode),
+        PuzzleEventKind::SCHEDULED => Box::new(ScheduledEvent::new()),
+    });
+
+/// Competition the bot follows: its puzzles, and when they are released. Puzzles fit in the daily
+/// arrays of the leaderboard, so events have at most `AOC_DAYS` days of at most 2 parts.
+pub trait PuzzleEvent: Send + Sync {
+    /// Name of the event, e.g. "Advent of Code".
+    fn name(&self) -> &str;
+
+    /// Number of puzzles of a year.
+    fn days(&self) -> u8;
+
+    /// Number of parts of each puzzle, each one giving a star.
+    fn parts(&self) -> u8;
+
+    /// Release time of the puzzle of a day.
+    fn unlock(&self, year: i32, day: u8) -> BotResult<DateTime<Utc>>;
+
+    /// Link to the puzzle of a day.
+    fn puzzle_url(&self, year: i32, day: u8) -> String;
+
+    /// Stars a member can earn in a year.
+    fn max_stars(&self) -> usize {
+        self.days() as usize * self.parts() as usize
+    }
+
+    /// Day of the season at a time, counted in UTC days from the day of the first release.
+    fn day_at(&self, year: i32, time: DateTime<Utc>) -> u8 {
+        self.unlock(year, 1).map_or(0, |first| {
+            (time.date_naive() - first.date_naive())
+                .num_days()
+                .saturating_add(1)
+                .clamp(0, u8::MAX.into()) as u8
+        })
+    }
+
+    /// Day of the month and month fields of a cron expression (UTC) running on each day of the
+    /// season of the current year, shifted by a number of days, e.g. "1-25 12" for Advent of
+    /// Code. Seasons over two months run on every day of both.
+    fn cron_days(&self, day_offset: i64) -> String {
+        let year = now().year();
+        match (self.unlock(year, 1), self.unlock(year, self.days())) {
+            (Ok(first), Ok(last)) => {
+                let first = first + Duration::days(day_offset);
+                let last = last + Duration::days(day_offset);
+                match first.month() == last.month() {
+                    true => format!("{}-{} {}", first.day(), last.day(), first.month()),
+                    false => format!("* {}-{}", first.month(), last.month()),
+                }
+            }
+            _ => "* *".to_string(),
+        }
+    }
+
+    /// Cron expression (UTC) of the time some offset after the release of each puzzle of the
+    /// season, e.g. "0 0 5 1-25 12 *" for the releases of Advent of Code.
+    fn release_cron(&self, offset: Duration) -> BotResult<String> {
+        let first = self.unlock(now().year(), 1)?;
+        let time = first + offset;
+        Ok(format!(
+            "{} {} {} {} *",
+            time.second(),
+            time.minute(),
+            time.hour(),
+            self.cron_days((time.date_naive() - first.date_naive()).num_days())
+        ))
+    }
+}
+
+/// Advent of Code: 25 puzzles of 2 parts, released at midnight AoC time from December 1st.
+pub struct AdventOfCode;
+
+impl PuzzleEvent for AdventOfCode {
+    fn name(&self) -> &str {
+        "Advent of Code"
+    }
+
+    fn days(&self) -> u8 {
+        AOC_DAYS
+    }
+
+    fn parts(&self) -> u8 {
+        2
+    }
+
+    fn unlock(&self, year: i32, day: u8) -> BotResult<DateTime<Utc>> {
+        // Problems are released at 00:00:00 AoC time (05:00:00 UTC in December)
+        AOC_TIMEZONE
+            .with_ymd_and_hms(year, AOC_MONTH, day.into(), 0, 0, 0)
+            .single()
+            .map(|unlock| unlock.with_timezone(&Utc))
+            .ok_or(BotError::Parse)
+    }
+
+    fn puzzle_url(&self, year: i32, day: u8) -> String {
+        format!("{}/{year}/day/{day}", config::SETTINGS.aoc_base_url)
+    }
+
+    // Days are the days of the month, whatever the month.
+    fn day_at(&self, _year: i32, time: DateTime<Utc>) -> u8 {
+        time.day() as u8
+    }
+}
+
+/// Other competition releasing a puzzle a day, e.g. a puzzle hunt within a company, as
+/// configured by the `event_*` settings.
+pub struct ScheduledEvent {
+    name: String,
+    days: u8,
+    parts: u8,
+    month: u32,
+    first_day: u32,
+    unlock_hour: u32,
+    timezone: Tz,
+    puzzle_url: Option<String>,
+}
+
+impl ScheduledEvent {
+    /// Event configured with the bot settings.
+    pub fn new() -> Self {
+        let settings = &config::SETTINGS;
+        Self {
+            name: settings.event_name.to_string(),
+            days: settings.event_days.clamp(1, AOC_DAYS),
+            parts: settings.event_parts.clamp(1, 2),
+            month: settings.event_month,
+            first_day: settings.event_first_day,
+            unlock_hour: settings.event_unlock_hour,
+            timezone: settings.event_timezone.parse().unwrap_or(Tz::UTC),
+            puzzle_url: settings.event_puzzle_url.clone(),
+        }
+    }
+}
+
+impl PuzzleEvent for ScheduledEvent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn days(&self) -> u8 {
+        self.days
+    }
+
+    fn parts(&self) -> u8 {
+        self.parts
+    }
+
+    fn unlock(&self, year: i32, day: u8) -> BotResult<DateTime<Utc>> {
+        // Days after the first one may fall in the next month.
+        self.timezone
+            .with_ymd_and_hms(year, self.month, self.first_day, self.unlock_hour, 0, 0)
+            .single()
+            .map(|first| (first + Duration::days(i64::from(day) - 1)).with_timezone(&Utc))
+            .ok_or(BotError::Parse)
+    }
+
+    fn puzzle_url(&self, year: i32, day: u8) -> String {
+        self.puzzle_url
+            .as_deref()
+            .unwrap_or_default()
+            .replace("{year}", &year.to_string())
+            .replace("{day}", &day.to_string())
+    }
+}
+
+/// Where the bot gets the leaderboards of the puzzle event from (e.g. the AoC website, fixture
+/// files).
+#[async_trait]
+pub trait AocSource: Send + Sync {
+    /// Name of the source, for logging.
+    fn name(&self) -> &'static str;
+
+    /// Whether the event has a global leaderboard, watched after each release.
+    fn has_global_leaderboard(&self) -> bool {
+        true
+    }
+
+    /// Private leaderboard of the year.
+    async fn fetch_private(&self, year: i32) -> BotResult<ScrapedLeaderboard>;
+
+    /// Global leaderboard of a day.
+    async fn fetch_global_day(&self, year: i32, day: u8) -> BotResult<ScrapedLeaderboard>;
+
+    /// Title of the puzzle of a day.
+    async fn fetch_puzzle_title(&self, year: i32, day: u8) -> BotResult<String>;
+
+    /// Private leaderboards of the years, fetched concurrently up to `concurrency` at a time.
+    /// Results come in order of completion.
+    async fn fetch_private_years(
+        &self,
+        years: Vec<i32>,
+        concurrency: usize,
+    ) -> Vec<(i32, BotResult<ScrapedLeaderboard>)> {
+        stream::iter(years)
+            .map(|year| async move { (year, self.fetch_private(year).await) })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await
+    }
+}
@@ -1,24 +1,32 @@
 use crate::{
-    core::leaderboard::{Entry, Identifier, Leaderboard},
-    utils::{current_year_day, exponential_decay, format_duration},
+    core::{
+        leaderboard::{daily_parts, Entry, Identifier, Leaderboard, ProblemPart, AOC_DAYS},
+        source::PUZZLE_EVENT,
+    },
+    utils::{current_year_day, exponential_decay, format_duration, now},
 };
-use chrono::Duration;
+use chrono::{Duration, TimeDelta, Timelike};
+use chrono_tz::Tz;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use std::{cmp::Reverse, collections::HashMap, fmt};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+    fmt,
+};
 
 // Time penalty added for TDF rankings if a day is not finished
 pub static PENALTY_UNFINISHED_DAY: Lazy<i64> = Lazy::new(|| Duration::days(7).num_seconds());
-const JERSEY_COLORS: [&'static str; 3] = ["yellow", "green", "combative"];
-const SCORING_METHODS: [&'static str; 2] = ["local", "stars"];
-const RANKING_METHODS: [&'static str; 4] = ["delta", "p1", "p2", "limit"];
+pub const JERSEY_COLORS: [&'static str; 3] = ["yellow", "green", "combative"];
+pub const SCORING_METHODS: [&'static str; 2] = ["local", "stars"];
+pub const RANKING_METHODS: [&'static str; 4] = ["delta", "p1", "p2", "limit"];
 
 // see https://en.wikipedia.org/wiki/Points_classification_in_the_Tour_de_France#Current
 const GREEN_JERSEY_POINTS: [u8; 15] = [50, 30, 20, 18, 16, 14, 12, 10, 8, 7, 6, 5, 4, 3, 2];
 const COMBATIVE_JERSEY_MAX_POINTS: f32 = 500.0;
 const COMBATIVE_JERSEY_POINTS_DECAY_RATE: f32 = 0.005;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub enum Scoring {
     LOCAL,
     STARS,
@@ -170,10 +178,21 @@ impl Standing<'_> {
         year: i32,
         day: u8,
     ) -> Vec<(String, String)> {
-        self.ranked_times_for_year_day(ranking_type, year, day)
-            .map(|(id, duration)| (id.name.clone(), format_duration(duration)))
+        self.by_time_iter(ranking_type, year, day)
+            .map(|(id, duration)| (id.name.to_string(), format_duration(duration)))
             .collect::<Vec<_>>()
     }
+
+    /// Members ranked by time for the day, fastest first, borrowing the identifiers.
+    pub fn by_time_iter<'a>(
+        &'a self,
+        ranking_type: &Ranking,
+        year: i32,
+        day: u8,
+    ) -> impl Iterator<Item = (&'a Identifier, Duration)> + 'a {
+        self.ranked_times_for_year_day(ranking_type, year, day)
+    }
+
     /// ordered vec of (id, time/points of interests, number of days of interest)
     pub fn tdf_season<'a: 'b, 'b>(
         &'a self,
@@ -234,11 +253,9 @@ impl Standing<'_> {
                             }
                         },
                     )
-                    // sort by total time ascending, then by number of penalties ascendings
-                    .sorted_unstable_by(|a, b| match a.1 == b.1 {
-                        true => a.2.cmp(&b.2),
-                        false => a.1.cmp(&b.1),
-                    })
+                    // sort by total time ascending, then by number of penalties ascendings,
+                    // then by member so ties are always shown in the same order
+                    .sorted_unstable_by(|a, b| a.1.cmp(&b.1).then(a.2.cmp(&b.2)).then(a.0.cmp(b.0)))
                     .collect::<Vec<(&Identifier, i64, i64)>>();
                 standings
             }
@@ -257,8 +274,8 @@ impl Standing<'_> {
                     daily_delta
                         .into_iter()
                         .map(|(_day, id, delta)| (id, delta))
-                        // sort by delta time ascending
-                        .sorted_unstable_by(|a, b| a.1.cmp(&b.1))
+                        // sort by delta time ascending, then by member
+                        .sorted_unstable_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)))
                         .zip(GREEN_JERSEY_POINTS.into_iter())
                         .map(|((id, _delta), points)| (id, day, points))
                         .collect::<Vec<(&Identifier, u8, u8)>>()
@@ -275,11 +292,9 @@ impl Standing<'_> {
                     })
                     .into_iter()
                     .map(|(id, (total_points, n_days))| (id, total_points, n_days))
-                    // sort by total points descending, then by number of scored days descendings
-                    .sorted_unstable_by(|a, b| match a.1 == b.1 {
-                        true => b.2.cmp(&a.2),
-                        false => b.1.cmp(&a.1),
-                    })
+                    // sort by total points descending, then by number of scored days descendings,
+                    // then by member
+                    .sorted_unstable_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(b.0)))
                     .collect::<Vec<(&Identifier, i64, i64)>>()
             }
             // returns Vec<(id, total earned points, number of stages with earned points)>
@@ -309,17 +324,67 @@ impl Standing<'_> {
                     .map(|(id, (total_points, scored_days))| {
                         (*id, *total_points as i64, *scored_days as i64)
                     })
-                    // sort by total points descending, then by number of scored_days descendings
-                    .sorted_unstable_by(|a, b| match a.1 == b.1 {
-                        true => b.2.cmp(&a.2),
-                        false => b.1.cmp(&a.1),
-                    })
+                    // sort by total points descending, then by number of scored_days descendings,
+                    // then by member
+                    .sorted_unstable_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(b.0)))
                     .collect::<Vec<(&Identifier, i64, i64)>>();
                 standings
             }
         }
     }
 
+    /// Head-to-head rivalries for the year, based on the full (part 2) solve time of the days
+    /// completed by both members. Ordered vec of (winner, loser, days won, days lost).
+    pub fn rivalries(&self, year: i32) -> Vec<(&Identifier, &Identifier, usize, usize)> {
+        let times_per_day = self
+            .leaderboard
+            .entries_per_day_member_for_year(year)
+            .into_iter()
+            .filter_map(|((day, id), entries_for_day)| {
+                Standing::get_time_for_part(&entries_for_day, Ranking::PART2)
+                    .and_then(|duration| Some((day, id, duration)))
+            })
+            .into_group_map_by(|(day, _id, _duration)| *day);
+
+        times_per_day
+            .values()
+            .flat_map(|times| times.iter().tuple_combinations())
+            .fold(
+                HashMap::new(),
+                |mut acc, ((_, a, time_a), (_, b, time_b))| {
+                    // pairs are keyed in a consistent order
+                    let ((first, time_first), (second, time_second)) = match a < b {
+                        true => ((*a, time_a), (*b, time_b)),
+                        false => ((*b, time_b), (*a, time_a)),
+                    };
+                    let wins = acc.entry((first, second)).or_insert((0, 0));
+                    match time_first.cmp(time_second) {
+                        Ordering::Less => wins.0 += 1,
+                        Ordering::Greater => wins.1 += 1,
+                        Ordering::Equal => (),
+                    };
+                    acc
+                },
+            )
+            .into_iter()
+            .filter_map(|((first, second), (first_wins, second_wins))| {
+                match first_wins.cmp(&second_wins) {
+                    Ordering::Greater => Some((first, second, first_wins, second_wins)),
+                    Ordering::Less => Some((second, first, second_wins, first_wins)),
+                    // no dominance
+                    Ordering::Equal => None,
+                }
+            })
+            // most one-sided rivalries first
+            .sorted_unstable_by(|a, b| {
+                (b.2 - b.3)
+                    .cmp(&(a.2 - a.3))
+                    .then(a.0.cmp(b.0))
+                    .then(a.1.cmp(b.1))
+            })
+            .collect::<Vec<_>>()
+    }
+
     // /// ordered vec of (id, total duration, days over the cut off)
     // pub fn tdf_season_0<'a: 'b, 'b>(
     //     &'a self,
@@ -416,71 +481,41 @@ impl Standing<'_> {
                 Ranking::LIMIT => Self::compute_time_before_next_release(&entries_for_day)
                     .and_then(|duration| Some((id, duration))),
             })
-            .sorted_unstable_by(|a, b| a.1.cmp(&b.1))
+            // ties are ordered by member, for reproducible rankings
+            .sorted_unstable_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)))
     }
 
     fn compute_delta(daily_entries: &Vec<&Entry>) -> Option<Duration> {
-        match daily_entries.len() {
-            2 => {
-                let mut ordered_parts =
-                    daily_entries.iter().sorted_unstable_by_key(|s| s.timestamp);
-                // safe unwrap since len == 2
-                let (first, second) =
-                    (ordered_parts.next().unwrap(), ordered_parts.next().unwrap());
-                Some(second.timestamp - first.timestamp)
-            }
-            1 => None,
-            _ => unreachable!(),
+        match daily_parts(daily_entries) {
+            (Some(first), Some(second)) => Some(second.timestamp - first.timestamp),
+            _ => None,
         }
     }
 
     fn compute_time_before_next_release(daily_entries: &Vec<&Entry>) -> Option<Duration> {
-        match daily_entries.len() {
-            2 => {
-                let ordered_parts = daily_entries.iter().sorted_unstable_by_key(|s| s.timestamp);
-                ordered_parts.last().and_then(|e| {
-                    Entry::puzzle_unlock(e.year, e.day)
-                        .ok()
-                        .and_then(|puzzle_release_time| {
-                            let next_release = puzzle_release_time + Duration::hours(24);
-                            let remaining_time_before_next_release = next_release - e.timestamp;
-                            match remaining_time_before_next_release > Duration::seconds(0) {
-                                true => Some(remaining_time_before_next_release),
-                                false => None,
-                            }
-                        })
-                })
-            }
+        match daily_parts(daily_entries) {
+            (Some(_first), Some(second)) => Entry::puzzle_unlock(second.year, second.day)
+                .ok()
+                .and_then(|puzzle_release_time| {
+                    let next_release = puzzle_release_time + Duration::hours(24);
+                    let remaining_time_before_next_release = next_release - second.timestamp;
+                    match remaining_time_before_next_release > Duration::seconds(0) {
+                        true => Some(remaining_time_before_next_release),
+                        false => None,
+                    }
+                }),
             _ => None,
         }
     }
 
     fn get_time_for_part(daily_entries: &Vec<&Entry>, part: Ranking) -> Option<Duration> {
-        match (daily_entries.len(), part) {
-            (2, Ranking::PART1) => {
-                let ordered_parts = daily_entries.iter().sorted_unstable_by_key(|s| s.timestamp);
-                // safe unwrap since len == 2
-                Some(
-                    ordered_parts
-                        .map(|e| e.duration_since_release().unwrap())
-                        .next()
-                        .unwrap(),
-                )
-            }
-            (2, Ranking::PART2) => {
-                let ordered_parts = daily_entries.iter().sorted_unstable_by_key(|s| s.timestamp);
-                // safe unwrap since len == 2
-                Some(
-                    ordered_parts
-                        .map(|e| e.duration_since_release().unwrap())
-                        .last()
-                        .unwrap(),
-                )
-            }
-            (1, Ranking::PART1) => Some(daily_entries[0].duration_since_release().unwrap()),
-            (1, Ranking::PART2) => None, // did not finished part 2
+        let entry = match (daily_parts(daily_entries), part) {
+            ((first, second), Ranking::PART1) => first.or(second),
+            ((Some(_first), second), Ranking::PART2) => second,
+            ((None, _second), Ranking::PART2) => None, // did not finished part 1
             _ => unreachable!(),
-        }
+        };
+        entry.and_then(|e| e.duration_since_release().ok())
     }
 
     fn compute_combative_points(remaining_time: i32) -> usize {
@@ -529,9 +564,57 @@ pub fn standings_board<'a, 'b>(
     leaderboard: &'a Leaderboard,
     year: i32,
 ) -> Vec<(&'a Identifier, [(u8, usize); 25], usize)> {
-    let scores = leaderboard.daily_stars_and_scores_per_member_for_year(year);
-    let entries = scores
+    standings_board_iter(score_type, leaderboard, year)
+        .map(|(id, scores, total)| (id, *scores, total))
+        .collect::<Vec<_>>()
+}
+
+/// Same rows as `standings_board`, members tied on points or stars being ordered by their total
+/// solve time instead: the time from the release of each day they solved to their last star of
+/// the day, which rewards consistency.
+pub fn standings_board_by_time<'a, 'b>(
+    score_type: &'b Scoring,
+    leaderboard: &'a Leaderboard,
+    year: i32,
+) -> Vec<(&'a Identifier, [(u8, usize); 25], usize)> {
+    let solve_times = leaderboard
+        .iter()
+        .filter(|e| e.year == year)
+        .into_group_map_by(|e| (e.id.numeric, e.day))
         .into_iter()
+        .filter_map(|((member, _day), entries)| {
+            let last = entries.into_iter().max_by_key(|e| e.timestamp)?;
+            Some((member, last.duration_since_release().ok()?))
+        })
+        .fold(HashMap::new(), |mut totals, (member, time)| {
+            let total = totals.entry(member).or_insert_with(Duration::zero);
+            *total = *total + time;
+            totals
+        });
+    let mut board = standings_board(score_type, leaderboard, year);
+    // stable sort, members tied on time too keep their order
+    board.sort_by_key(|(id, _scores, total)| {
+        (
+            Reverse(*total),
+            solve_times
+                .get(&id.numeric)
+                .copied()
+                .unwrap_or(TimeDelta::MAX),
+        )
+    });
+    board
+}
+
+/// Same rows as `standings_board`, borrowing the daily scores from the leaderboard cache.
+/// Only the ordering is buffered, rows are yielded as they are consumed.
+pub fn standings_board_iter<'a>(
+    score_type: &Scoring,
+    leaderboard: &'a Leaderboard,
+    year: i32,
+) -> impl Iterator<Item = (&'a Identifier, &'a [(u8, usize); 25], usize)> {
+    let score_type = *score_type;
+    leaderboard
+        .iter_daily_stars_and_scores_for_year(year)
         .map(|(id, scores)| {
             // we compute total score, and total number of stars
             (
@@ -543,18 +626,150 @@ pub fn standings_board<'a, 'b>(
                 }),
             )
         })
-        .sorted_unstable_by_key(|entry| match score_type {
-            // sort by score descending, then by number of stars descending
-            Scoring::LOCAL => (Reverse(entry.2 .1), Reverse(entry.2 .0)),
-            // sort by number of stars descending, then by score descending
-            Scoring::STARS => (Reverse(entry.2 .0), Reverse(entry.2 .1)),
+        .sorted_unstable_by_key(move |entry| match score_type {
+            // sort by score descending, then by number of stars descending, then by member
+            Scoring::LOCAL => (Reverse(entry.2 .1), Reverse(entry.2 .0), entry.0),
+            // sort by number of stars descending, then by score descending, then by member
+            Scoring::STARS => (Reverse(entry.2 .0), Reverse(entry.2 .1), entry.0),
         })
         .map(
-            |(id, scores, (total_stars, total_score))| match score_type {
+            move |(id, scores, (total_stars, total_score))| match score_type {
                 Scoring::LOCAL => (id, scores, total_score),
                 Scoring::STARS => (id, scores, total_stars),
             },
         )
-        .collect::<Vec<_>>();
-    entries
+}
+
+/// (position, total stars, total score) of a member in the local standings board
+pub fn member_standing<'a>(
+    board: impl IntoIterator<Item = (&'a Identifier, &'a [(u8, usize); 25], usize)>,
+    name: &str,
+) -> Option<(usize, usize, usize)> {
+    board
+        .into_iter()
+        .find_position(|(id, _scores, _total)| &*id.name == name)
+        .map(|(idx, (_id, scores, total))| {
+            let stars = scores.iter().map(|(n, _)| *n as usize).sum();
+            (idx + 1, stars, total)
+        })
+}
+
+/// (member, best, median, worst) time to get the first star of a day from its release, over the
+/// days of the year each member started, lowest median first.
+pub fn first_star_latencies(
+    leaderboard: &Leaderboard,
+    year: i32,
+) -> Vec<(&Identifier, Duration, Duration, Duration)> {
+    leaderboard
+        .iter()
+        .filter(|e| e.year == year && e.part == ProblemPart::FIRST)
+        .filter_map(|e| Some((&e.id, e.duration_since_release().ok()?)))
+        .into_group_map()
+        .into_iter()
+        .filter_map(|(id, latencies)| {
+            let latencies = latencies.into_iter().sorted().collect::<Vec<Duration>>();
+            let n = latencies.len();
+            let median = match n % 2 {
+                0 => (*latencies.get(n / 2 - 1)? + latencies[n / 2]) / 2,
+                _ => latencies[n / 2],
+            };
+            Some((id, *latencies.first()?, median, *latencies.last()?))
+        })
+        .sorted_by_key(|(id, _best, median, _worst)| (*median, *id))
+        .collect()
+}
+
+/// Stars of a member during a year by hour of the day they got them at, in their timezone.
+pub fn solve_hours(leaderboard: &Leaderboard, year: i32, member: u64, timezone: Tz) -> [usize; 24] {
+    leaderboard
+        .iter()
+        .filter(|e| e.year == year && e.id.numeric == member)
+        .fold([0; 24], |mut hours, e| {
+            hours[e.timestamp.with_timezone(&timezone).hour() as usize] += 1;
+            hours
+        })
+}
+
+/// (member, puzzles) solved within the window after their release during a year, by the members
+/// who did it at least once, most puzzles first. Puzzles are solved with their second star.
+pub fn solved_within(
+    leaderboard: &Leaderboard,
+    year: i32,
+    window: Duration,
+) -> Vec<(&Identifier, usize)> {
+    leaderboard
+        .iter()
+        .filter(|e| e.year == year && e.part == ProblemPart::SECOND)
+        .filter(|e| e.duration_since_release().is_ok_and(|time| time <= window))
+        .counts_by(|e| &e.id)
+        .into_iter()
+        .sorted_by_key(|(id, count)| (Reverse(*count), *id))
+        .collect()
+}
+
+/// (average time to get both stars of a day, share of the released stars obtained) by the
+/// members of a private leaderboard for a year, to compare leaderboards.
+pub fn board_statistics(leaderboard: &Leaderboard, year: i32) -> (Option<Duration>, f64) {
+    let entries = leaderboard
+        .iter()
+        .filter(|e| e.year == year)
+        .collect::<Vec<&Entry>>();
+
+    let solve_times = entries
+        .iter()
+        .filter(|e| e.part == ProblemPart::SECOND)
+        .filter_map(|e| e.duration_since_release().ok())
+        .collect::<Vec<Duration>>();
+    let average = (!solve_times.is_empty()).then(|| {
+        solve_times.iter().fold(Duration::zero(), |acc, d| acc + *d) / solve_times.len() as i32
+    });
+
+    let now = now();
+    let released_days = (1..=PUZZLE_EVENT.days())
+        .filter(|day| Entry::puzzle_unlock(year, *day).is_ok_and(|unlock| unlock <= now))
+        .count();
+    let members = entries.iter().map(|e| e.id.numeric).unique().count();
+    let completion = match members * released_days {
+        0 => 0.0,
+        released => entries.len() as f64 / (PUZZLE_EVENT.parts() as usize * released) as f64,
+    };
+    (average, completion)
+}
+
+/// Private leaderboards merged into one for a year, keeping the completions of the days given.
+/// Members of several leaderboards are counted once, so scores are computed over the union.
+pub fn merged_board(boards: &[&Leaderboard], year: i32, days: &[u8]) -> Leaderboard {
+    let mut merged = Leaderboard::new();
+    merged.extend(
+        boards
+            .iter()
+            .flat_map(|board| board.iter())
+            .filter(|e| e.year == year && days.contains(&e.day))
+            .cloned(),
+    );
+    merged
+}
+
+/// Private leaderboard restricted to a group of members, so that scores are computed within
+/// the group.
+pub fn group_board(leaderboard: &Leaderboard, members: &[u64]) -> Leaderboard {
+    let mut grouped = Leaderboard::new();
+    grouped.extend(
+        leaderboard
+            .iter()
+            .filter(|e| members.contains(&e.id.numeric))
+            .cloned(),
+    );
+    grouped
+}
+
+/// Days of a year with completions on each of the leaderboards, in order.
+pub fn shared_days(boards: &[&Leaderboard], year: i32) -> Vec<u8> {
+    (1..=AOC_DAYS)
+        .filter(|day| {
+            boards
+                .iter()
+                .all(|board| board.iter().any(|e| e.year == year && e.day == *day))
+        })
+        .collect()
 }
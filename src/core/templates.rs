@@ -1,27 +1,258 @@
 use crate::{
-    core::leaderboard::Entry,
-    utils::{current_year_day, format_rank},
+    config,
+    core::{i18n::Language, identities, leaderboard::Entry, themes::Theme},
+    utils::{current_year_day, escape_slack, format_rank, now},
 };
-use chrono::{Duration, Utc};
-use minijinja::{Environment, Template};
+use chrono::Duration;
+use minijinja::{value::Value, Environment, Output, State};
 use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{fmt, fs, path::Path, sync::RwLock};
 use strum::{EnumIter, IntoEnumIterator};
-use tracing::info;
+use tracing::{error, info};
 
-static TEMPLATES_ENVIRONMENT: Lazy<Environment> = Lazy::new(|| {
+// Sub-directory of the templates directory holding the template sets
+const TEMPLATE_SETS_DIR: &'static str = "sets";
+
+// Name of the built-in template set holding the compact version of the templates
+const COMPACT_TEMPLATE_SET: &'static str = "compact";
+const VERBOSITY_MODES: [&'static str; 2] = ["compact", "verbose"];
+
+// Guarded by a lock so the templates can be swapped at runtime on hot reload.
+static TEMPLATES_ENVIRONMENT: Lazy<RwLock<Environment<'static>>> =
+    Lazy::new(|| RwLock::new(build_environment()));
+
+fn build_environment() -> Environment<'static> {
     info!("Initializing templating engine environment.");
+    let settings = &config::SETTINGS;
     let mut env = Environment::new();
+    // All interpolated values are escaped for Slack, unless marked as safe.
+    env.set_formatter(slack_formatter);
+    // Emoji and decorations of the configured theme.
+    env.add_global("theme", Value::from_serialize(Theme::current().table()));
+    // Mention of the Slack user of an AoC member, e.g. `{{ name|mention }}`.
+    env.add_filter("mention", identities::mention);
+
+    // Use strum to iterate over the variants of the enums.
+    for language in Language::iter() {
+        for template in MessageTemplate::iter() {
+            let name = language.template_name(&template);
 
-    // Use strum to iterate over the variants of the enum.
-    for template in MessageTemplate::iter() {
-        env.add_template(template.name(), template.template())
-            .unwrap();
+            // Operators can override any template by dropping a file with the same name in the
+            // templates directory. Invalid overrides fall back to the built-in template.
+            let overridden = settings
+                .templates_dir
+                .as_ref()
+                .and_then(|dir| read_override(dir, &name))
+                .and_then(
+                    |source| match env.add_template_owned(name.clone(), source) {
+                        Ok(_) => {
+                            info!("Template '{name}' overridden from templates directory.");
+                            Some(())
+                        }
+                        Err(e) => {
+                            error!("Invalid template override '{name}': {e}");
+                            None
+                        }
+                    },
+                );
+
+            // Untranslated templates are not registered, and will fall back to english.
+            if let (None, Some(source)) = (overridden, language.template(&template)) {
+                env.add_template_owned(name.clone(), source).unwrap();
+            }
+
+            // Compact templates are registered as a built-in template set.
+            if let Some(source) = language.compact_template(&template) {
+                env.add_template_owned(template_set_name(COMPACT_TEMPLATE_SET, &name), source)
+                    .unwrap();
+            }
+
+            // Template sets are layered on top of the default templates, and only need to
+            // define the templates they want to customize.
+            for (set, set_dir) in template_sets(settings.templates_dir.as_deref()) {
+                if let Some(source) = read_override(&set_dir, &name) {
+                    let set_name = template_set_name(&set, &name);
+                    match env.add_template_owned(set_name.clone(), source) {
+                        Ok(_) => info!("Template '{set_name}' loaded from template set."),
+                        Err(e) => error!("Invalid template '{set_name}': {e}"),
+                    }
+                }
+            }
+        }
     }
 
     info!("Templates loaded in templating engine environment.");
 
     env
-});
+}
+
+/// Escape interpolated strings so user-controlled values (member names, titles, ...) cannot
+/// corrupt the message formatting or create mentions. Values marked as safe are left untouched.
+fn slack_formatter(
+    out: &mut Output,
+    _state: &State,
+    value: &Value,
+) -> Result<(), minijinja::Error> {
+    match (value.is_safe(), value.as_str()) {
+        (false, Some(s)) => out.write_str(&escape_slack(s)),
+        _ => write!(out, "{value}"),
+    }
+    .map_err(minijinja::Error::from)
+}
+
+fn read_override(dir: &str, name: &str) -> Option<String> {
+    let path = Path::new(dir).join(name);
+    match path.exists() {
+        true => fs::read_to_string(&path)
+            .map_err(|e| error!("Could not read template override {:?}: {e}", path))
+            .ok(),
+        false => None,
+    }
+}
+
+/// (name, directory) of the template sets defined in the templates directory.
+fn template_sets(templates_dir: Option<&str>) -> Vec<(String, String)> {
+    templates_dir
+        .and_then(|dir| fs::read_dir(Path::new(dir).join(TEMPLATE_SETS_DIR)).ok())
+        .map_or(vec![], |entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| {
+                    (
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path().to_string_lossy().to_string(),
+                    )
+                })
+                .collect()
+        })
+}
+
+fn template_set_name(set: &str, name: &str) -> String {
+    format!("{set}:{name}")
+}
+
+/// Rebuild the templating environment, picking up any change in the templates directory.
+pub fn reload_templates() {
+    let env = build_environment();
+    *TEMPLATES_ENVIRONMENT.write().unwrap() = env;
+}
+
+#[derive(Debug, Clone)]
+pub enum Verbosity {
+    COMPACT,
+    VERBOSE,
+}
+
+impl Verbosity {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            mode if mode == VERBOSITY_MODES[0] => Some(Verbosity::COMPACT),
+            mode if mode == VERBOSITY_MODES[1] => Some(Verbosity::VERBOSE),
+            _ => None,
+        }
+    }
+
+    /// Verbosity configured for the given channel. Default is verbose.
+    pub fn for_channel(channel_id: &str) -> Self {
+        let settings = &config::SETTINGS;
+        settings
+            .slack_channels_verbosity
+            .as_ref()
+            .and_then(|modes| modes.get(channel_id))
+            .and_then(|mode| Verbosity::from_string(mode))
+            .unwrap_or(Verbosity::VERBOSE)
+    }
+
+    /// Maximum number of rows shown in rankings, if limited.
+    pub fn max_rows(&self) -> Option<usize> {
+        match self {
+            Verbosity::COMPACT => Some(3),
+            Verbosity::VERBOSE => None,
+        }
+    }
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verbosity::COMPACT => {
+                write!(f, "{}", VERBOSITY_MODES[0])
+            }
+            Verbosity::VERBOSE => {
+                write!(f, "{}", VERBOSITY_MODES[1])
+            }
+        }
+    }
+}
+
+/// How a message should be rendered: language, template set, verbosity and page of long outputs.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub language: Language,
+    pub template_set: Option<String>,
+    pub verbosity: Verbosity,
+    pub page: usize,
+    pub page_size: Option<usize>,
+}
+
+impl RenderOptions {
+    /// Rendering options configured for the given channel.
+    pub fn for_channel(channel_id: &str) -> Self {
+        let settings = &config::SETTINGS;
+        RenderOptions {
+            language: Language::for_channel(channel_id),
+            template_set: settings
+                .slack_channels_template_set
+                .as_ref()
+                .and_then(|sets| sets.get(channel_id))
+                .cloned(),
+            verbosity: Verbosity::for_channel(channel_id),
+            page: 0,
+            page_size: settings.slack_page_size,
+        }
+    }
+
+    pub fn with_page(self, page: usize) -> Self {
+        RenderOptions { page, ..self }
+    }
+
+    /// (rows to skip, max rows to show) of long outputs, depending on verbosity and page.
+    pub fn visible_rows(&self) -> (usize, Option<usize>) {
+        match (self.verbosity.max_rows(), self.page_size) {
+            (Some(n), _) => (0, Some(n)),
+            (None, Some(size)) if size > 0 => (self.page * size, Some(size)),
+            (None, _) => (0, None),
+        }
+    }
+
+    /// Row of the caller added below the top rows of compact outputs, if ranked lower.
+    pub fn shown_caller_row(&self, caller_row: Option<usize>) -> Option<usize> {
+        let top = self.verbosity.max_rows()?;
+        caller_row.filter(|row| *row >= top)
+    }
+
+    /// Number of pages needed to display the given number of rows.
+    pub fn n_pages(&self, n_rows: usize) -> usize {
+        match (self.verbosity.max_rows(), self.page_size) {
+            (None, Some(size)) if size > 0 => std::cmp::max(1, (n_rows + size - 1) / size),
+            _ => 1,
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            language: Language::default(),
+            template_set: None,
+            verbosity: Verbosity::VERBOSE,
+            page: 0,
+            page_size: None,
+        }
+    }
+}
 
 #[derive(EnumIter)]
 pub enum MessageTemplate {
@@ -34,12 +265,47 @@ pub enum MessageTemplate {
     HardChallenge,
     PrivateLeaderboardUpdated,
     LeaderboardMemberJoin,
+    LeaderboardMemberLeave,
+    LeaderboardMemberRename,
+    AocStatus,
+    FirstBlood,
+    EveryoneFinished,
+    StarMilestone,
+    SeasonCompleted,
+    Spotlight,
+    DailyStandings,
+    GitHubSolution,
     NewEntriesToday,
     NewEntriesLate,
     TdfStandings,
     Ranking,
     LeaderboardDisplay,
     Hero,
+    RivalryGraph,
+    Iam,
+    Me,
+    Nudge,
+    NudgeReminder,
+    Replay,
+    LeaderTopic,
+    Countdown,
+    AppHome,
+    PuzzleUnfurl,
+    Welcome,
+    Snooze,
+    Unsnooze,
+    SnoozeEnded,
+    WeeklyDigest,
+    Audit,
+    Heroes,
+    Quiet,
+    IdentityVerified,
+    Approve,
+    Versus,
+    Grouped,
+    Difficulty,
+    Early,
+    GitHub,
 }
 
 impl MessageTemplate {
@@ -52,6 +318,16 @@ impl MessageTemplate {
             MessageTemplate::DailySummary => "summary.txt",
             MessageTemplate::PrivateLeaderboardUpdated => "private_leaderboard_updated.txt",
             MessageTemplate::LeaderboardMemberJoin => "private_leaderboard_new_members.txt",
+            MessageTemplate::LeaderboardMemberLeave => "private_leaderboard_members_left.txt",
+            MessageTemplate::LeaderboardMemberRename => "private_leaderboard_members_renamed.txt",
+            MessageTemplate::AocStatus => "aoc_status.txt",
+            MessageTemplate::FirstBlood => "first_blood.txt",
+            MessageTemplate::EveryoneFinished => "everyone_finished.txt",
+            MessageTemplate::StarMilestone => "star_milestone.txt",
+            MessageTemplate::SeasonCompleted => "season_completed.txt",
+            MessageTemplate::Spotlight => "spotlight.txt",
+            MessageTemplate::DailyStandings => "daily_standings.txt",
+            MessageTemplate::GitHubSolution => "github_solution.txt",
             MessageTemplate::NewEntriesToday => "today_entries.txt",
             MessageTemplate::NewEntriesLate => "late_entries.txt",
             MessageTemplate::GlobalStatistics => "global_leaderboard_statistics.txt",
@@ -60,11 +336,71 @@ impl MessageTemplate {
             MessageTemplate::TdfStandings => "tdf.txt",
             MessageTemplate::LeaderboardDisplay => "leaderboard.txt",
             MessageTemplate::Hero => "hero.txt",
+            MessageTemplate::RivalryGraph => "rivalry.txt",
+            MessageTemplate::Iam => "iam.txt",
+            MessageTemplate::Me => "me.txt",
+            MessageTemplate::Nudge => "nudge.txt",
+            MessageTemplate::NudgeReminder => "nudge_reminder.txt",
+            MessageTemplate::Replay => "replay.txt",
+            MessageTemplate::LeaderTopic => "leader_topic.txt",
+            MessageTemplate::Countdown => "countdown.txt",
+            MessageTemplate::AppHome => "app_home.txt",
+            MessageTemplate::PuzzleUnfurl => "puzzle_unfurl.txt",
+            MessageTemplate::Welcome => "welcome.txt",
+            MessageTemplate::Snooze => "snooze.txt",
+            MessageTemplate::Unsnooze => "unsnooze.txt",
+            MessageTemplate::SnoozeEnded => "snooze_ended.txt",
+            MessageTemplate::WeeklyDigest => "weekly_digest.txt",
+            MessageTemplate::Audit => "audit.txt",
+            MessageTemplate::Heroes => "heroes.txt",
+            MessageTemplate::Quiet => "quiet.txt",
+            MessageTemplate::IdentityVerified => "identity_verified.txt",
+            MessageTemplate::Approve => "approve.txt",
+            MessageTemplate::Versus => "versus.txt",
+            MessageTemplate::Grouped => "grouped.txt",
+            MessageTemplate::Difficulty => "difficulty.txt",
+            MessageTemplate::Early => "early.txt",
+            MessageTemplate::GitHub => "github.txt",
         }
     }
 
-    pub fn get(&self) -> Template<'_, '_> {
-        TEMPLATES_ENVIRONMENT.get_template(self.name()).unwrap()
+    pub fn render<S: Serialize>(&self, ctx: S) -> Result<String, minijinja::Error> {
+        self.render_with(&RenderOptions::default(), ctx)
+    }
+
+    /// Render the template with the given options. Templates are looked up for each language
+    /// of the fallback chain, first in the template set, then in the compact templates if
+    /// needed, and finally in the default templates.
+    pub fn render_with<S: Serialize>(
+        &self,
+        options: &RenderOptions,
+        ctx: S,
+    ) -> Result<String, minijinja::Error> {
+        let env = TEMPLATES_ENVIRONMENT.read().unwrap();
+        let template = options
+            .language
+            .fallback_chain()
+            .iter()
+            .find_map(|lang| {
+                let name = lang.template_name(self);
+                let layers = [
+                    options.template_set.as_deref(),
+                    match options.verbosity {
+                        Verbosity::COMPACT => Some(COMPACT_TEMPLATE_SET),
+                        Verbosity::VERBOSE => None,
+                    },
+                ];
+                layers
+                    .iter()
+                    .flatten()
+                    .find_map(|set| env.get_template(&template_set_name(set, &name)).ok())
+                    .or_else(|| env.get_template(&name).ok())
+            })
+            .map_or_else(|| env.get_template(self.name()), Ok)?;
+        template.render(ctx).map_err(|e| {
+            error!("Could not render template '{}': {e}", template.name());
+            e
+        })
     }
 
     pub fn template(&self) -> &'static str {
@@ -87,9 +423,11 @@ impl MessageTemplate {
                 with the `limit` method (those times are used to attribute points for the `!tdf combative` jersey). \
                 If no day and/or year is set, the current day/or year is automatically defined.`\n\n\
                 👉 📊 *Show me the board!*\n\
-                ```!board [ranking method] [year]```\n\
+                ```!board [ranking method] [year] [--by time]```\n\
                 Current score and stars completion for the year, shown as a neat ascii board. Default is ranking by `local` \
-                score for the current year, but ranking by number of `stars` is also available.`\n\n\
+                score for the current year, but ranking by number of `stars` is also available, as well as the `combined` \
+                board of all our private leaderboards. With `--by time`, members tied on points or stars are ordered by their total \
+                solve time, which rewards consistency. Also available as `!standings`.`\n\n\
                 👉 🚴 *The long haul!*\n\
                 ```!tdf [jersey color] [day] [year]```\n\
                 Tour de France alternative standings! Come join the peloton and compete to earn `yellow` jersey credentials, \
@@ -100,7 +438,49 @@ impl MessageTemplate {
                 - `green` jersey points are earned each day by going full blast between part 1 and part 2 ! The points attributed are \
                 based on the official Tour de France green jersey points.\n\
                 - `combative` jersey points are attributed each day to the brave soul showing grit by not throwing the towel too early and keeping \
-                their focus on finishing a day before the next one starts ... The closer to the cutoff, the more points earned !"
+                their focus on finishing a day before the next one starts ... The closer to the cutoff, the more points earned !\n\n\
+                👉 🕸️ *Who beats who?*\n\
+                ```!rivalry [dot|mermaid] [year]```\n\
+                Graph of the head-to-head rivalries for the year, in `dot` (default) or `mermaid` format. An edge goes from \
+                the winner to the loser of a rivalry, labeled with the number of days each of them completed first. \
+                The graph is uploaded as a file.\n\n\
+                👉 🪪 *Who am I?*\n\
+                ```!iam <AoC name or id>```\n\
+                Tell the bot who you are on the private leaderboard, so announcements can mention you.\n\n\
+                👉 🙋 *How am I doing?*\n\
+                ```!me [year]```\n\
+                Your own position, stars and local score for the year, with your median, best and worst time to the first \
+                star of the days, and a chart of the hours of the day you solve puzzles at. Also available as `!stats`.\n\n\
+                👉 🔔 *Keep me going!*\n\
+                ```!nudge [on|off]```\n\
+                Get a friendly private reminder in the evening when you haven't completed the day yet.\n\n\
+                👉 😴 *Do not disturb*\n\
+                ```!snooze [duration]\n!unsnooze```\n\
+                Hold the announcements of the channel for a while (e.g. `2h` or `30m`, one hour by default), \
+                during incidents or meetings. Held announcements are posted when the snooze ends.\n\n\
+                👉 🏆 *Hall of heroes*\n\
+                ```!heroes [year]```\n\
+                Appearances of the members on the global leaderboard during the year, with the points they earned there.\n\n\
+                👉 🤫 *Keep it low-key*\n\
+                ```!quiet [on|off]```\n\
+                Stop being named in announcements, your stars still counting in the standings.\n\n\
+                👉 ⚔️ *Leaderboard battle*\n\
+                ```!versus <leaderboard> <leaderboard> [year]```\n\
+                Compare two private leaderboards: average solve times, completion rates and a combined ranking of the days both completed.\n\n\
+                👉 🧗 *How hard was it?*\n\
+                ```!difficulty <1-10> [day]```\n\
+                Rate the difficulty of a day of the year, today by default. Votes are blended with the time the global \
+                leaderboard took to fill into a difficulty index of the day.\n\n\
+                👉 ⏰ *Early birds*\n\
+                ```!early [year]```\n\
+                Number of puzzles each member solved shortly after their release (within the hour by default), for the \
+                dedicated early risers.\n\n\
+                👉 🐙 *Share from GitHub*\n\
+                ```!github [owner/repository]```\n\
+                Register the GitHub repository you push your solutions to, and they are linked in the daily solutions \
+                thread as soon as you push them. Without repository, stop sharing.\n\n\
+                👥 Add `--group <name>` to `!fast`, `!board`, `!tdf`, `!rivalry`, `!me` or `!early` to get the standings \
+                within a group of members, e.g. `!standings --group juniors`."
             },
             MessageTemplate::CustomMessage => {
                 "🙅 {{message}}"
@@ -119,9 +499,9 @@ impl MessageTemplate {
             },
             MessageTemplate::DailyChallenge => {
                 "```{{header}}```\n\
-                🎉 Today's challenge is up!\n\
+                {{ theme.party }} Today's challenge is up!\n\
                     \x20 *{{title}}*\n\
-                🔫 Go after it and get some fun, ⏱️ time is ticking !"
+                🔫 Go after it and get some fun, {{ theme.timer }} time is ticking !"
             },
             MessageTemplate::DailySolutionThread => {
                 "👇 *Daily discussion thread for day {{day}}*\n\
@@ -129,44 +509,94 @@ impl MessageTemplate {
                  🚨 *Spoilers Ahead* :rotating_light:"
             },
             MessageTemplate::DailySummary => {
-                "🗓️ *December, {{day}} {{year}}*\n\
+                "{{ theme.calendar }} *December, {{day}} {{year}}*\n\
                 ----- 🥁 *Daily update* 🗞️ -----\n\
                 Here is how things went down at the front of the pack today:\n\
                 ___________________________________________________________________\n\
-                Top 5 to finish *PART 1* 🏁\n\
+                Top 5 to finish *PART 1* {{ theme.finish }}\n\
                 {%- for (prefix, name, time) in ranking_p1 %}\n\
-                    {{prefix}} in ⏱️ {{time}} 👉🏻 *{{name}}*
+                    {{prefix}} in {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
                 {%- endfor %}\n\
                 ___________________________________________________________________\n\
-                Top 5 to finish *PART 2* 🏁\n\
+                Top 5 to finish *PART 2* {{ theme.finish }}\n\
                 {%- for (prefix, name, time) in ranking_p2 %}\n\
-                    {{prefix}} in ⏱️ {{time}} 👉🏻 *{{name}}*
+                    {{prefix}} in {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
                 {%- endfor %}\n\
                 ___________________________________________________________________\n\
-                Top 5 *DELTA* 🏁\n\
+                Top 5 *DELTA* {{ theme.finish }}\n\
                 {%- for (prefix, name, time) in ranking_delta %}\n\
-                    {{prefix}} in ⏱️ {{time}} 👉🏻 *{{name}}*
-                {%- endfor %}"
+                    {{prefix}} in {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
+                {%- endfor %}\n\
+                ___________________________________________________________________\n\
+                🎄 {{ stars }}/{{ max_stars }} stars so far: `{{ progress }}`
+                {%- if difficulty %}\n\
+                    🧗 Difficulty of the day: *{{ difficulty }}/10*
+                    {%- if hardest and hardest[0] != day_number %}, day {{ hardest[0] }} was the hardest so far: *{{ hardest[1] }}/10*
+                    {%- elif hardest %}, the hardest so far
+                    {%- endif %}
+                {%- endif %}"
             },
             MessageTemplate::PrivateLeaderboardUpdated => {
-                "🔁 Private Leaderboard successfully updated!"
+                "📌 *{{ year }} standings* by local score:\n\
+                ```{{ leaderboard }}```\n\
+                🔁 _Last updated: {{ timestamp }}_"
             },
             MessageTemplate::LeaderboardMemberJoin => {
                 "{%- for name in members %}\n\
-                    🕺 A new player has joined the christmas arena ! Happy to have you on board *{{name}}* !
+                    {{ theme.welcome }} A new player has joined the christmas arena ! Happy to have you on board *{{name|mention}}* !
                  {%- endfor %}"
             },
+            MessageTemplate::LeaderboardMemberLeave => {
+                "👋 {{ members|join(', ') }} left the leaderboard. Points depend on the number of members, \
+                so the scores of the year have been recomputed without {{ 'them' if members|length > 1 else 'this member' }}."
+            },
+            MessageTemplate::LeaderboardMemberRename => {
+                "{%- for (before, after) in renames %}\n\
+                    🏷️ {{ before }} is now known as *{{ after|mention }}*.
+                 {%- endfor %}"
+            },
+            MessageTemplate::AocStatus => {
+                "{% if available %}✅ AoC is back, the leaderboard is up to date again.\
+                {% else %}🚧 AoC appears to be down, retrying...{% endif %}"
+            },
+            MessageTemplate::FirstBlood => {
+                "🩸 First blood! *{{ name|mention }}* is the first to get {{ 'the first star' if part == '1' else 'both stars' }} \
+                of day {{ day }}, in {{ theme.timer }} {{ duration }}!"
+            },
+            MessageTemplate::EveryoneFinished => {
+                "🎉 Everyone has both stars of day {{ day }}! It took the group {{ theme.timer }} {{ duration }}.\
+                {% if last %} Shout-out to *{{ last|mention }}* for bringing it home!{% endif %}"
+            },
+            MessageTemplate::StarMilestone => {
+                "🌟 The leaderboard just passed *{{ milestone }} stars* this season, {{ total }} out of {{ max }} possible!\n\
+                `{{ progress }}`"
+            },
+            MessageTemplate::Spotlight => {
+                "🔦 Today's spotlight is on *{{ name|mention }}*! Would you walk us through your solution of day {{ day }}? \
+                Share your approach, the trick that made it click, or the bug that bit you."
+            },
+            MessageTemplate::DailyStandings => {
+                "📓 Standings at the end of day {{ day }} of {{ year }}, and how they moved since the day before:\n\
+                ```{{ standings }}```"
+            },
+            MessageTemplate::GitHubSolution => {
+                "🐙 *{{ name|mention }}* pushed their solution of day {{ day }}: <{{ url }}|{{ repository }}@{{ sha }}>"
+            },
+            MessageTemplate::SeasonCompleted => {
+                "🎊🎉🎊 *{{ name|mention }}* just earned their *50th star* and completed the whole season! 🎊🎉🎊\n\
+                {{ theme.star ~ theme.star ~ theme.star }} {{ 'First' if order == 1 else rank }} member of the leaderboard to finish the year! {{ theme.star ~ theme.star ~ theme.star }}"
+            },
             MessageTemplate::NewEntriesToday => {
                 "{%- for entry in completions %}\n\
-                    {% with both = entry.parts_duration|length > 1, double = '⭐⭐', single = '⭐' %}\
-                    📣 {{entry.name}} just earned *{{entry.n_stars}}* more star{{ 's' if entry.n_stars > 1 }} for day {{entry.day}} ({{[double, '*<->', entry.delta, '*']|join(' ') if both else single}}) +{{entry.new_points}}pts
+                    {% with both = entry.parts_duration|length > 1, double = theme.star ~ theme.star, single = theme.star %}\
+                    {{ theme.announce }} {{entry.name|mention}} just earned *{{entry.n_stars}}* more star{{ 's' if entry.n_stars > 1 }} for day {{entry.day}} ({% if both %}{{double}} *<-> {{entry.delta}} *{% else %}{{single}}{% endif %}) +{{entry.new_points}}pts
                     {%- endwith %}
                  {%- endfor %}\n"
             },
             MessageTemplate::NewEntriesLate => {
                 "{%- for entry in completions %}\n\
-                    {% with both = entry.parts_duration|length > 1, double = '✅', single = '✔️' %}\
-                    🚂  {{entry.name}} just caught up on *{{entry.n_stars}}* more star{{ 's' if entry.n_stars > 1 }} for day {{entry.day}} ({{ [double, 'both parts completed!', '*<->', entry.delta, '*']|join(' ')  if both else single }}) +{{entry.new_points}}pts
+                    {% with both = entry.parts_duration|length > 1, double = theme.check, single = theme.check_single %}\
+                    {{ theme.late }}  {{entry.name|mention}} just caught up on *{{entry.n_stars}}* more star{{ 's' if entry.n_stars > 1 }} for day {{entry.day}} ({% if both %}{{double}} both parts completed! *<-> {{entry.delta}} *{% else %}{{single}}{% endif %}) +{{entry.new_points}}pts
                     {%- endwith %}
                  {%- endfor %}"
             },
@@ -183,20 +613,215 @@ impl MessageTemplate {
                     {{'Fastest' if not is_limit else 'Closest'}} *{{ ranking_method }} time* for day {{ day }}/12/{{ year }}:
                 {%- endif %}\n\
                 {%- for (prefix, name, time) in ranking %}\n\
-                    {{prefix}} in ⏱️ {{time}} 👉🏻 *{{name}}*
+                    {{prefix}} in {{ theme.timer }} {{time}} {{ theme.pointer }} *{{name}}*
                 {%- endfor %}"
             }
             MessageTemplate::Hero => {
-                "🎉 🥳 Our very own *{{ name }}* made it to the global leaderboard on part *{{ part }}*! (*{{ rank }}*) 🙌"
+                "{{ theme.party }} 🥳 Our very own *{{ name|mention }}* made it to the global leaderboard on part *{{ part }}*! (*{{ rank }}*) 🙌"
             },
             MessageTemplate::LeaderboardDisplay => {
                 "{%- if current_year -%}
-                    📓 Current Leaderboard by {{ '*local score*' if scoring_method == 'local' else '*number of stars*' }} as of {{timestamp}}:
+                    📓 Current {{ 'combined ' if combined }}Leaderboard by {{ '*local score*' if scoring_method == 'local' else '*number of stars*' }}{{ ', ties by *total solve time*' if by_time }} as of {{timestamp}}:
                 {%- else -%}
-                    📓 Learderboard by {{ '*local score*' if scoring_method == 'local' else '*number of stars*' }} from the {{ year }} event:
+                    📓 {{ 'Combined ' if combined }}Learderboard by {{ '*local score*' if scoring_method == 'local' else '*number of stars*' }}{{ ', ties by *total solve time*' if by_time }} from the {{ year }} event:
                 {%- endif %}\n\
                 ```{{ leaderboard }}```"
             }
+            MessageTemplate::RivalryGraph => {
+                "🕸️ Head-to-head rivalries from the {{ year }} event, as a {{ format }} graph in the attached file."
+            }
+            MessageTemplate::Iam => {
+                "🪪 Nice to meet you *{{ name }}*! To prove it's you, set your AoC display name to `{{ code }}` \
+                for a moment, and I will mention you from the next leaderboard update. You can set your name back \
+                right after. No access to your AoC settings? An admin can vouch for you with `{{ approve }}`."
+            }
+            MessageTemplate::IdentityVerified => {
+                "🪪 Verified, you are *{{ name }}*! I will mention you from now on. \
+                You can set your AoC display name back whenever you like, I will follow."
+            }
+            MessageTemplate::Approve => {
+                "🪪 <@{{ user }}> is now known as *{{ name }}*."
+            }
+            MessageTemplate::GitHub => {
+                "{%- if repository -%}
+                    🐙 Got it, I will link your solutions pushed to *{{ repository }}* in the daily solutions thread. \
+                    Mention the day in the commit message or in the path of the files, e.g. `day05/main.rs`.
+                {%- else -%}
+                    🐙 Your solutions won't be linked from GitHub anymore.
+                {%- endif %}"
+            }
+            MessageTemplate::Early => {
+                "{%- if counts -%}
+                    ⏰ Puzzles solved within {{ window_min }} minutes of their release in {{ year }}, hail the early birds:\n\
+                    {%- for (name, count) in counts %}\n\
+                        {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name|mention }}*: \
+                        {{ count }} puzzle{{ 's' if count > 1 }}
+                    {%- endfor %}
+                {%- else -%}
+                    Nobody solved a puzzle within {{ window_min }} minutes of its release in {{ year }} yet. Set your alarms!
+                {%- endif %}"
+            }
+            MessageTemplate::Difficulty => {
+                "🧗 Thanks, your vote for day {{ day }} is in: *{{ vote }}/10*.\
+                {%- if index %} Community difficulty index of the day: *{{ index }}/10*.{% endif %}"
+            }
+            MessageTemplate::Grouped => {
+                "👥 Group *{{ group }}*:\n{{ answer }}"
+            }
+            MessageTemplate::Versus => {
+                "⚔️ *{{ boards[0][0] }}* vs *{{ boards[1][0] }}* in {{ year }}:\n\
+                {%- for (name, members, average, completion) in boards %}\n\
+                    {{ theme.pointer }} *{{ name }}*: {{ members }} member{{ 's' if members != 1 }}, {{ completion }} of the stars, \
+                    {{ average ~ ' on average for both stars' if average else 'no day completed' }}
+                {%- endfor %}
+                {%- if days %}\n\
+                    {{ theme.party }} Combined ranking over the {{ days|length }} day{{ 's' if days|length > 1 }} shared ({{ days|join(', ') }}):\n\
+                    {%- for (name, boards, score) in ranking %}\n\
+                        {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name }}* ({{ boards }}): {{ score }} pts
+                    {%- endfor %}
+                {%- else %}\n\
+                    No day was completed on both leaderboards yet.
+                {%- endif %}"
+            }
+            MessageTemplate::Me => {
+                "{%- if position -%}
+                    🙋 *{{ name }}*, you are *#{{ position }}* with {{ stars }} {{ theme.star }} and {{ score }} points \
+                    {{- ' as of ' ~ timestamp if current_year else ' in the ' ~ year ~ ' event' }}.
+                    {%- if first_star %}\n\
+                        ⏱️ First star in *{{ first_star.median }}* (median), best {{ first_star.best }}, worst {{ first_star.worst }}.
+                    {%- endif %}
+                    {%- if peak_hour is not none %}\n\
+                        🕰️ Your stars mostly come around *{{ peak_hour }}h*.
+                    {%- endif %}
+                {%- else -%}
+                    🙋 *{{ name }}*, no star for you {{ 'yet this year' if current_year else 'in the ' ~ year ~ ' event' }}.
+                {%- endif %}"
+            }
+            MessageTemplate::Quiet => {
+                "{%- if quiet -%}
+                    🤫 Got it, you won't be named in announcements anymore, your stars still count in the standings. \
+                    Use `!quiet off` to be named again.
+                {%- else -%}
+                    📣 You will be named in announcements again. Use `!quiet on` to stay discreet.
+                {%- endif %}"
+            }
+            MessageTemplate::Nudge => {
+                "{%- if subscribed -%}
+                    🔔 Deal! I will send you a friendly reminder in the evening if you haven't completed the day yet. \
+                    Use `!nudge off` to stop at any time.
+                {%- else -%}
+                    🔕 No more reminders, you're on your own now. Use `!nudge on` if you miss me!
+                {%- endif %}"
+            }
+            MessageTemplate::Replay => {
+                "{%- if count -%}
+                    🔁 Sending again {{ count }} message{{ 's' if count > 1 }} which could not be delivered.
+                {%- else -%}
+                    ✅ No undelivered message to send again.
+                {%- endif %}"
+            }
+            MessageTemplate::Heroes => {
+                "{%- if heroes -%}
+                    {{ theme.party }} Wall of fame of the global leaderboard for {{ year }}:\n\
+                    {%- for (name, appearances, points, best_rank) in heroes %}\n\
+                        {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name|mention }}*: \
+                        {{ appearances }} appearance{{ 's' if appearances > 1 }}, {{ points }} pts, best rank {{ best_rank }}
+                    {%- endfor %}
+                {%- else -%}
+                    No member made it to the global leaderboard in {{ year }} yet. Keep trying!
+                {%- endif %}"
+            }
+            MessageTemplate::Audit => {
+                "{%- if entries -%}
+                    📋 Last {{ entries|length }} command{{ 's' if entries|length > 1 }} received:\n\
+                    {%- for entry in entries %}\n\
+                        {{ theme.pointer }} {{ entry.time }} {{ '<@' ~ entry.user ~ '>' if entry.user else 'Someone' }} in <#{{ entry.channel }}>: `{{ entry.input }}` → \
+                        {{ entry.result ~ ' (' ~ entry.latency ~ ' ms)' if entry.result else 'not handled' }}
+                    {%- endfor %}
+                {%- else -%}
+                    📋 No command received yet.
+                {%- endif %}"
+            }
+            MessageTemplate::LeaderTopic => "🥇 {{ name }} — {{ score }} pts (updated {{ time }})",
+            MessageTemplate::AppHome => {
+                "*Advent of Code {{ year }}* {{ theme.calendar }}\n\
+                {% if not name %}\
+                    🙋 I don't know who you are yet, introduce yourself with `!iam <AoC name or id>`.\
+                {% elif position %}\
+                    🙋 *{{ name }}*, you are *#{{ position }}* with {{ stars }} {{ theme.star }} and {{ score }} points.\
+                {% else %}\
+                    🙋 *{{ name }}*, no star for you yet this year.\
+                {% endif %}\n\
+                {% if name and day %}\
+                    {% if parts == 2 %}\
+                        {{ theme.check }} Day {{ day }} completed, well done!\
+                    {% elif parts == 1 %}\
+                        {{ theme.check_single }} Day {{ day }}: part 1 done, part 2 to go.\
+                    {% else %}\
+                        {{ theme.timer }} Day {{ day }} is waiting for you.\
+                    {% endif %}\n\
+                {% endif %}\
+                \n\
+                *Top 10* {{ theme.finish }}\n\
+                ```{{ board }}```\n\
+                _Updated {{ timestamp }}_"
+            }
+            MessageTemplate::PuzzleUnfurl => {
+                "*{{ title }}* {{ theme.calendar }} {{ year }}\n\
+                {{ theme.star }} Solved by *{{ solved }}* member{{ 's' if solved != 1 }} of the private leaderboard\
+                {%- if fastest %}, fastest in {{ theme.timer }} *{{ fastest }}*{% endif %}."
+            }
+            MessageTemplate::Welcome => {
+                "{{ theme.welcome }} Welcome to the private leaderboard, *{{ name }}*!\n\
+                Announcements and results are posted in <#{{ channel }}>, come and say hi.\n\n\
+                {{ help }}\n\
+                Type `!help` for the full handbook.\n\n\
+                {{ theme.pointer }} If I got your AoC account wrong, link the right one with `!iam <AoC name or id>`."
+            }
+            MessageTemplate::Snooze => {
+                "😴 Shhh... announcements are snoozed in this channel until *{{ until }}*. \
+                They will be posted when the snooze ends, or earlier with `!unsnooze`."
+            }
+            MessageTemplate::Unsnooze => "⏰ Snooze ended, announcements are back in this channel!",
+            MessageTemplate::SnoozeEnded => {
+                "⏰ Snooze is over, announcements are back in this channel!"
+            }
+            MessageTemplate::WeeklyDigest => {
+                "{{ theme.calendar }} *Weekly digest* - Advent of Code {{ year }}\n\
+                {%- if kudos %}\n\
+                    Community kudos {{ theme.star }} received by the solutions shared in the daily threads:\n\
+                    {%- for (name, count) in kudos %}\n\
+                        {{ theme.medals[loop.index0] if loop.index0 < theme.medals|length else theme.pointer }} *{{ name }}*: {{ count }}
+                    {%- endfor %}
+                {%- endif %}
+                {%- if heroes %}\n\
+                    {{ theme.party }} Wall of fame of the global leaderboard this season:\n\
+                    {%- for (name, appearances, points, best_rank) in heroes %}\n\
+                        {{ theme.pointer }} *{{ name }}*: {{ appearances }} appearance{{ 's' if appearances > 1 }}, {{ points }} pts, best rank {{ best_rank }}
+                    {%- endfor %}
+                {%- endif %}
+                {%- if odds %}\n\
+                    🎲 Chances to win the season, simulating the remaining days: \
+                    {%- for (name, percent) in odds %} *{{ name }}* {{ percent }}%{{ ',' if not loop.last }}{%- endfor %}
+                {%- endif %}
+                {%- if hardest %}\n\
+                    🧗 Day {{ hardest[0] }} was the hardest so far: *{{ hardest[1] }}/10*
+                {%- endif %}
+                {%- if first_stars %}\n\
+                    ⏱️ Quickest to the first star of the days (median, best, worst):\n\
+                    {%- for (name, median, best, worst) in first_stars %}\n\
+                        {{ theme.pointer }} *{{ name }}*: {{ median }}, {{ best }}, {{ worst }}
+                    {%- endfor %}
+                {%- endif %}"
+            }
+            MessageTemplate::Countdown => {
+                "{{ theme.timer }} Day {{ day }} unlocks in *{{ minutes }} minute{{ 's' if minutes > 1 }}*! Grab a coffee ☕ and get ready."
+            }
+            MessageTemplate::NudgeReminder => {
+                "🌙 Hey *{{ name }}*, day {{ day }} is still waiting for you! No pressure, every star counts {{ theme.star }} \
+                - and you've got this 💪\n\
+                _Reply `!nudge off` to stop these reminders._"
+            }
             MessageTemplate::TdfStandings => {
                 "{%- if current_year and not day -%}
                     🚴 {{ '🟡 Yellow 🛵' if jersey=='yellow' else ('🟢 Green 🍏' if jersey=='green' else '⚫Combative 🥋')}} Jersey current standings as of {{timestamp}}:
@@ -209,6 +834,48 @@ impl MessageTemplate {
             }
         }
     }
+    /// Compact version of the template, fitting in a single line. Templates without compact
+    /// version are rendered in full.
+    pub fn compact_template(&self) -> Option<&'static str> {
+        match self {
+            MessageTemplate::Help => Some(
+                "🗒️ Commands: `!help` • `!fast [delta|p1|p2|limit] [day] [year]` • `!board [local|stars|combined] [year] [--by time]` • \
+                `!tdf [yellow|green|combative] [day] [year]` • `!rivalry [dot|mermaid] [year]` • \
+                `!iam <AoC name or id>` • `!me [year]` • `!nudge [on|off]` • `!snooze [duration]` • `!unsnooze` • \
+                `!heroes [year]` • `!quiet [on|off]` • `!versus <leaderboard> <leaderboard> [year]` • \
+                `!difficulty <1-10> [day]` • `!early [year]` • `!github [owner/repository]`",
+            ),
+            MessageTemplate::DailyChallenge => Some("{{ theme.party }} Day {{day}} is up: *{{title}}* {{ theme.timer }}"),
+            MessageTemplate::DailySummary => Some(
+                "{{ theme.calendar }} Day {{day}} {{ theme.finish }} P1: \
+                {%- for (prefix, name, time) in ranking_p1 %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %} \
+                | P2: {%- for (prefix, name, time) in ranking_p2 %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %} \
+                | Δ: {%- for (prefix, name, time) in ranking_delta %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %} \
+                | 🎄 {{ stars }}/{{ max_stars }} stars, {{ percent }}%\
+                {%- if difficulty %} | 🧗 {{ difficulty }}/10{% endif %}",
+            ),
+            MessageTemplate::GlobalStatistics => Some(
+                "🌍 Global Leaderboard complete for day {{day}}: P1 *{{p1_fast}}* - *{{p1_slow}}* • P2 *{{p2_fast}}* - *{{p2_slow}}*",
+            ),
+            MessageTemplate::NewEntriesToday => Some(
+                "{{ theme.announce }} {% for entry in completions %}{{entry.name|mention}} +{{entry.n_stars}}{{ theme.star }} (day {{entry.day}}, +{{entry.new_points}}pts){{ ', ' if not loop.last }}{% endfor %}",
+            ),
+            MessageTemplate::NewEntriesLate => Some(
+                "{{ theme.late }} {% for entry in completions %}{{entry.name|mention}} +{{entry.n_stars}}{{ theme.check_single }} (day {{entry.day}}, +{{entry.new_points}}pts){{ ', ' if not loop.last }}{% endfor %}",
+            ),
+            MessageTemplate::Ranking => Some(
+                "{{ theme.timer }} *{{ ranking_method }}* day {{ day }}: \
+                {%- for (prefix, name, time) in ranking %} {{prefix}}*{{name}}* {{time|trim}}{{ ',' if not loop.last }}{%- endfor %}",
+            ),
+            MessageTemplate::LeaderboardDisplay => Some(
+                "📓 Top {{ 'combined' if combined else ('local score' if scoring_method == 'local' else 'stars') }}{{ ' (ties by time)' if by_time }} {{ year }}: ```{{ leaderboard }}```",
+            ),
+            MessageTemplate::TdfStandings => Some(
+                "🚴 {{ '🟡' if jersey=='yellow' else ('🟢' if jersey=='green' else '⚫')}} {{ year }}{{ ' day ' ~ day if day }}: ```{{ standings }}```",
+            ),
+            _ => None,
+        }
+    }
 }
 
 pub fn invalid_year_day_message(year: i32, day: Option<u8>) -> Option<String> {
@@ -272,12 +939,12 @@ pub fn invalid_year_day_message(year: i32, day: Option<u8>) -> Option<String> {
                 "I know the suspense is unbearable, but I can't go faster than the music 🎶...\n\
                 *Come back in {delta} day{}* to see what's happening on December {}.",
                 potential_s,
-                format_rank(day)
+                format_rank(day.into())
             ))
         }
         // it's today, make sure AOC puzzle was released
         (true, true, _) => {
-            let now = Utc::now();
+            let now = now();
             // safe unwrap since day is some
             let day = day.unwrap();
             Entry::puzzle_unlock(year, day)
@@ -0,0 +1,120 @@
+use crate::config;
+use serde::Serialize;
+use std::fmt;
+
+const THEMES: [&'static str; 3] = ["classic", "minimal", "extra-festive"];
+
+#[derive(Debug, Clone)]
+pub enum Theme {
+    CLASSIC,
+    MINIMAL,
+    FESTIVE,
+}
+
+/// Emoji and decorations used by the templates, available in templates as `theme`.
+#[derive(Debug, Serialize)]
+pub struct ThemeTable {
+    pub party: &'static str,
+    pub star: &'static str,
+    pub check: &'static str,
+    pub check_single: &'static str,
+    pub announce: &'static str,
+    pub late: &'static str,
+    pub timer: &'static str,
+    pub finish: &'static str,
+    pub pointer: &'static str,
+    pub calendar: &'static str,
+    pub welcome: &'static str,
+    // Prefixes of the first positions of rankings
+    pub medals: &'static [&'static str],
+    pub trophies: &'static [&'static str],
+}
+
+static CLASSIC: ThemeTable = ThemeTable {
+    party: "🎉",
+    star: "⭐",
+    check: "✅",
+    check_single: "✔️",
+    announce: "📣",
+    late: "🚂",
+    timer: "⏱️",
+    finish: "🏁",
+    pointer: "👉🏻",
+    calendar: "🗓️",
+    welcome: "🕺",
+    medals: &["🥇", "🥈", "🥉"],
+    trophies: &["🏆", "🥈", "🥉", "🍫", "🍬"],
+};
+
+static MINIMAL: ThemeTable = ThemeTable {
+    party: "",
+    star: "*",
+    check: "++",
+    check_single: "+",
+    announce: "",
+    late: "",
+    timer: "",
+    finish: "",
+    pointer: "-",
+    calendar: "",
+    welcome: "",
+    medals: &[],
+    trophies: &[],
+};
+
+static FESTIVE: ThemeTable = ThemeTable {
+    party: "🎉🎊",
+    star: "🌟",
+    check: "🎁",
+    check_single: "🎀",
+    announce: "🔔",
+    late: "🦌",
+    timer: "⏳",
+    finish: "🎄",
+    pointer: "🎅",
+    calendar: "📅",
+    welcome: "☃️",
+    medals: &["🎄", "🎁", "🍪"],
+    trophies: &["🎄", "🎁", "🍪", "🍫", "🍬"],
+};
+
+impl Theme {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            theme if theme == THEMES[0] => Some(Theme::CLASSIC),
+            theme if theme == THEMES[1] => Some(Theme::MINIMAL),
+            theme if theme == THEMES[2] => Some(Theme::FESTIVE),
+            _ => None,
+        }
+    }
+
+    /// Theme configured for the bot. Default is classic.
+    pub fn current() -> Self {
+        let settings = &config::SETTINGS;
+        Theme::from_string(&settings.theme).unwrap_or(Theme::CLASSIC)
+    }
+
+    pub fn table(&self) -> &'static ThemeTable {
+        match self {
+            Theme::CLASSIC => &CLASSIC,
+            Theme::MINIMAL => &MINIMAL,
+            Theme::FESTIVE => &FESTIVE,
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Theme::CLASSIC => {
+                write!(f, "{}", THEMES[0])
+            }
+            Theme::MINIMAL => {
+                write!(f, "{}", THEMES[1])
+            }
+            Theme::FESTIVE => {
+                write!(f, "{}", THEMES[2])
+            }
+        }
+    }
+}
@@ -14,6 +14,10 @@ pub enum BotError {
     ChannelSend(String),
     Slack(String),
     Compute(String),
+    // The layout of an AoC page changed, e.g. a selector finding nothing anymore
+    Layout(String),
+    // AoC is down or under maintenance, retried later
+    Unavailable(String),
     Parse,
 }
 
@@ -28,6 +32,8 @@ impl fmt::Display for BotError {
             BotError::ChannelSend(s) => write!(f, "MPSC Error: {}", s),
             BotError::Slack(s) => write!(f, "Slack Communication Error: {}", s),
             BotError::Compute(s) => write!(f, "Computation Error: {}", s),
+            BotError::Layout(s) => write!(f, "AOC Layout Error: {}", s),
+            BotError::Unavailable(s) => write!(f, "AOC Unavailable: {}", s),
             BotError::Parse => write!(f, "Parsing Error"),
         }
     }
@@ -0,0 +1,102 @@
+use crate::{
+    api::{day_completions, member, standings, Day, Member, MemberStanding},
+    core::commands::default_year,
+    storage::{MemoryCache, KUDOS},
+};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, SimpleObject};
+use itertools::Itertools;
+
+pub type ApiSchema = async_graphql::Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Member of the private leaderboard.
+#[derive(SimpleObject)]
+pub struct MemberRef {
+    id: u64,
+    name: String,
+}
+
+/// Solution posted by a member in a daily solutions thread.
+#[derive(SimpleObject)]
+pub struct Solution {
+    member: String,
+    year: i32,
+    day: u8,
+    kudos: usize,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Members of the private leaderboard, over all the years.
+    async fn members(&self, ctx: &Context<'_>) -> Vec<MemberRef> {
+        let cache = ctx.data_unchecked::<MemoryCache>();
+        cache
+            .read(|data| {
+                data.leaderboard
+                    .iter()
+                    .map(|e| &e.id)
+                    .unique()
+                    .sorted()
+                    .map(|id| MemberRef {
+                        id: id.numeric,
+                        name: id.name.to_string(),
+                    })
+                    .collect()
+            })
+            .await
+    }
+
+    /// Local standings of the year, the default year if not given.
+    async fn standings(&self, ctx: &Context<'_>, year: Option<i32>) -> Vec<MemberStanding> {
+        let year = year.unwrap_or_else(default_year);
+        let cache = ctx.data_unchecked::<MemoryCache>();
+        cache
+            .read(move |data| standings(&data.leaderboard, year).standings)
+            .await
+    }
+
+    /// Member with the given AoC id, with its standings for each year.
+    async fn member(&self, ctx: &Context<'_>, id: u64) -> Option<Member> {
+        let cache = ctx.data_unchecked::<MemoryCache>();
+        cache.read(move |data| member(&data.leaderboard, id)).await
+    }
+
+    /// Completion times of the members for a day of the year, fastest first.
+    async fn day(&self, ctx: &Context<'_>, year: Option<i32>, day: u8) -> Day {
+        let year = year.unwrap_or_else(default_year);
+        let cache = ctx.data_unchecked::<MemoryCache>();
+        cache
+            .read(move |data| day_completions(&data.leaderboard, year, day))
+            .await
+    }
+
+    /// Solutions posted in the daily solutions threads, optionally filtered.
+    async fn solutions(
+        &self,
+        member: Option<String>,
+        year: Option<i32>,
+        day: Option<u8>,
+    ) -> Vec<Solution> {
+        KUDOS
+            .get()
+            .into_values()
+            .filter(|post| member.as_ref().map_or(true, |m| *m == post.member))
+            .filter(|post| year.map_or(true, |y| y == post.year))
+            .filter(|post| day.map_or(true, |d| d == post.day))
+            .sorted_by_key(|post| (post.year, post.day, post.member.clone()))
+            .map(|post| Solution {
+                member: post.member,
+                year: post.year,
+                day: post.day,
+                kudos: post.kudos,
+            })
+            .collect()
+    }
+}
+
+pub fn schema(cache: MemoryCache) -> ApiSchema {
+    async_graphql::Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(cache)
+        .finish()
+}
@@ -0,0 +1,75 @@
+use crate::{
+    config,
+    core::leaderboard::Entry,
+    error::{BotError, BotResult},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Arc,
+};
+use tracing::{error, info};
+
+/// Runtime state handed off by a stopping process to the next one, so a restart neither
+/// announces twice nor loses what was not announced yet.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Handoff {
+    // Scraped private leaderboard (scrape time, entries), diffed against by the next scrape
+    pub leaderboard: Option<(DateTime<Utc>, Vec<Entry>)>,
+    // Former display names of the members, and members of the private leaderboard for each year
+    #[serde(default)]
+    pub previous_names: HashMap<u64, Vec<Arc<str>>>,
+    #[serde(default)]
+    pub roster: HashMap<i32, HashSet<u64>>,
+    // Current daily solutions thread (channel id, ts)
+    pub solutions_thread: Option<(String, String)>,
+    // Channel id => ts of the bot output thread of the channel
+    pub output_threads: HashMap<String, String>,
+    // Channel id => end of the snooze of the channel
+    pub snoozes: HashMap<String, DateTime<Utc>>,
+    // Announcements not posted yet, already rendered: (channel id, text)
+    pub pending: Vec<(String, String)>,
+}
+
+impl Handoff {
+    /// State handed off by the previous process, if any. The handoff file is removed, so the
+    /// state is resumed only once.
+    pub fn resume() -> Option<Handoff> {
+        let file = config::SETTINGS.handoff_file.as_ref()?;
+        let content = fs::read_to_string(Path::new(file)).ok()?;
+        if let Err(e) = fs::remove_file(file) {
+            error!("Could not remove handoff file '{file}'. {e}");
+        }
+        match serde_json::from_str::<Handoff>(&content) {
+            Ok(handoff) => {
+                info!(
+                    "Resuming from handoff file '{file}', {} announcements pending.",
+                    handoff.pending.len()
+                );
+                Some(handoff)
+            }
+            Err(e) => {
+                error!("Invalid handoff file '{file}', ignoring it. {e}");
+                None
+            }
+        }
+    }
+
+    /// Writes the state for the next process, when a handoff file is configured.
+    pub fn hand_off(&self) -> BotResult<()> {
+        let Some(file) = config::SETTINGS.handoff_file.as_ref() else {
+            return Ok(());
+        };
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| BotError::IO(e.to_string()))?;
+        fs::write(file, content)?;
+        info!(
+            "Runtime state handed off to '{file}', {} announcements pending.",
+            self.pending.len()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{bus::BUS_METRICS, systemd};
+use chrono::{DateTime, Duration, Utc};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, StatusCode,
+};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{convert::Infallible, net::SocketAddr, sync::RwLock};
+use tracing::{error, info};
+
+// The scheduler is considered stuck after missing a few heartbeats (sent every minute).
+static SCHEDULER_TIMEOUT_MINUTES: i64 = 3;
+// An update of the private leaderboard is considered stuck after running this long.
+static SCRAPE_TIMEOUT_MINUTES: i64 = 10;
+
+pub static HEALTH: Lazy<Health> = Lazy::new(Health::new);
+
+/// Liveness of the bot components, reported by the health endpoints.
+pub struct Health {
+    started: DateTime<Utc>,
+    last_aoc_fetch: RwLock<Option<DateTime<Utc>>>,
+    // Whether AoC answered the last request, rather than being down
+    aoc_available: RwLock<bool>,
+    slack_connected: RwLock<bool>,
+    scheduler_heartbeat: RwLock<Option<DateTime<Utc>>>,
+    // Start of the update of the private leaderboard running, if any
+    scrape_started: RwLock<Option<DateTime<Utc>>>,
+}
+
+#[derive(Serialize)]
+struct HealthStatus {
+    last_aoc_fetch: Option<String>,
+    aoc_available: bool,
+    slack_connected: bool,
+    scheduler_heartbeat: Option<String>,
+    scheduler_alive: bool,
+    scrape_stuck: bool,
+    event_queue_depth: Option<usize>,
+    event_queue_capacity: Option<usize>,
+    events_published: u64,
+    events_dropped: u64,
+}
+
+impl Health {
+    fn new() -> Health {
+        Health {
+            started: Utc::now(),
+            last_aoc_fetch: RwLock::new(None),
+            aoc_available: RwLock::new(true),
+            slack_connected: RwLock::new(false),
+            scheduler_heartbeat: RwLock::new(None),
+            scrape_started: RwLock::new(None),
+        }
+    }
+
+    pub fn record_aoc_fetch(&self) {
+        *self.last_aoc_fetch.write().unwrap() = Some(Utc::now());
+    }
+
+    /// Records whether AoC is available, returning whether it changed.
+    pub fn set_aoc_available(&self, available: bool) -> bool {
+        let mut aoc_available = self.aoc_available.write().unwrap();
+        let changed = *aoc_available != available;
+        *aoc_available = available;
+        changed
+    }
+
+    /// Records whether the bot is connected to Slack, systemd being told the bot is ready once
+    /// it is.
+    pub fn set_slack_connected(&self, connected: bool) {
+        *self.slack_connected.write().unwrap() = connected;
+        if connected {
+            systemd::notify_ready();
+        }
+    }
+
+    pub fn record_scheduler_heartbeat(&self) {
+        *self.scheduler_heartbeat.write().unwrap() = Some(Utc::now());
+    }
+
+    pub fn record_scrape_started(&self) {
+        *self.scrape_started.write().unwrap() = Some(Utc::now());
+    }
+
+    pub fn record_scrape_ended(&self) {
+        *self.scrape_started.write().unwrap() = None;
+    }
+
+    /// Whether an update of the private leaderboard has been running for too long, e.g. waiting
+    /// on a lock forever.
+    pub fn scrape_stuck(&self) -> bool {
+        self.scrape_started
+            .read()
+            .unwrap()
+            .is_some_and(|started| started < Utc::now() - Duration::minutes(SCRAPE_TIMEOUT_MINUTES))
+    }
+
+    fn status(&self) -> HealthStatus {
+        let last_aoc_fetch = *self.last_aoc_fetch.read().unwrap();
+        let scheduler_heartbeat = *self.scheduler_heartbeat.read().unwrap();
+        // No heartbeat is expected yet right after startup.
+        let scheduler_alive = scheduler_heartbeat.unwrap_or(self.started)
+            > Utc::now() - Duration::minutes(SCHEDULER_TIMEOUT_MINUTES);
+        let depth = BUS_METRICS.depth();
+        let scrape_stuck = self.scrape_stuck();
+        HealthStatus {
+            last_aoc_fetch: last_aoc_fetch.map(|t| t.to_rfc3339()),
+            aoc_available: *self.aoc_available.read().unwrap(),
+            slack_connected: *self.slack_connected.read().unwrap(),
+            scheduler_heartbeat: scheduler_heartbeat.map(|t| t.to_rfc3339()),
+            scheduler_alive,
+            scrape_stuck,
+            event_queue_depth: depth.map(|(depth, _capacity)| depth),
+            event_queue_capacity: depth.map(|(_depth, capacity)| capacity),
+            events_published: BUS_METRICS.published(),
+            events_dropped: BUS_METRICS.dropped(),
+        }
+    }
+}
+
+/// Serves `/healthz` (the scheduler is alive and not stuck updating the private leaderboard) and `/readyz` (the bot is connected to Slack and
+/// fetched the private leaderboard at least once).
+pub async fn serve(port: u16) {
+    let make_svc = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(health_route)) });
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("Serving health endpoints on {addr}.");
+    if let Err(e) = hyper::Server::bind(&addr).serve(make_svc).await {
+        error!("Health endpoints stopped. {e}");
+    }
+}
+
+async fn health_route(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let status = HEALTH.status();
+    let healthy = match req.uri().path() {
+        "/healthz" => status.scheduler_alive && !status.scrape_stuck,
+        "/readyz" => status.slack_connected && status.last_aoc_fetch.is_some(),
+        _ => {
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap())
+        }
+    };
+    let code = match healthy {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    let body = serde_json::to_string(&status).unwrap_or_default();
+    Ok(Response::builder()
+        .status(code)
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap())
+}
@@ -0,0 +1,31 @@
+//! Christmas Elf Officer, an Advent of Code bot for Slack.
+//!
+//! The bot is built on a library which can be reused on its own, without running the bot:
+//! - [`client::aoc::AoC`] retrieves the private and global leaderboards from Advent of Code,
+//!   [`client::aoc::AoC::with_session`] building a client without the bot configuration.
+//! - [`core::leaderboard::Leaderboard`] holds the completions of the members, and computes the
+//!   daily stars, scores and statistics of a year.
+//! - [`core::standings`] ranks the members: local and stars boards, rankings by time, Tour de
+//!   France jerseys and rivalries.
+//! - [`core::source::PuzzleEvent`] describes the puzzle event followed (days, parts, release
+//!   schedule), and [`core::source::AocSource`] where its leaderboards come from.
+//! - [`utils::compute_highlights`] gives the new completions between two leaderboards.
+
+pub mod api;
+pub mod bus;
+pub mod calendar;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod core;
+pub mod error;
+pub mod graphql;
+pub mod handoff;
+pub mod health;
+pub mod scheduler;
+pub mod secrets;
+pub mod shutdown;
+pub mod storage;
+pub mod systemd;
+pub mod utils;
+pub mod validation;
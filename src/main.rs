@@ -1,37 +1,96 @@
-use chrono::{Timelike, Utc};
+use chrono::{Duration, Timelike, Utc};
+use clap::Parser;
+use opentelemetry_otlp::WithExportConfig;
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::info;
-
-use client::slack::AoCSlackClient;
-use core::events::Event;
-use scheduler::{JobProcess, Scheduler};
-use storage::MemoryCache;
-
-pub mod cli;
-pub mod client;
-pub mod config;
-pub mod core;
-pub mod error;
-pub mod scheduler;
-pub mod storage;
-pub mod utils;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info};
+use tracing_subscriber::{filter::LevelFilter, prelude::*};
+
+use ceo::{
+    api,
+    bus::EventBus,
+    calendar,
+    cli::Cli,
+    client::slack::AoCSlackClient,
+    config::{self, LogFormat},
+    core::source::PUZZLE_EVENT,
+    handoff::Handoff,
+    health,
+    scheduler::{self, JobProcess, Scheduler},
+    secrets, shutdown,
+    storage::{self, MemoryCache},
+    validation,
+};
 
 #[tokio::main]
 // async fn main() -> Result<(), Box<dyn std::error::Error>> {
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Subcommands run once and print to stdout, without starting the bot.
+    if let Some(command) = Cli::parse().command {
+        command.run().await?;
+        return Ok(());
+    }
+
     let settings = &config::SETTINGS;
 
-    let subscriber = tracing_subscriber::FmtSubscriber::builder()
-        .with_max_level(settings.get_trace_level())
-        .finish();
+    // Opt-in error reporting. Panics are captured, and so are errors logged with `error!`,
+    // along with the fields of their spans. Reporting stops when the guard is dropped.
+    let _sentry = settings.sentry_dsn.as_ref().map(|dsn| {
+        sentry::init((
+            dsn.as_str(),
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                ..Default::default()
+            },
+        ))
+    });
+
+    let fmt_layer = match settings.get_log_format() {
+        LogFormat::JSON => tracing_subscriber::fmt::layer().json().boxed(),
+        LogFormat::TEXT => tracing_subscriber::fmt::layer().boxed(),
+    };
+
+    // Opt-in trace export, spans being sent in batches to the OTLP endpoint.
+    let otel_layer = settings.otlp_endpoint.as_ref().map(|endpoint| {
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+                opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                    "service.name",
+                    env!("CARGO_PKG_NAME"),
+                )]),
+            ))
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
+            .expect("Setting OpenTelemetry exporter failed");
+        tracing_opentelemetry::layer().with_tracer(tracer)
+    });
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::from_level(settings.get_trace_level()))
+        .with(fmt_layer)
+        .with(
+            settings
+                .sentry_dsn
+                .as_ref()
+                .map(|_| sentry_tracing::layer()),
+        )
+        .with(otel_layer)
+        .try_init()
+        .expect("Setting default subscriber failed");
 
-    tracing::subscriber::set_global_default(subscriber).expect("Setting default subscriber failed");
+    // Secrets are needed by the first requests, so the bot does not start without them.
+    secrets::load().await?;
+    tokio::spawn(secrets::refresh_periodically());
+    tokio::spawn(storage::flush_audit_periodically());
 
     // Silencing the warning, as removing the mut here would actually break compilation.
     #[allow(unused_mut)]
-    // Capacity of 64 should be more than plenty to handle all the messages
-    let (tx, mut rx) = mpsc::channel::<Event>(64);
+    let (tx, mut rx) = EventBus::new();
 
     // Retrieve current minute to initialize schedule of private leaderbaord updates.
     // AoC API rules states to not fetch leaderboard at a frequency higher than 15min.
@@ -42,31 +101,141 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // At every 15th minute from (now_minute % 15) through 59.
     let private_leaderboard_schedule = format!("{} {}/15 * * 12,1 *", now_second, now_minute % 15);
 
+    // Jobs following the releases of the puzzle event, e.g. "0 0 5 1-25 12 *" for the releases
+    // of AoC at 05:00 UTC in December.
+    let event = &PUZZLE_EVENT;
+    let solutions_thread_schedule = event.release_cron(Duration::minutes(210))?;
+    let global_leaderboard_schedule = event.release_cron(Duration::zero())?;
+    let daily_challenge_schedule = event.release_cron(Duration::seconds(1))?;
+    let summary_schedule = event.release_cron(Duration::minutes(690))?;
+    let spotlight_schedule = event.release_cron(Duration::hours(12))?;
+    // Just before the next unlock, the day being over.
+    let standings_schedule = event.release_cron(Duration::days(1) - Duration::minutes(5))?;
+    // Countdowns are scheduled on Slack ahead of the puzzle unlock, so they are posted on time.
+    let countdowns_schedule = event.release_cron(-Duration::hours(1))?;
+
+    // Evening nudges for subscribed members who did not complete the current day yet.
+    let nudges_schedule = format!("0 0 {} {} *", settings.nudge_hour, event.cron_days(0));
+    // Solutions pushed to GitHub are linked while the day goes on.
+    let github_schedule = format!("0 */15 * {} *", event.cron_days(0));
+
     // Initialize global cache
     let cache = MemoryCache::new();
 
-    let sched = Scheduler::new(cache.clone(), Arc::new(tx.clone())).await?;
+    // Resume from the state handed off by the previous process, if any.
+    let mut resumed = Handoff::resume().unwrap_or_default();
+    let resumed_leaderboard = resumed.leaderboard.take();
+    let leaderboard_resumed = resumed_leaderboard.is_some();
+    if let Some((timestamp, entries)) = resumed_leaderboard {
+        let previous_names = std::mem::take(&mut resumed.previous_names);
+        let roster = std::mem::take(&mut resumed.roster);
+        cache
+            .update(move |data| {
+                data.timestamp = timestamp;
+                data.leaderboard.extend(entries);
+                data.leaderboard
+                    .restore_members_history(previous_names, roster);
+            })
+            .await;
+    }
 
-    let jobs = vec![
-        JobProcess::InitializePrivateLeaderboard, // only ran once, at startup.
-        JobProcess::UpdatePrivateLeaderboard(&private_leaderboard_schedule),
-        JobProcess::InitializeDailySolutionsThread("0 30 8 1-25 12 *"),
-        JobProcess::WatchGlobalLeaderboard("0 0 5 1-25 12 *"),
-        JobProcess::ParseDailyChallenge("1 0 5 1-25 12 *"),
-        JobProcess::SendDailySummary("0 30 16 1-25 12 *"),
-    ];
+    let mut sched = Scheduler::new(cache.clone(), Arc::new(tx.clone())).await?;
+
+    // A replayed season is announced by the replay instead of the scheduled jobs.
+    let mut jobs = match settings.replay_year {
+        Some(year) => {
+            info!("Starting replay of season {year}.");
+            tokio::spawn(scheduler::replay_season(
+                cache.clone(),
+                Arc::new(tx.clone()),
+                year,
+            ));
+            vec![JobProcess::Heartbeat("0 * * * * *")]
+        }
+        None => vec![
+            JobProcess::UpdatePrivateLeaderboard(&private_leaderboard_schedule),
+            JobProcess::InitializeDailySolutionsThread(&solutions_thread_schedule),
+            JobProcess::WatchGlobalLeaderboard(&global_leaderboard_schedule),
+            JobProcess::ParseDailyChallenge(&daily_challenge_schedule),
+            JobProcess::SendDailySummary(&summary_schedule),
+            JobProcess::SendDailySpotlight(&spotlight_schedule),
+            JobProcess::SendDailyStandings(&standings_schedule),
+            JobProcess::SendNudges(&nudges_schedule),
+            JobProcess::WatchGitHubSolutions(&github_schedule),
+            JobProcess::ScheduleCountdowns(&countdowns_schedule),
+            JobProcess::SendWeeklyDigest("0 0 18 * 12 Sun"),
+            JobProcess::Heartbeat("0 * * * * *"),
+        ],
+    };
+    // Only ran once, at startup. A resumed leaderboard is kept instead, so the completions
+    // made during the restart are announced by the next update.
+    if !leaderboard_resumed && settings.replay_year.is_none() {
+        jobs.insert(0, JobProcess::InitializePrivateLeaderboard);
+    }
+    validation::validate_configuration(&jobs).await?;
+    let calendar_posts = calendar::scheduled_posts(&jobs);
     for job in jobs {
         sched.add_job(job).await?;
     }
 
+    // Reload message templates from the templates directory on SIGHUP.
+    match signal(SignalKind::hangup()) {
+        Ok(mut hangup) => {
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    info!("SIGHUP received, reloading message templates.");
+                    ceo::core::templates::reload_templates();
+                }
+            });
+        }
+        Err(e) => error!("Could not register SIGHUP handler. {e}"),
+    };
+
+    // Stop cleanly on SIGTERM/SIGINT.
+    tokio::spawn(shutdown::watch_signals());
+
+    if let Some(port) = settings.health_port {
+        tokio::spawn(health::serve(port));
+    }
+
+    if let Some(port) = settings.api_port {
+        tokio::spawn(api::serve(
+            port,
+            cache.clone(),
+            Arc::new(tx.clone()),
+            calendar_posts,
+        ));
+    }
+
     info!("Starting scheduler.");
     sched.start().await?;
 
     info!("Initializing messaging engine.");
 
     let slack_client = AoCSlackClient::new();
-    slack_client
-        .handle_messages_and_events(cache, tx, rx)
+    let mut handoff = slack_client
+        .handle_messages_and_events(cache.clone(), tx, rx, resumed)
         .await?;
+
+    info!("Stopping scheduler.");
+    sched.shutdown().await?;
+    storage::flush_stores();
+
+    // Hand the runtime state off to the next process, if configured.
+    if settings.handoff_file.is_some() {
+        handoff.leaderboard = Some(
+            cache
+                .read(|data| (data.timestamp, data.leaderboard.iter().cloned().collect()))
+                .await,
+        );
+        (handoff.previous_names, handoff.roster) =
+            cache.read(|data| data.leaderboard.members_history()).await;
+        if let Err(e) = handoff.hand_off() {
+            error!("Could not hand off runtime state. {e}");
+        }
+    }
+
+    // Export the spans not sent yet.
+    opentelemetry::global::shutdown_tracer_provider();
     Ok(())
 }
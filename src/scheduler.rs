@@ -1,23 +1,64 @@
 use crate::{
-    client::aoc::AoC,
+    bus::EventBus,
+    client::{
+        aoc::{configured_source, AoC, UNKNOWN_TITLE},
+        github::GitHub,
+        replay::replay_time,
+    },
     config,
     core::{
+        display,
         events::Event,
-        standings::{Ranking, Standing},
+        identities,
+        leaderboard::{Entry, Identifier, Leaderboard, ProblemPart, ScrapedLeaderboard},
+        simulation::win_probabilities,
+        source::{AocSource, PUZZLE_EVENT},
+        standings::{
+            first_star_latencies, standings_board, standings_board_iter, Ranking, Scoring, Standing,
+        },
     },
     error::{BotError, BotResult},
-    storage::MemoryCache,
-    utils::{compute_highlights, current_year_day, get_new_members},
+    health::HEALTH,
+    secrets,
+    storage::{
+        HeroAppearance, MemoryCache, StandingsSnapshot, DIFFICULTY, HEROES, KUDOS, OTHER_BOARDS,
+        SETTINGS_STORE,
+    },
+    systemd,
+    utils::{
+        collective_progress, compute_highlights, current_year_day, format_duration,
+        format_duration_with_days, get_everyone_finished, get_first_bloods, get_left_members,
+        get_new_members, get_renamed_members, get_season_completions, get_star_milestone, now,
+        pick_spotlight, present_anonymous_everyone_finished, present_anonymous_first_bloods,
+        present_anonymous_highlights, present_anonymous_members, present_anonymous_renames,
+        present_anonymous_season_completions, significant_highlights, DayHighlight,
+    },
 };
-use std::{sync::Arc, time::Duration};
-use tokio::{sync::mpsc::Sender, time};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time;
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{error, info};
+use tracing::{debug, error, info, info_span, instrument, warn, Span};
+
+// Number of members shown in the kudos leaderboard of the weekly digest
+const DIGEST_KUDOS_ROWS: usize = 10;
+// Members whose chances to win the season are given in the weekly digest
+const DIGEST_ODDS_ROWS: usize = 3;
+// Members quickest to the first star of the days (median) in the weekly digest
+const DIGEST_FIRST_STAR_ROWS: usize = 3;
+// Members shown in the standings posted at the end of each day
+const DAILY_STANDINGS_ROWS: usize = 10;
 
 pub struct Scheduler {
     scheduler: JobScheduler,
     cache: MemoryCache,
-    sender: Arc<Sender<Event>>, // communication to messaging service
+    sender: Arc<EventBus>, // communication to messaging service
 }
 
 pub enum JobProcess<'schedule> {
@@ -27,10 +68,50 @@ pub enum JobProcess<'schedule> {
     WatchGlobalLeaderboard(&'schedule str),
     ParseDailyChallenge(&'schedule str),
     SendDailySummary(&'schedule str),
+    SendDailySpotlight(&'schedule str),
+    SendDailyStandings(&'schedule str),
+    SendNudges(&'schedule str),
+    WatchGitHubSolutions(&'schedule str),
+    ScheduleCountdowns(&'schedule str),
+    SendWeeklyDigest(&'schedule str),
+    Heartbeat(&'schedule str),
+}
+
+impl JobProcess<'_> {
+    /// Cron expression of the job, if run on a schedule.
+    pub fn schedule(&self) -> Option<&str> {
+        match self {
+            JobProcess::InitializePrivateLeaderboard => None,
+            JobProcess::InitializeDailySolutionsThread(schedule)
+            | JobProcess::UpdatePrivateLeaderboard(schedule)
+            | JobProcess::WatchGlobalLeaderboard(schedule)
+            | JobProcess::ParseDailyChallenge(schedule)
+            | JobProcess::SendDailySummary(schedule)
+            | JobProcess::SendDailySpotlight(schedule)
+            | JobProcess::SendDailyStandings(schedule)
+            | JobProcess::SendNudges(schedule)
+            | JobProcess::WatchGitHubSolutions(schedule)
+            | JobProcess::ScheduleCountdowns(schedule)
+            | JobProcess::SendWeeklyDigest(schedule)
+            | JobProcess::Heartbeat(schedule) => Some(schedule),
+        }
+    }
+
+    /// Name of the posts of the job shown in the calendar feed, for the posts members expect.
+    pub fn calendar_name(&self) -> Option<&'static str> {
+        match self {
+            JobProcess::InitializeDailySolutionsThread(_) => Some("Daily solutions thread"),
+            JobProcess::SendDailySummary(_) => Some("Daily summary"),
+            JobProcess::SendDailySpotlight(_) => Some("Daily spotlight"),
+            JobProcess::SendDailyStandings(_) => Some("Daily standings"),
+            JobProcess::SendWeeklyDigest(_) => Some("Weekly digest"),
+            _ => None,
+        }
+    }
 }
 
 impl Scheduler {
-    pub async fn new(cache: MemoryCache, sender: Arc<Sender<Event>>) -> BotResult<Self> {
+    pub async fn new(cache: MemoryCache, sender: Arc<EventBus>) -> BotResult<Self> {
         let scheduler = JobScheduler::new().await?;
         Ok(Scheduler {
             scheduler,
@@ -61,6 +142,25 @@ impl Scheduler {
             JobProcess::SendDailySummary(schedule) => {
                 send_daily_summary_job(schedule, self.cache.clone(), self.sender.clone()).await?
             }
+            JobProcess::SendDailySpotlight(schedule) => {
+                send_daily_spotlight_job(schedule, self.cache.clone(), self.sender.clone()).await?
+            }
+            JobProcess::SendDailyStandings(schedule) => {
+                send_daily_standings_job(schedule, self.cache.clone(), self.sender.clone()).await?
+            }
+            JobProcess::SendNudges(schedule) => {
+                send_nudges_job(schedule, self.cache.clone(), self.sender.clone()).await?
+            }
+            JobProcess::WatchGitHubSolutions(schedule) => {
+                watch_github_solutions_job(schedule, self.sender.clone()).await?
+            }
+            JobProcess::ScheduleCountdowns(schedule) => {
+                schedule_countdowns_job(schedule, self.sender.clone()).await?
+            }
+            JobProcess::SendWeeklyDigest(schedule) => {
+                send_weekly_digest_job(schedule, self.cache.clone(), self.sender.clone()).await?
+            }
+            JobProcess::Heartbeat(schedule) => heartbeat_job(schedule).await?,
         };
         Ok(self.scheduler.add(job).await?)
     }
@@ -69,13 +169,12 @@ impl Scheduler {
         Ok(self.scheduler.start().await?)
     }
 
-    // pub fn cache_size(&self) -> usize {
-    //     let data = self.cache.data.lock().unwrap();
-    //     data.leaderboard.len()
-    // }
+    pub async fn shutdown(&mut self) -> BotResult<()> {
+        Ok(self.scheduler.shutdown().await?)
+    }
 
-    // pub fn ref_count(&self) -> usize {
-    //     Arc::strong_count(&self.cache.data)
+    // pub async fn cache_size(&self) -> usize {
+    //     self.cache.read(|data| data.leaderboard.len()).await
     // }
 }
 
@@ -87,7 +186,7 @@ async fn initialize_private_leaderboard_job(cache: MemoryCache) -> BotResult<Job
     let job = Job::new_one_shot_async(Duration::from_secs(0), move |_uuid, _l| {
         let cache = cache.clone();
         Box::pin(async move {
-            let aoc_client = AoC::new();
+            let aoc_client = configured_source();
             let settings = &config::SETTINGS;
 
             let (current_year, _day) = current_year_day();
@@ -96,18 +195,116 @@ async fn initialize_private_leaderboard_job(cache: MemoryCache) -> BotResult<Job
                 live_years.extend(2015..current_year)
             };
 
-            for year in live_years {
-                match aoc_client.private_leaderboard(year).await {
+            for (year, scraped) in aoc_client
+                .fetch_private_years(live_years, settings.aoc_max_concurrent_requests)
+                .await
+            {
+                match scraped {
                     Ok(scraped_leaderboard) => {
-                        let mut data = cache.data.lock().unwrap();
-                        data.merge_with(scraped_leaderboard);
+                        cache
+                            .update(move |data| data.merge_with(scraped_leaderboard))
+                            .await;
                     }
                     Err(e) => {
-                        let error = BotError::AOC(format!("Could not scrape leaderboard. {e}"));
+                        let error =
+                            BotError::AOC(format!("Could not scrape leaderboard for {year}. {e}"));
                         error!("{error}");
                     }
                 };
             }
+            scrape_other_leaderboards(current_year).await;
+        })
+    })?;
+    Ok(job)
+}
+
+/// Replays a past season at accelerated speed, see [`crate::client::replay`]. Each puzzle is
+/// announced when it unlocks, and the replayed leaderboard is scraped about once per replayed
+/// hour, until the last day of the season is over.
+pub async fn replay_season(cache: MemoryCache, sender: Arc<EventBus>, year: i32) {
+    let settings = &config::SETTINGS;
+    let aoc_client = configured_source();
+    let mut interval = time::interval(Duration::from_millis(
+        settings.replay_day_sec.max(1) * 1000 / 24,
+    ));
+    let mut announced_day = 0;
+
+    info!(
+        "Replaying season {year}, one day every {} seconds.",
+        settings.replay_day_sec
+    );
+    loop {
+        interval.tick().await;
+        let elapsed = match (replay_time(year), Entry::puzzle_unlock(year, 1)) {
+            (Ok(time), Ok(first_unlock)) => time - first_unlock,
+            (Err(e), _) | (_, Err(e)) => {
+                error!("Could not replay season {year}. {e}");
+                return;
+            }
+        };
+        let day = elapsed.num_days() + 1;
+        let current_day = day.min(PUZZLE_EVENT.days().into()) as u8;
+
+        if current_day > announced_day {
+            announced_day = current_day;
+            let title = aoc_client
+                .fetch_puzzle_title(year, current_day)
+                .await
+                .unwrap_or_else(|_| UNKNOWN_TITLE.to_string());
+            if let Err(e) = sender
+                .send(Event::DailyChallengeIsUp(current_day, title))
+                .await
+            {
+                let error =
+                    BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+                error!("{error}");
+            };
+        }
+
+        scrape_private_leaderboard(&cache, &sender, year, current_day).await;
+
+        if day > PUZZLE_EVENT.days().into() {
+            info!("Replay of season {year} is over.");
+            return;
+        }
+    }
+}
+
+// Shows the scheduler is alive, for the health endpoints.
+async fn heartbeat_job(schedule: &str) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        Box::pin(async move {
+            HEALTH.record_scheduler_heartbeat();
+            // systemd restarts the bot when the watchdog is not notified in time.
+            if HEALTH.scrape_stuck() {
+                warn!("Private leaderboard update is stuck, watchdog not notified.");
+            } else {
+                systemd::notify_watchdog();
+            }
+        })
+    })?;
+    Ok(job)
+}
+
+async fn schedule_countdowns_job(schedule: &str, sender: Arc<EventBus>) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        let sender = sender.clone();
+        Box::pin(async move {
+            let settings = &config::SETTINGS;
+            let (year, day) = current_year_day();
+            info!("Scheduling countdowns for day {day}.");
+
+            for minutes in settings.countdown_minutes.iter() {
+                if let Err(e) = sender
+                    .send(Event::DailyCountdown(year, day, *minutes))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
         })
     })?;
     Ok(job)
@@ -115,7 +312,7 @@ async fn initialize_private_leaderboard_job(cache: MemoryCache) -> BotResult<Job
 
 async fn initialize_daily_solutions_thread_job(
     schedule: &str,
-    sender: Arc<Sender<Event>>,
+    sender: Arc<EventBus>,
 ) -> BotResult<Job> {
     let job = Job::new_async(schedule, move |_uuid, _l| {
         let sender = sender.clone();
@@ -137,90 +334,515 @@ async fn initialize_daily_solutions_thread_job(
 async fn update_private_leaderboard_job(
     schedule: &str,
     cache: MemoryCache,
-    sender: Arc<Sender<Event>>,
+    sender: Arc<EventBus>,
 ) -> BotResult<Job> {
     let job = Job::new_async(schedule, move |uuid, mut l| {
         let cache = cache.clone();
         let sender = sender.clone();
         Box::pin(async move {
-            let aoc_client = AoC::new();
+            let (year, day) = current_year_day();
+            scrape_private_leaderboard(&cache, &sender, year, day).await;
+            scrape_other_leaderboards(year).await;
 
-            let (year, _day) = current_year_day();
-            match aoc_client.private_leaderboard(year).await {
-                Ok(scraped_leaderboard) => {
-                    // Scoped to force 'current_leaderboard' to drop before 'await' so future can be Send.
-                    let (highlights, new_members) = {
-                        let mut current_leaderboard = cache.data.lock().unwrap();
-
-                        // Check for new parts completions
-                        let highlights = compute_highlights(
-                            &current_leaderboard.leaderboard,
-                            &scraped_leaderboard.leaderboard,
-                        );
+            // Query the next execution time for this job
+            let next_tick = l.next_tick_for_job(uuid).await;
+            match next_tick {
+                Ok(Some(ts)) => info!("Next refresh for private leaderboard at {:?}", ts),
+                _ => error!("Could not get next tick for refresh private leaderboard job"),
+            }
+        })
+    })?;
+    Ok(job)
+}
 
-                        // Check for new members
-                        let new_members = get_new_members(
-                            &current_leaderboard.leaderboard,
-                            &scraped_leaderboard.leaderboard,
-                        );
+// Changes found by diffing a scrape of the private leaderboard with the cached one.
+struct ScrapeChanges {
+    highlights: Vec<DayHighlight>,
+    first_bloods: Vec<(Identifier, u8, ProblemPart, chrono::Duration)>,
+    // (day, last finisher, time taken by the group since release)
+    everyone_finished: Vec<(u8, Identifier, chrono::Duration)>,
+    // (milestone, total stars, maximum stars of the season)
+    star_milestone: Option<(usize, usize, usize)>,
+    // (member, finishing order)
+    season_completions: Vec<(Identifier, usize)>,
+    new_members: Vec<String>,
+    left_members: Vec<String>,
+    renames: Vec<(String, String)>,
+    board: String,
+    timestamp: DateTime<Utc>,
+    new_leader: Option<(String, usize)>,
+    // Name => anonymous name of the members who asked not to be named in announcements
+    quiet: HashMap<String, String>,
+}
 
-                        // Update leadearboard in cache.
-                        current_leaderboard.merge_with(scraped_leaderboard);
+impl ScrapeChanges {
+    /// Changes with the members who asked not to be named renamed as anonymous members, shown,
+    /// left out or counted together like the others according to the anonymous policy. Their
+    /// renames are left out.
+    fn conceal_quiet_members(self) -> ScrapeChanges {
+        let quiet = self.quiet;
+        let conceal = |name: String| quiet.get(&name).cloned().unwrap_or(name);
+        let conceal_id = |id: Identifier| match quiet.get(&*id.name) {
+            Some(anonymous) => Identifier {
+                name: anonymous.as_str().into(),
+                numeric: id.numeric,
+            },
+            None => id,
+        };
+        ScrapeChanges {
+            highlights: self
+                .highlights
+                .into_iter()
+                .map(|h| DayHighlight {
+                    name: conceal(h.name),
+                    ..h
+                })
+                .collect(),
+            first_bloods: self
+                .first_bloods
+                .into_iter()
+                .map(|(id, day, part, duration)| (conceal_id(id), day, part, duration))
+                .collect(),
+            everyone_finished: self
+                .everyone_finished
+                .into_iter()
+                .map(|(day, id, duration)| (day, conceal_id(id), duration))
+                .collect(),
+            star_milestone: self.star_milestone,
+            season_completions: self
+                .season_completions
+                .into_iter()
+                .map(|(id, order)| (conceal_id(id), order))
+                .collect(),
+            new_members: self.new_members.into_iter().map(conceal).collect(),
+            left_members: self.left_members.into_iter().map(conceal).collect(),
+            // Verification codes set for a moment are not worth announcing either.
+            renames: self
+                .renames
+                .into_iter()
+                .filter(|(before, after)| !quiet.contains_key(before) && !quiet.contains_key(after))
+                .filter(|(before, after)| {
+                    !identities::is_verification_code(before)
+                        && !identities::is_verification_code(after)
+                })
+                .collect(),
+            board: self.board,
+            timestamp: self.timestamp,
+            new_leader: self.new_leader.map(|(name, score)| (conceal(name), score)),
+            quiet: HashMap::new(),
+        }
+    }
+}
 
-                        (highlights, new_members)
-                    };
+/// Scrapes the other private leaderboards of the year, which are not announced but compared by
+/// commands.
+pub(crate) async fn scrape_other_leaderboards(year: i32) {
+    let settings = &config::SETTINGS;
+    for (name, id) in settings.aoc_other_leaderboards.iter().flatten() {
+        let aoc_client = AoC::with_session(
+            &settings.aoc_base_url,
+            &secrets::aoc_session_cookie(),
+            *id,
+            settings.aoc_api_timeout_sec,
+        );
+        match aoc_client.fetch_private(year).await {
+            Ok(scraped_leaderboard) => {
+                let mut boards = OTHER_BOARDS.write().unwrap();
+                boards
+                    .entry(name.to_string())
+                    .or_insert_with(ScrapedLeaderboard::new)
+                    .merge_with(scraped_leaderboard);
+            }
+            Err(e) => {
+                let error = BotError::AOC(format!(
+                    "Could not scrape leaderboard {name} for {year}. {e}"
+                ));
+                error!("{error}");
+            }
+        }
+    }
+}
+
+/// One scrape cycle of the private leaderboard, announcing what changed since the previous one.
+#[instrument(name = "scrape", skip(cache, sender), fields(members))]
+pub(crate) async fn scrape_private_leaderboard(
+    cache: &MemoryCache,
+    sender: &EventBus,
+    year: i32,
+    day: u8,
+) {
+    let aoc_client = configured_source();
+    HEALTH.record_scrape_started();
+
+    match aoc_client.fetch_private(year).await {
+        Ok(scraped_leaderboard) => {
+            report_aoc_status(sender, true).await;
+            Span::current().record(
+                "members",
+                scraped_leaderboard.leaderboard.members_ids().len(),
+            );
+            // Diffed and merged by the state manager in a single operation, so that no update
+            // is missed in between.
+            let diff_span = info_span!("diff");
+            let changes = cache
+                .update(move |current_leaderboard| {
+                    let _entered = diff_span.enter();
+
+                    // Members who asked not to be named, before their name changes or they leave.
+                    let mut quiet_ids = identities::quiet_members(&current_leaderboard.leaderboard);
+                    quiet_ids.extend(identities::quiet_members(&scraped_leaderboard.leaderboard));
+                    let quiet = current_leaderboard
+                        .leaderboard
+                        .iter()
+                        .chain(scraped_leaderboard.leaderboard.iter())
+                        .map(|e| &e.id)
+                        .filter(|id| quiet_ids.contains(&id.numeric))
+                        .map(|id| (id.name.to_string(), Identifier::anonymous_name(id.numeric)))
+                        .collect::<HashMap<String, String>>();
+                    let leader_before = leader(&current_leaderboard.leaderboard, year);
+
+                    // Members who left are dropped, so that points are computed over the current
+                    // members like on AoC, rather than shifting silently on the next restart.
+                    let left_members = get_left_members(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                        year,
+                    );
+                    if !left_members.is_empty() {
+                        let ids = left_members.iter().map(|id| id.numeric).collect();
+                        current_leaderboard.leaderboard.remove_members(&ids);
+                    }
+
+                    // Check for new parts completions
+                    let highlights = compute_highlights(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                    );
+
+                    // Check for the first completions of the parts of new days
+                    let first_bloods = get_first_bloods(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                        year,
+                    );
+
+                    // Check for the days all the members have now completed
+                    let everyone_finished = get_everyone_finished(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                        year,
+                        config::SETTINGS
+                            .excluded_members
+                            .as_deref()
+                            .unwrap_or_default(),
+                    );
+
+                    // Check for the total stars of the season crossing a milestone
+                    let star_milestone = get_star_milestone(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                        year,
+                        &config::SETTINGS.star_milestones,
+                    );
+
+                    // Check for members who just completed the year
+                    let season_completions = get_season_completions(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                        year,
+                    );
+
+                    // Check for new members
+                    let new_members = get_new_members(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                    );
+
+                    // Check for members who changed their display name
+                    let renames = get_renamed_members(
+                        &current_leaderboard.leaderboard,
+                        &scraped_leaderboard.leaderboard,
+                    );
+
+                    // Update leadearboard in cache.
+                    current_leaderboard.merge_with(scraped_leaderboard);
+
+                    // Completions below the thresholds are not announced, but still shown by commands.
+                    let settings = &config::SETTINGS;
+                    let top_members = settings.significant_top_positions.map(|n| {
+                        standings_board_iter(
+                            &Scoring::LOCAL,
+                            &current_leaderboard.leaderboard,
+                            year,
+                        )
+                        .take(n)
+                        .map(|(id, _scores, _score)| id.name.to_string())
+                        .collect::<HashSet<String>>()
+                    });
+                    let highlights = significant_highlights(
+                        highlights,
+                        top_members.as_ref(),
+                        settings
+                            .significant_max_delta_min
+                            .map(chrono::Duration::minutes),
+                        settings.significant_max_hours.map(chrono::Duration::hours),
+                    );
+
+                    // Current standings, for the live standings message
+                    let board = display::board(standings_board(
+                        &Scoring::LOCAL,
+                        &current_leaderboard.leaderboard,
+                        year,
+                    ));
 
-                    // Conditionnally trigger internal events, base on leaderboard processing.
-                    if !new_members.is_empty() {
-                        if let Err(e) = sender
-                            .send(Event::PrivateLeaderboardNewMembers(new_members))
-                            .await
-                        {
-                            let error = BotError::ChannelSend(format!(
-                                "Could not send message to MPSC channel. {e}"
-                            ));
-                            error!("{error}");
-                        };
+                    // First place changed
+                    let leader_after = leader(&current_leaderboard.leaderboard, year);
+                    let new_leader = match (leader_before, leader_after) {
+                        (Some((before, _)), Some((after, score))) if before != after => {
+                            Some((after, score))
+                        }
+                        (None, after) => after,
+                        _ => None,
                     }
-                    if !highlights.is_empty() {
-                        if let Err(e) = sender
-                            .send(Event::PrivateLeaderboardNewEntries(highlights))
-                            .await
-                        {
-                            let error = BotError::ChannelSend(format!(
-                                "Could not send message to MPSC channel. {e}"
-                            ));
-                            error!("{error}");
-                        };
+                    .map(|(id, score)| (id.name.to_string(), score));
+
+                    ScrapeChanges {
+                        highlights,
+                        first_bloods,
+                        everyone_finished,
+                        star_milestone,
+                        season_completions,
+                        new_members,
+                        left_members: left_members
+                            .into_iter()
+                            .map(|id| id.name.to_string())
+                            .collect(),
+                        renames,
+                        board,
+                        timestamp: current_leaderboard.timestamp,
+                        new_leader,
+                        quiet,
                     }
-                    if let Err(e) = sender.send(Event::PrivateLeaderboardUpdated).await {
+                })
+                .await;
+
+            // Registered Slack users follow the renamed members.
+            for (before, after) in changes.renames.iter() {
+                identities::rename(before, after);
+            }
+
+            // Identities claimed with `!iam` are verified by the AoC name set to their code.
+            let verified = cache
+                .read(|data| identities::verify_claims(&data.leaderboard))
+                .await;
+            for (user, name) in verified {
+                info!("Identity of {name} verified.");
+                if let Err(e) = sender.send(Event::IdentityVerified(user, name)).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+
+            let ScrapeChanges {
+                highlights,
+                first_bloods,
+                everyone_finished,
+                star_milestone,
+                season_completions,
+                new_members,
+                left_members,
+                renames,
+                board,
+                timestamp,
+                new_leader,
+                quiet: _,
+            } = changes.conceal_quiet_members();
+
+            // Anonymous members are announced according to the configured policy.
+            let anonymous_policy = config::SETTINGS.get_anonymous_policy();
+            let highlights = present_anonymous_highlights(highlights, anonymous_policy);
+            let first_bloods = present_anonymous_first_bloods(first_bloods, anonymous_policy);
+            let everyone_finished =
+                present_anonymous_everyone_finished(everyone_finished, anonymous_policy);
+            let season_completions =
+                present_anonymous_season_completions(season_completions, anonymous_policy);
+            let new_members = present_anonymous_members(new_members, anonymous_policy);
+            let left_members = present_anonymous_members(left_members, anonymous_policy);
+            let renames = present_anonymous_renames(renames, anonymous_policy);
+
+            if !left_members.is_empty() {
+                info!("Members left the leaderboard, scores recomputed: {left_members:?}.");
+            }
+            if !left_members.is_empty() && config::SETTINGS.announce_members_left {
+                if let Err(e) = sender
+                    .send(Event::PrivateLeaderboardMembersLeft(left_members))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+
+            if !renames.is_empty() {
+                if let Err(e) = sender
+                    .send(Event::PrivateLeaderboardMembersRenamed(renames))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+
+            if let Some((name, score)) = new_leader {
+                if let Err(e) = sender
+                    .send(Event::PrivateLeaderboardNewLeader(name, score, timestamp))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+
+            // Conditionnally trigger internal events, base on leaderboard processing.
+            if !new_members.is_empty() {
+                // New members mapped to a Slack user are welcomed in DM.
+                let welcomes = new_members
+                    .iter()
+                    .filter_map(|name| {
+                        identities::slack_user(name).map(|user| (user, name.to_string()))
+                    })
+                    .collect::<Vec<(String, String)>>();
+                if let Err(e) = sender
+                    .send(Event::PrivateLeaderboardNewMembers(new_members))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+                for (user, name) in welcomes {
+                    if let Err(e) = sender.send(Event::WelcomeMember(user, name)).await {
                         let error = BotError::ChannelSend(format!(
                             "Could not send message to MPSC channel. {e}"
                         ));
                         error!("{error}");
                     };
                 }
-                Err(e) => {
-                    let error = BotError::AOC(format!("Could not scrape leaderboard. {e}"));
+            }
+            // Sent right away, completions being batched in their own announcement.
+            for (id, day, part, duration) in first_bloods {
+                let event = Event::PrivateLeaderboardFirstBlood(
+                    id.name.to_string(),
+                    day,
+                    part,
+                    format_duration(duration),
+                );
+                if let Err(e) = sender.send(event).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
                     error!("{error}");
-                }
+                };
+            }
+            if !highlights.is_empty() {
+                if let Err(e) = sender
+                    .send(Event::PrivateLeaderboardNewEntries(highlights))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+            for (day, last, duration) in everyone_finished {
+                let event = Event::PrivateLeaderboardEveryoneFinished(
+                    day,
+                    last,
+                    format_duration_with_days(duration),
+                );
+                if let Err(e) = sender.send(event).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+            for (id, order) in season_completions {
+                let event = Event::PrivateLeaderboardSeasonCompleted(id.name.to_string(), order);
+                if let Err(e) = sender.send(event).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+            if let Some((milestone, total, max)) = star_milestone {
+                if let Err(e) = sender
+                    .send(Event::PrivateLeaderboardStarMilestone(
+                        milestone, total, max,
+                    ))
+                    .await
+                {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+            if let Err(e) = sender
+                .send(Event::PrivateLeaderboardUpdated(year, board, timestamp))
+                .await
+            {
+                let error =
+                    BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+                error!("{error}");
             };
+        }
+        // Nothing is diffed during outages, the next scrape catches up.
+        Err(BotError::Unavailable(reason)) => {
+            debug!("AoC unavailable, leaderboard not scraped. {reason}");
+            report_aoc_status(sender, false).await;
+        }
+        Err(e) => {
+            let error = BotError::AOC(format!("Could not scrape leaderboard. {e}"));
+            error!("{error}");
+        }
+    };
+    HEALTH.record_scrape_ended();
+}
 
-            // Query the next execution time for this job
-            let next_tick = l.next_tick_for_job(uuid).await;
-            match next_tick {
-                Ok(Some(ts)) => info!("Next refresh for private leaderboard at {:?}", ts),
-                _ => error!("Could not get next tick for refresh private leaderboard job"),
-            }
-        })
-    })?;
-    Ok(job)
+/// Reports AoC going down or coming back, once per outage.
+async fn report_aoc_status(sender: &EventBus, available: bool) {
+    if !HEALTH.set_aoc_available(available) {
+        return;
+    }
+    match available {
+        true => info!("AoC is available again."),
+        false => warn!("AoC appears to be down, retrying."),
+    }
+    if config::SETTINGS.announce_aoc_outages {
+        if let Err(e) = sender.send(Event::AocStatusChanged(available)).await {
+            let error =
+                BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+            error!("{error}");
+        };
+    }
 }
 
 async fn watch_global_leaderboard_job(
     schedule: &str,
     cache: MemoryCache,
-    sender: Arc<Sender<Event>>,
+    sender: Arc<EventBus>,
 ) -> BotResult<Job> {
     let job = Job::new_async(schedule, move |_uuid, _l| {
         let cache = cache.clone();
@@ -228,7 +850,10 @@ async fn watch_global_leaderboard_job(
 
         Box::pin(async move {
             let settings = &config::SETTINGS;
-            let aoc_client = AoC::new();
+            let aoc_client = configured_source();
+            if !aoc_client.has_global_leaderboard() {
+                return;
+            }
 
             let mut interval = time::interval(Duration::from_secs(
                 settings.global_leaderboard_polling_interval_sec,
@@ -240,50 +865,62 @@ async fn watch_global_leaderboard_job(
 
             let (year, day) = current_year_day();
 
-            let mut known_hero_hashes: Vec<String> = vec![];
-
             info!("Starting polling Global Leaderboard for day {day}.");
             let mut is_global_leaderboard_complete = false;
             let mut counter = 0;
 
             while !is_global_leaderboard_complete {
-                match aoc_client.global_leaderboard(year, day).await {
+                match aoc_client.fetch_global_day(year, day).await {
                     Ok(global_leaderboard) => {
+                        report_aoc_status(&sender, true).await;
                         is_global_leaderboard_complete =
                             global_leaderboard.leaderboard.is_global_complete();
 
-                        // Scoped to not held data across .await
-                        let hero_entries = {
-                            // check if private members made it to the global leaderboard
-                            let private_leaderboard = cache.data.lock().unwrap();
-                            global_leaderboard
-                                .leaderboard
-                                .get_common_members_with(&private_leaderboard.leaderboard)
-                        };
+                        // check if private members made it to the global leaderboard
+                        let (private_members, quiet) = cache
+                            .read(|data| {
+                                let quiet = identities::quiet_members(&data.leaderboard);
+                                (data.leaderboard.members_ids(), quiet)
+                            })
+                            .await;
+                        let hero_entries = global_leaderboard
+                            .leaderboard
+                            .get_entries_of_members(&private_members);
 
                         for entry in hero_entries {
-                            let entry_hash = entry.to_key();
-                            // If not already known, send shoutout to hero
-                            if !known_hero_hashes.contains(&entry_hash) {
-                                // let (name, part, rank) = &hero_hit;
-                                let (name, part, rank) = (
-                                    entry.id.name.clone(),
-                                    entry.part,
-                                    entry.rank.unwrap_or_default(),
-                                );
-                                if let Err(e) = sender
-                                    .send(Event::GlobalLeaderboardHeroFound((name, part, rank)))
-                                    .await
-                                {
-                                    let error = BotError::ChannelSend(format!(
-                                        "Could not send message to MPSC channel. {e}"
-                                    ));
-                                    error!("{error}");
-                                } else {
-                                    // Announcement successful, let's register the hero.
-                                    known_hero_hashes.push(entry_hash);
-                                };
+                            // Entries of the global leaderboard are always ranked.
+                            let Some(rank) = entry.rank else {
+                                continue;
+                            };
+                            // Kept for the wall of fame, and announced once: heroes already
+                            // recorded (e.g. before a restart) are known.
+                            let new_hero = HEROES.record(HeroAppearance {
+                                member_id: entry.id.numeric,
+                                member: entry.id.name.to_string(),
+                                year,
+                                day,
+                                part: entry.part,
+                                rank: rank.0,
+                                points: rank.points(),
+                            });
+                            if !new_hero {
+                                continue;
                             }
+                            // Quiet members are announced as anonymous members.
+                            let name = match quiet.contains(&entry.id.numeric) {
+                                true => Identifier::anonymous_name(entry.id.numeric),
+                                false => entry.id.name.to_string(),
+                            };
+                            let part = entry.part;
+                            if let Err(e) = sender
+                                .send(Event::GlobalLeaderboardHeroFound((name, part, rank)))
+                                .await
+                            {
+                                let error = BotError::ChannelSend(format!(
+                                    "Could not send message to MPSC channel. {e}"
+                                ));
+                                error!("{error}");
+                            };
                         }
 
                         if is_global_leaderboard_complete {
@@ -293,6 +930,10 @@ async fn watch_global_leaderboard_job(
                                 .statistics_for_year_day(year, day)
                             {
                                 Ok(stats) => {
+                                    // Time for the leaderboard to fill, for the difficulty index
+                                    if let Some(time) = stats.p2_slow {
+                                        DIFFICULTY.record_global(year, day, time);
+                                    }
                                     if let Err(e) = sender
                                         .send(Event::GlobalLeaderboardComplete((day, stats)))
                                         .await
@@ -326,6 +967,16 @@ async fn watch_global_leaderboard_job(
                             }
                         }
                     }
+                    // Polling stops rather than failing the same way until the end of the day.
+                    Err(e @ BotError::Layout(_)) => {
+                        error!("{e}");
+                        alert_layout_changed(&sender, e.to_string()).await;
+                        break;
+                    }
+                    Err(BotError::Unavailable(reason)) => {
+                        debug!("AoC unavailable, global leaderboard not polled. {reason}");
+                        report_aoc_status(&sender, false).await;
+                    }
                     Err(e) => {
                         let error =
                             BotError::AOC(format!("Could not scrape global leaderboard. {e}"));
@@ -341,61 +992,64 @@ async fn watch_global_leaderboard_job(
     Ok(job)
 }
 
-async fn parse_daily_challenge_job(schedule: &str, sender: Arc<Sender<Event>>) -> BotResult<Job> {
+async fn parse_daily_challenge_job(schedule: &str, sender: Arc<EventBus>) -> BotResult<Job> {
     let job = Job::new_async(schedule, move |_uuid, _l| {
         let sender = sender.clone();
         Box::pin(async move {
-            let aoc_client = AoC::new();
+            let aoc_client = configured_source();
 
             let (year, day) = current_year_day();
 
             info!("Retrieving challenge title for day {day}.");
 
-            match aoc_client.daily_challenge(year, day).await {
-                Ok(title) => {
-                    if let Err(e) = sender
-                        .send(Event::DailyChallengeIsUp(day, title.clone()))
-                        .await
-                    {
-                        let error = BotError::ChannelSend(format!(
-                            "Could not send message to MPSC channel. {e}"
-                        ));
-                        error!("{error}");
-                    };
+            let title = match aoc_client.fetch_puzzle_title(year, day).await {
+                Ok(title) => Some(title),
+                // The puzzle is announced anyway, without its title.
+                Err(e @ BotError::Layout(_)) => {
+                    error!("{e}");
+                    alert_layout_changed(&sender, e.to_string()).await;
+                    Some(UNKNOWN_TITLE.to_string())
                 }
                 Err(e) => {
                     let error = BotError::AOC(format!("Could not scrape global leaderboard. {e}"));
                     error!("{error}");
+                    None
                 }
             };
+            if let Some(title) = title {
+                if let Err(e) = sender.send(Event::DailyChallengeIsUp(day, title)).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
         })
     })?;
     Ok(job)
 }
 
+/// Alerts admins that an AoC page cannot be parsed anymore.
+async fn alert_layout_changed(sender: &EventBus, details: String) {
+    if let Err(e) = sender.send(Event::AocLayoutChanged(details)).await {
+        let error = BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+        error!("{error}");
+    };
+}
+
 async fn send_daily_summary_job(
     schedule: &str,
     cache: MemoryCache,
-    sender: Arc<Sender<Event>>,
+    sender: Arc<EventBus>,
 ) -> BotResult<Job> {
     let job = Job::new_async(schedule, move |uuid, mut l| {
         let cache = cache.clone();
         let sender = sender.clone();
         Box::pin(async move {
             let (year, day) = current_year_day();
-            let (p1, p2, delta) = {
-                let leaderboard = cache.data.lock().unwrap();
-                let standings = Standing::new(&leaderboard.leaderboard);
-                let p1 = standings.by_time(&Ranking::PART1, year, day);
-                let p2 = standings.by_time(&Ranking::PART2, year, day);
-                let delta = standings.by_time(&Ranking::DELTA, year, day);
-                (p1, p2, delta)
-            };
+            let summary = daily_summary(&cache, year, day).await;
 
-            if let Err(e) = sender
-                .send(Event::DailySummary(year, day, p1, p2, delta))
-                .await
-            {
+            if let Err(e) = sender.send(summary).await {
                 let error =
                     BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
                 error!("{error}");
@@ -411,3 +1065,369 @@ async fn send_daily_summary_job(
     })?;
     Ok(job)
 }
+
+/// Summary of the fastest members of the day, for each ranking, with the stars earned by the
+/// members so far and the difficulty of the day.
+pub(crate) async fn daily_summary(cache: &MemoryCache, year: i32, day: u8) -> Event {
+    cache
+        .read(move |leaderboard| {
+            let standings = Standing::new(&leaderboard.leaderboard);
+            let p1 = standings.by_time(&Ranking::PART1, year, day);
+            let p2 = standings.by_time(&Ranking::PART2, year, day);
+            let delta = standings.by_time(&Ranking::DELTA, year, day);
+            let progress = collective_progress(&leaderboard.leaderboard, year);
+            let difficulty = (DIFFICULTY.index(year, day), DIFFICULTY.hardest(year));
+            Event::DailySummary(year, day, p1, p2, delta, progress, difficulty)
+        })
+        .await
+}
+
+async fn send_daily_spotlight_job(
+    schedule: &str,
+    cache: MemoryCache,
+    sender: Arc<EventBus>,
+) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        let cache = cache.clone();
+        let sender = sender.clone();
+        Box::pin(async move {
+            let (year, day) = current_year_day();
+
+            let stored = SETTINGS_STORE.get();
+            if stored
+                .spotlights
+                .iter()
+                .any(|(y, d, _member)| *y == year && *d == day)
+            {
+                info!("Spotlight of day {day} already picked.");
+                return;
+            }
+            let spotlights = stored.spotlights.clone();
+            let Some(member) = cache
+                .read(move |leaderboard| {
+                    let quiet = identities::quiet_members(&leaderboard.leaderboard);
+                    pick_spotlight(&leaderboard.leaderboard, year, day, &spotlights, &quiet)
+                })
+                .await
+            else {
+                info!("No member with stars for day {day}, no spotlight.");
+                return;
+            };
+
+            // Recorded first, so that the rotation stays fair even if the announcement fails.
+            SETTINGS_STORE.update(|stored| stored.spotlights.push((year, day, member.numeric)));
+            if let Err(e) = sender
+                .send(Event::DailySpotlight(day, member.name.to_string()))
+                .await
+            {
+                let error =
+                    BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+                error!("{error}");
+            };
+        })
+    })?;
+    Ok(job)
+}
+
+async fn send_daily_standings_job(
+    schedule: &str,
+    cache: MemoryCache,
+    sender: Arc<EventBus>,
+) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        let cache = cache.clone();
+        let sender = sender.clone();
+        Box::pin(async move {
+            // Run before the next unlock, the day ending being the one before it
+            let (year, next_day) = current_year_day();
+            let Some(day) = next_day.checked_sub(1).filter(|day| *day > 0) else {
+                return;
+            };
+
+            let stored = SETTINGS_STORE.get();
+            if stored
+                .standings_snapshots
+                .iter()
+                .any(|snapshot| snapshot.year == year && snapshot.day == day)
+            {
+                info!("Standings of day {day} already posted.");
+                return;
+            }
+            let board = cache
+                .read(move |leaderboard| {
+                    standings_board_iter(&Scoring::LOCAL, &leaderboard.leaderboard, year)
+                        .map(|(id, _scores, score)| (id.numeric, id.name.to_string(), score))
+                        .collect::<Vec<(u64, String, usize)>>()
+                })
+                .await;
+            if board.is_empty() {
+                info!("No standings for day {day}.");
+                return;
+            }
+
+            // Movements are shown against the latest standings of the year, if any.
+            let previous = stored
+                .standings_snapshots
+                .iter()
+                .filter(|snapshot| snapshot.year == year && snapshot.day < day)
+                .max_by_key(|snapshot| snapshot.day)
+                .map(|snapshot| snapshot.positions.clone());
+            let rows = board
+                .iter()
+                .enumerate()
+                .take(DAILY_STANDINGS_ROWS)
+                .map(|(idx, (id, name, score))| {
+                    let movement = match previous.as_ref().map(|positions| positions.get(id)) {
+                        None => String::new(),
+                        Some(None) => "NEW".to_string(),
+                        Some(Some(before)) => match before.cmp(&(idx + 1)) {
+                            Ordering::Greater => format!("▲{}", before - (idx + 1)),
+                            Ordering::Less => format!("▼{}", idx + 1 - before),
+                            Ordering::Equal => String::new(),
+                        },
+                    };
+                    (name.to_string(), *score, movement)
+                })
+                .collect::<Vec<(String, usize, String)>>();
+
+            // Only the standings of the current year are needed to compare.
+            let snapshot = StandingsSnapshot {
+                year,
+                day,
+                positions: board
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, (id, _name, _score))| (*id, idx + 1))
+                    .collect(),
+            };
+            SETTINGS_STORE.update(|stored| {
+                stored
+                    .standings_snapshots
+                    .retain(|snapshot| snapshot.year == year);
+                stored.standings_snapshots.push(snapshot);
+            });
+            if let Err(e) = sender.send(Event::DailyStandings(year, day, rows)).await {
+                let error =
+                    BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+                error!("{error}");
+            };
+        })
+    })?;
+    Ok(job)
+}
+
+async fn watch_github_solutions_job(schedule: &str, sender: Arc<EventBus>) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        let sender = sender.clone();
+        Box::pin(async move {
+            let (year, day) = current_year_day();
+            let Ok(unlock) = Entry::puzzle_unlock(year, day) else {
+                return;
+            };
+            if unlock > now() {
+                return;
+            }
+
+            let stored = SETTINGS_STORE.get();
+            let pending = stored
+                .github_repositories
+                .iter()
+                .filter(|(user, _repository)| {
+                    !stored
+                        .github_solutions
+                        .iter()
+                        .any(|(y, d, linked)| *y == year && *d == day && linked == *user)
+                })
+                .collect::<Vec<(&String, &String)>>();
+            if pending.is_empty() {
+                return;
+            }
+
+            let github = GitHub::new();
+            for (user, repository) in pending {
+                let Some(name) = identities::member_name(user) else {
+                    continue;
+                };
+                let commit = match github.solution_commit(repository, day, unlock).await {
+                    Ok(Some(commit)) => commit,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!("Could not check the commits of {repository}. {e}");
+                        continue;
+                    }
+                };
+
+                // Recorded first, so that a solution is linked once even if the announcement fails.
+                SETTINGS_STORE.update(|stored| {
+                    stored.github_solutions.retain(|(y, _d, _user)| *y == year);
+                    stored.github_solutions.push((year, day, user.to_string()));
+                });
+                let event = Event::GitHubSolution(
+                    name,
+                    day,
+                    repository.to_string(),
+                    commit.sha,
+                    commit.url,
+                );
+                if let Err(e) = sender.send(event).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+        })
+    })?;
+    Ok(job)
+}
+
+async fn send_nudges_job(
+    schedule: &str,
+    cache: MemoryCache,
+    sender: Arc<EventBus>,
+) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        let cache = cache.clone();
+        let sender = sender.clone();
+        Box::pin(async move {
+            let (year, day) = current_year_day();
+
+            // Subscribed members who did not complete the current day yet
+            let subscribers = SETTINGS_STORE
+                .get()
+                .nudge_subscribers
+                .into_iter()
+                .filter_map(|user| identities::member_name(&user).map(|name| (user, name)))
+                .collect::<Vec<(String, String)>>();
+            let nudges = cache
+                .read(move |leaderboard| {
+                    subscribers
+                        .into_iter()
+                        .filter(|(_user, name)| {
+                            !leaderboard.leaderboard.iter().any(|e| {
+                                e.year == year
+                                    && e.day == day
+                                    && e.part == ProblemPart::SECOND
+                                    && *e.id.name == *name
+                            })
+                        })
+                        .collect::<Vec<(String, String)>>()
+                })
+                .await;
+
+            info!("Sending {} nudges for day {day}.", nudges.len());
+
+            for (user, name) in nudges {
+                if let Err(e) = sender.send(Event::NudgeMember(user, name, day)).await {
+                    let error = BotError::ChannelSend(format!(
+                        "Could not send message to MPSC channel. {e}"
+                    ));
+                    error!("{error}");
+                };
+            }
+        })
+    })?;
+    Ok(job)
+}
+
+async fn send_weekly_digest_job(
+    schedule: &str,
+    cache: MemoryCache,
+    sender: Arc<EventBus>,
+) -> BotResult<Job> {
+    let job = Job::new_async(schedule, move |_uuid, _l| {
+        let cache = cache.clone();
+        let sender = sender.clone();
+        Box::pin(async move {
+            let (year, _day) = current_year_day();
+
+            // Kudos received by the solution posts of each member, most praised first
+            let kudos = KUDOS
+                .get()
+                .into_values()
+                .filter(|post| post.year == year)
+                .fold(HashMap::new(), |mut kudos, post| {
+                    *kudos.entry(post.member).or_insert(0) += post.kudos;
+                    kudos
+                })
+                .into_iter()
+                .filter(|(_member, count)| *count > 0)
+                .sorted_by_key(|(member, count)| (Reverse(*count), member.to_string()))
+                .take(DIGEST_KUDOS_ROWS)
+                .collect::<Vec<(String, usize)>>();
+
+            // Appearances of the members on the global leaderboard so far this season
+            let heroes = HEROES.wall_of_fame(year);
+
+            // Chances of the members to win the season, simulating the remaining days
+            let runs = config::SETTINGS.simulation_runs;
+            let odds = cache
+                .read(move |leaderboard| {
+                    win_probabilities(&leaderboard.leaderboard, year, runs)
+                        .into_iter()
+                        .map(|(name, p)| (name, (p * 100.0).round() as usize))
+                        .filter(|(_name, percent)| *percent > 0)
+                        .take(DIGEST_ODDS_ROWS)
+                        .collect::<Vec<(String, usize)>>()
+                })
+                .await;
+
+            // Members quickest to the first star, with their (median, best, worst) times
+            let first_stars = cache
+                .read(move |leaderboard| {
+                    first_star_latencies(&leaderboard.leaderboard, year)
+                        .into_iter()
+                        .take(DIGEST_FIRST_STAR_ROWS)
+                        .map(|(id, best, median, worst)| {
+                            (
+                                id.name.to_string(),
+                                format_duration(median),
+                                format_duration(best),
+                                format_duration(worst),
+                            )
+                        })
+                        .collect::<Vec<(String, String, String, String)>>()
+                })
+                .await;
+
+            // Hardest day of the season so far
+            let hardest = DIFFICULTY.hardest(year);
+
+            if kudos.is_empty()
+                && heroes.is_empty()
+                && odds.is_empty()
+                && hardest.is_none()
+                && first_stars.is_empty()
+            {
+                info!("Nothing to recap this week, skipping weekly digest.");
+                return;
+            }
+            info!("Sending weekly digest.");
+
+            if let Err(e) = sender
+                .send(Event::WeeklyDigest(
+                    year,
+                    kudos,
+                    heroes,
+                    odds,
+                    hardest,
+                    first_stars,
+                ))
+                .await
+            {
+                let error =
+                    BotError::ChannelSend(format!("Could not send message to MPSC channel. {e}"));
+                error!("{error}");
+            };
+        })
+    })?;
+    Ok(job)
+}
+
+/// (member, local score) of the first member of the private leaderboard for the year.
+fn leader(leaderboard: &Leaderboard, year: i32) -> Option<(Identifier, usize)> {
+    standings_board_iter(&Scoring::LOCAL, leaderboard, year)
+        .next()
+        .filter(|(_id, _scores, score)| *score > 0)
+        .map(|(id, _scores, score)| (id.clone(), score))
+}
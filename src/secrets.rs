@@ -0,0 +1,155 @@
+use crate::{
+    config::{self, SecretProvider},
+    error::{BotError, BotResult},
+};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+use tokio::time;
+use tracing::{error, info};
+
+// Keys of the secrets in the secret manager, named like the settings they replace
+const AOC_SESSION_COOKIE: &'static str = "aoc_session_cookie";
+const SLACK_TOKEN: &'static str = "slack_token";
+const SLACK_APP_TOKEN: &'static str = "slack_app_token";
+const SLACK_SIGNING_SECRET: &'static str = "slack_signing_secret";
+const GITHUB_TOKEN: &'static str = "github_token";
+
+// Secrets last fetched from the secret manager, by key
+static SECRETS: Lazy<RwLock<HashMap<String, String>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn fetched(key: &str) -> Option<String> {
+    SECRETS.read().unwrap().get(key).cloned()
+}
+
+/// Session cookie of the AoC member the private leaderboard is fetched with.
+pub fn aoc_session_cookie() -> String {
+    fetched(AOC_SESSION_COOKIE).unwrap_or_else(|| config::SETTINGS.aoc_session_cookie.to_string())
+}
+
+/// Bot token of the Slack app.
+pub fn slack_token() -> String {
+    fetched(SLACK_TOKEN).unwrap_or_else(|| config::SETTINGS.slack_token.to_string())
+}
+
+/// App-level token of the Slack app, required by Socket Mode.
+pub fn slack_app_token() -> Option<String> {
+    fetched(SLACK_APP_TOKEN).or_else(|| config::SETTINGS.slack_app_token.clone())
+}
+
+/// Signing secret of the Slack app, required by the Events API.
+pub fn slack_signing_secret() -> Option<String> {
+    fetched(SLACK_SIGNING_SECRET).or_else(|| config::SETTINGS.slack_signing_secret.clone())
+}
+
+/// Token of the GitHub API, if any.
+pub fn github_token() -> Option<String> {
+    fetched(GITHUB_TOKEN).or_else(|| config::SETTINGS.github_token.clone())
+}
+
+/// Fetches the secrets from the configured secret manager, if any. Secrets missing from the
+/// secret manager are read from the settings.
+pub async fn load() -> BotResult<()> {
+    let settings = &config::SETTINGS;
+    let secrets = match settings.get_secret_provider() {
+        None => return Ok(()),
+        Some(SecretProvider::VAULT) => fetch_from_vault().await?,
+        Some(SecretProvider::AWS) => fetch_from_aws().await?,
+    };
+    let secrets = [
+        AOC_SESSION_COOKIE,
+        SLACK_TOKEN,
+        SLACK_APP_TOKEN,
+        SLACK_SIGNING_SECRET,
+        GITHUB_TOKEN,
+    ]
+    .into_iter()
+    .filter_map(|key| match secrets.get(key) {
+        Some(Value::String(secret)) => Some((key.to_string(), secret.to_string())),
+        _ => None,
+    })
+    .collect::<HashMap<String, String>>();
+
+    info!("{} secrets loaded from the secret manager.", secrets.len());
+    *SECRETS.write().unwrap() = secrets;
+    Ok(())
+}
+
+/// Fetches the secrets again every `secrets_refresh_min` minutes, e.g. after they are rotated.
+/// The secrets fetched last are kept when the secret manager cannot be reached.
+pub async fn refresh_periodically() {
+    let settings = &config::SETTINGS;
+    if settings.get_secret_provider().is_none() {
+        return;
+    }
+    let period = Duration::from_secs(60 * settings.secrets_refresh_min.max(1));
+    let mut interval = time::interval_at(time::Instant::now() + period, period);
+    loop {
+        interval.tick().await;
+        if let Err(e) = load().await {
+            error!("Could not refresh secrets. {e}");
+        }
+    }
+}
+
+/// Secret of the KV secrets engine at `vault_secret_path`, either version 1 or 2.
+async fn fetch_from_vault() -> BotResult<serde_json::Map<String, Value>> {
+    let settings = &config::SETTINGS;
+    let (Some(addr), Some(token), Some(path)) = (
+        &settings.vault_addr,
+        &settings.vault_token,
+        &settings.vault_secret_path,
+    ) else {
+        return Err(BotError::Config(
+            "VAULT_ADDR, VAULT_TOKEN and VAULT_SECRET_PATH are required to use Vault.".to_string(),
+        ));
+    };
+
+    let url = format!(
+        "{}/v1/{}",
+        addr.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+    let body = reqwest::Client::new()
+        .get(url)
+        .header("X-Vault-Token", token)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let response = serde_json::from_str::<Value>(&body)
+        .map_err(|e| BotError::Config(format!("Could not parse Vault response. {e}")))?;
+
+    // Version 2 of the engine nests the secret, along with its metadata.
+    let data = &response["data"];
+    match data.get("data").unwrap_or(data) {
+        Value::Object(secrets) => Ok(secrets.clone()),
+        _ => Err(BotError::Config(format!(
+            "No secret found in Vault at {path}."
+        ))),
+    }
+}
+
+/// Secret of AWS Secrets Manager named `aws_secret_id`, stored as a JSON object.
+async fn fetch_from_aws() -> BotResult<serde_json::Map<String, Value>> {
+    let settings = &config::SETTINGS;
+    let secret_id = settings.aws_secret_id.as_ref().ok_or(BotError::Config(
+        "AWS_SECRET_ID is required to use AWS Secrets Manager.".to_string(),
+    ))?;
+
+    let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let output = aws_sdk_secretsmanager::Client::new(&aws_config)
+        .get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await
+        .map_err(|e| BotError::Config(format!("Could not fetch secret {secret_id}. {e}")))?;
+
+    match output.secret_string().map(serde_json::from_str::<Value>) {
+        Some(Ok(Value::Object(secrets))) => Ok(secrets),
+        _ => Err(BotError::Config(format!(
+            "Secret {secret_id} is not a JSON object."
+        ))),
+    }
+}
@@ -0,0 +1,30 @@
+use crate::systemd;
+use once_cell::sync::Lazy;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// Cancelled when the bot is asked to stop. Components finish their work in flight and stop
+/// accepting new work.
+pub static SHUTDOWN: Lazy<CancellationToken> = Lazy::new(CancellationToken::new);
+
+/// Waits for SIGTERM or SIGINT, then triggers the shutdown.
+pub async fn watch_signals() {
+    let (mut terminate, mut interrupt) = match (
+        signal(SignalKind::terminate()),
+        signal(SignalKind::interrupt()),
+    ) {
+        (Ok(terminate), Ok(interrupt)) => (terminate, interrupt),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Could not register shutdown signal handlers. {e}");
+            return;
+        }
+    };
+    let name = tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = interrupt.recv() => "SIGINT",
+    };
+    info!("{name} received, shutting down.");
+    systemd::notify_stopping();
+    SHUTDOWN.cancel();
+}
@@ -1,17 +1,479 @@
-use crate::core::leaderboard::ScrapedLeaderboard;
-use std::sync::{Arc, Mutex};
+use crate::{
+    config,
+    core::leaderboard::{ProblemPart, ScrapedLeaderboard},
+    error::{BotError, BotResult},
+};
+use chrono::{Duration, Utc};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, RwLock,
+    },
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time,
+};
+use tracing::error;
 
-type SharedLeaderboard = Arc<Mutex<ScrapedLeaderboard>>;
+// Operation on the scraped leaderboard, run by the state manager
+type StateOperation = Box<dyn FnOnce(&mut ScrapedLeaderboard) + Send>;
 
+pub static SETTINGS_STORE: Lazy<SettingsStore> =
+    Lazy::new(|| JsonStore::load(&config::SETTINGS.settings_store_file));
+pub static DEAD_LETTERS: Lazy<DeadLetterQueue> =
+    Lazy::new(|| JsonStore::load(&config::SETTINGS.dead_letters_file));
+pub static KUDOS: Lazy<KudosStore> = Lazy::new(|| JsonStore::load(&config::SETTINGS.kudos_file));
+pub static AUDIT: Lazy<AuditLog> = Lazy::new(|| JsonStore::load(&config::SETTINGS.audit_file));
+pub static HEROES: Lazy<HeroStore> = Lazy::new(|| JsonStore::load(&config::SETTINGS.heroes_file));
+pub static DIFFICULTY: Lazy<DifficultyStore> =
+    Lazy::new(|| JsonStore::load(&config::SETTINGS.difficulty_file));
+// Other private leaderboards by name, as last scraped. Not persisted, they are scraped again
+// at startup.
+pub static OTHER_BOARDS: Lazy<RwLock<HashMap<String, ScrapedLeaderboard>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+// Number of commands kept in the audit log, the oldest being dropped first
+const AUDIT_MAX_ENTRIES: usize = 1000;
+// Commands are recorded in memory, the audit log being persisted at this interval if it changed
+const AUDIT_FLUSH_SEC: u64 = 30;
+// Times the global leaderboard took to fill, in minutes, for the easiest (0/10) and the hardest
+// (10/10) days. Days in between are placed on a logarithmic scale.
+const DIFFICULTY_EASY_MINUTES: f64 = 5.0;
+const DIFFICULTY_HARD_MINUTES: f64 = 120.0;
+
+/// Handle to the state manager, the task owning the scraped leaderboard. Operations are run
+/// one at a time in the order they are sent, so reads never see a partial update and callers
+/// never block on a lock.
 #[derive(Clone)]
 pub struct MemoryCache {
-    pub data: SharedLeaderboard,
+    operations: mpsc::UnboundedSender<StateOperation>,
 }
 
 impl MemoryCache {
+    /// Starts the state manager, with an empty leaderboard.
     pub fn new() -> MemoryCache {
-        MemoryCache {
-            data: Arc::new(Mutex::new(ScrapedLeaderboard::new())),
+        let (operations, mut pending) = mpsc::unbounded_channel::<StateOperation>();
+        tokio::spawn(async move {
+            let mut data = ScrapedLeaderboard::new();
+            while let Some(operation) = pending.recv().await {
+                operation(&mut data);
+            }
+        });
+        MemoryCache { operations }
+    }
+
+    /// Result of a read of the scraped leaderboard.
+    pub async fn read<F, R>(&self, read: F) -> R
+    where
+        F: FnOnce(&ScrapedLeaderboard) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.update(move |data| read(data)).await
+    }
+
+    /// Result of an update of the scraped leaderboard.
+    pub async fn update<F, R>(&self, update: F) -> R
+    where
+        F: FnOnce(&mut ScrapedLeaderboard) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let operation: StateOperation = Box::new(move |data| {
+            let _ = reply.send(update(data));
+        });
+        self.operations
+            .send(operation)
+            .unwrap_or_else(|_| panic!("State manager stopped."));
+        result.await.expect("State manager stopped.")
+    }
+}
+
+/// Settings edited at runtime by admins, overriding the static configuration.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StoredSettings {
+    pub default_channel: Option<String>,
+    pub monitoring_channel: Option<String>,
+    pub default_year: Option<i32>,
+    // Names of the events not announced anymore
+    #[serde(default)]
+    pub muted_events: Vec<String>,
+    // AoC member name => Slack user id, registered with `!iam`
+    #[serde(default)]
+    pub identities: HashMap<String, String>,
+    // Slack user id => (AoC member id, verification code) of the identities claimed with `!iam`,
+    // until verified or approved by an admin
+    #[serde(default)]
+    pub identity_claims: HashMap<String, (u64, String)>,
+    // Slack user ids of the members subscribed to evening nudges
+    #[serde(default)]
+    pub nudge_subscribers: Vec<String>,
+    // Slack user ids of the members who asked not to be named in announcements
+    #[serde(default)]
+    pub quiet_members: Vec<String>,
+    // Channel id => ts of the pinned standings message edited after each scrape
+    #[serde(default)]
+    pub standings_messages: HashMap<String, String>,
+    // (year, day, AoC member id) of the members put in the daily spotlight, oldest first
+    #[serde(default)]
+    pub spotlights: Vec<(i32, u8, u64)>,
+    // Positions of the members in the local standings at the end of the days, oldest first
+    #[serde(default)]
+    pub standings_snapshots: Vec<StandingsSnapshot>,
+    // Slack user id => GitHub repository the member shares their solutions in, e.g.
+    // "octocat/advent-of-code", registered with `!github`
+    #[serde(default)]
+    pub github_repositories: HashMap<String, String>,
+    // (year, day, Slack user id) of the solutions linked from GitHub, oldest first
+    #[serde(default)]
+    pub github_solutions: Vec<(i32, u8, String)>,
+}
+
+/// Positions of the members in the local standings at the end of a day, to show how they moved
+/// the next day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandingsSnapshot {
+    pub year: i32,
+    pub day: u8,
+    // AoC member id => position, the first being 1
+    pub positions: HashMap<u64, usize>,
+}
+
+/// Failed Slack message, kept to be replayed later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub channel_id: String,
+    pub thread_ts: Option<String>,
+    pub text: String,
+    pub error: String,
+}
+
+/// Solution post of a member in a daily solutions thread, with the kudos reactions it received.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolutionPost {
+    pub member: String,
+    pub user: String,
+    pub year: i32,
+    pub day: u8,
+    pub kudos: usize,
+}
+
+/// Command received from Slack, with the outcome of its handling once handled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub channel_id: String,
+    // ts of the command message
+    pub ts: String,
+    pub user_id: Option<String>,
+    pub input: String,
+    // Reception time, in milliseconds since epoch
+    pub received: i64,
+    // "ok", or why the command was not answered as expected. None while not handled yet.
+    pub result: Option<String>,
+    pub latency_ms: Option<i64>,
+}
+
+/// Appearance of a private member on the global leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeroAppearance {
+    pub member_id: u64,
+    pub member: String,
+    pub year: i32,
+    pub day: u8,
+    pub part: ProblemPart,
+    pub rank: u16,
+    // Global score earned with the rank
+    pub points: usize,
+}
+
+/// Difficulty of a day: votes of the members, and the time the global leaderboard took to fill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayDifficulty {
+    pub year: i32,
+    pub day: u8,
+    // Slack user id => vote, from 1 (easy) to 10 (hard)
+    #[serde(default)]
+    pub votes: HashMap<String, u8>,
+    // Time for the 100th member of the global leaderboard to get both stars, in seconds
+    pub global_time_sec: Option<i64>,
+}
+
+/// Data persisted to a JSON file, loaded at startup.
+pub struct JsonStore<T> {
+    pub data: RwLock<T>,
+    file: String,
+    // Whether the data changed in memory since it was last persisted
+    changed: AtomicBool,
+    // Held while the store file is written, so that the latest data is written last
+    writing: Mutex<()>,
+}
+
+pub type SettingsStore = JsonStore<StoredSettings>;
+pub type DeadLetterQueue = JsonStore<Vec<DeadLetter>>;
+// Message ts => solution post
+pub type KudosStore = JsonStore<HashMap<String, SolutionPost>>;
+pub type AuditLog = JsonStore<Vec<AuditEntry>>;
+pub type HeroStore = JsonStore<Vec<HeroAppearance>>;
+pub type DifficultyStore = JsonStore<Vec<DayDifficulty>>;
+
+impl<T: Serialize + DeserializeOwned + Default + Clone + Send + Sync + 'static> JsonStore<T> {
+    /// Loads data from the store file, if any.
+    pub fn load(file: &str) -> JsonStore<T> {
+        let data = match fs::read_to_string(Path::new(file)) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("Invalid store file '{file}', ignoring it. {e}");
+                T::default()
+            }),
+            Err(_) => T::default(),
+        };
+        JsonStore {
+            data: RwLock::new(data),
+            file: file.to_string(),
+            changed: AtomicBool::new(false),
+            writing: Mutex::new(()),
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.data.read().unwrap().clone()
+    }
+
+    /// Persists the current data to the store file.
+    pub fn flush(&self) -> BotResult<()> {
+        let _writing = self.writing.lock().unwrap();
+        self.changed.store(false, Ordering::SeqCst);
+        self.write().map_err(|e| {
+            self.changed.store(true, Ordering::SeqCst);
+            e
+        })
+    }
+
+    /// Persists the data if it changed in memory since it was last persisted.
+    pub fn flush_changes(&self) -> BotResult<()> {
+        let _writing = self.writing.lock().unwrap();
+        if !self.changed.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.write().map_err(|e| {
+            self.changed.store(true, Ordering::SeqCst);
+            e
+        })
+    }
+
+    fn write(&self) -> BotResult<()> {
+        let content = serde_json::to_string_pretty(&*self.data.read().unwrap())
+            .map_err(|e| BotError::IO(e.to_string()))?;
+        fs::write(&self.file, content)?;
+        Ok(())
+    }
+
+    /// Changes the data in memory, under the lock so that concurrent changes are all kept. The
+    /// change is persisted by the next flush.
+    pub fn modify<R>(&self, change: impl FnOnce(&mut T) -> R) -> R {
+        let result = change(&mut self.data.write().unwrap());
+        self.changed.store(true, Ordering::SeqCst);
+        result
+    }
+
+    /// Changes the data under the lock, so that concurrent changes are all kept, and persists
+    /// it off the async tasks. Failures to persist are logged, the data being persisted again
+    /// with the next change or at exit.
+    pub fn update<R>(&'static self, change: impl FnOnce(&mut T) -> R) -> R {
+        let result = self.modify(change);
+        let persist = move || {
+            if let Err(e) = self.flush_changes() {
+                error!("Could not save store file '{}'. {e}", self.file);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn_blocking(persist)),
+            Err(_) => persist(),
+        }
+        result
+    }
+}
+
+/// Persists the stores loaded so far, before exiting.
+pub fn flush_stores() {
+    let flushed = [
+        Lazy::get(&SETTINGS_STORE).map(|store| store.flush()),
+        Lazy::get(&DEAD_LETTERS).map(|store| store.flush()),
+        Lazy::get(&KUDOS).map(|store| store.flush()),
+        Lazy::get(&AUDIT).map(|store| store.flush()),
+        Lazy::get(&HEROES).map(|store| store.flush()),
+        Lazy::get(&DIFFICULTY).map(|store| store.flush()),
+    ];
+    for e in flushed
+        .into_iter()
+        .flatten()
+        .filter_map(|flushed| flushed.err())
+    {
+        error!("Could not flush store. {e}");
+    }
+}
+
+/// Persists the audit log every `AUDIT_FLUSH_SEC` seconds if commands were recorded meanwhile,
+/// rather than on each command.
+pub async fn flush_audit_periodically() {
+    let period = std::time::Duration::from_secs(AUDIT_FLUSH_SEC);
+    let mut interval = time::interval_at(time::Instant::now() + period, period);
+    loop {
+        interval.tick().await;
+        match tokio::task::spawn_blocking(|| AUDIT.flush_changes()).await {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => error!("Could not save audit log. {e}"),
+            Err(e) => error!("Could not save audit log. {e}"),
+        }
+    }
+}
+
+impl AuditLog {
+    /// Records a command as received, not handled yet.
+    pub fn received(&self, entry: AuditEntry) {
+        self.modify(|entries| {
+            entries.push(entry);
+            if entries.len() > AUDIT_MAX_ENTRIES {
+                entries.drain(..entries.len() - AUDIT_MAX_ENTRIES);
+            }
+        });
+    }
+
+    /// Records the outcome of a received command, and how long it took to handle it.
+    pub fn handled(&self, channel_id: &str, ts: &str, result: String) {
+        self.modify(|entries| {
+            let entry = entries
+                .iter_mut()
+                .rev()
+                .find(|entry| entry.channel_id == channel_id && entry.ts == ts);
+            if let Some(entry) = entry {
+                entry.latency_ms = Some(Utc::now().timestamp_millis() - entry.received);
+                entry.result = Some(result);
+            }
+        });
+    }
+
+    /// Latest entries, newest first.
+    pub fn latest(&self, n: usize) -> Vec<AuditEntry> {
+        self.get().into_iter().rev().take(n).collect()
+    }
+}
+
+impl HeroStore {
+    /// Records an appearance, unless the member already appeared for this part. Returns whether
+    /// the appearance is new, the heroes known surviving restarts.
+    pub fn record(&'static self, appearance: HeroAppearance) -> bool {
+        self.update(|appearances| {
+            let known = appearances.iter().any(|known| {
+                known.member_id == appearance.member_id
+                    && known.year == appearance.year
+                    && known.day == appearance.day
+                    && known.part == appearance.part
+            });
+            if !known {
+                appearances.push(appearance);
+            }
+            !known
+        })
+    }
+
+    /// (member, appearances, points, best rank) of the members who appeared on the global
+    /// leaderboard during the year, most points first.
+    pub fn wall_of_fame(&self, year: i32) -> Vec<(String, usize, usize, u16)> {
+        self.get()
+            .into_iter()
+            .filter(|appearance| appearance.year == year)
+            .into_group_map_by(|appearance| appearance.member_id)
+            .into_values()
+            .filter_map(|appearances| {
+                // Latest name of the member
+                let member = appearances.last()?.member.clone();
+                let points = appearances.iter().map(|a| a.points).sum();
+                let best_rank = appearances.iter().map(|a| a.rank).min()?;
+                Some((member, appearances.len(), points, best_rank))
+            })
+            .sorted_by_key(|(member, _appearances, points, best_rank)| {
+                (std::cmp::Reverse(*points), *best_rank, member.to_string())
+            })
+            .collect()
+    }
+}
+
+impl DifficultyStore {
+    /// Records the vote of a member for a day, replacing their previous vote.
+    pub fn vote(&'static self, year: i32, day: u8, user: &str, vote: u8) {
+        self.update(|days| {
+            Self::day_mut(days, year, day)
+                .votes
+                .insert(user.to_string(), vote);
+        });
+    }
+
+    /// Records the time the global leaderboard took to fill for a day.
+    pub fn record_global(&'static self, year: i32, day: u8, time: Duration) {
+        self.update(|days| {
+            Self::day_mut(days, year, day).global_time_sec = Some(time.num_seconds());
+        });
+    }
+
+    /// Difficulty index of a day out of 10, if voted on or timed on the global leaderboard.
+    pub fn index(&self, year: i32, day: u8) -> Option<f64> {
+        self.get()
+            .iter()
+            .find(|known| known.year == year && known.day == day)
+            .and_then(DayDifficulty::index)
+    }
+
+    /// (day, difficulty index) of the hardest day of the year so far.
+    pub fn hardest(&self, year: i32) -> Option<(u8, f64)> {
+        self.get()
+            .iter()
+            .filter(|known| known.year == year)
+            .filter_map(|known| Some((known.day, known.index()?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    fn day_mut(days: &mut Vec<DayDifficulty>, year: i32, day: u8) -> &mut DayDifficulty {
+        match days
+            .iter()
+            .position(|known| known.year == year && known.day == day)
+        {
+            Some(idx) => &mut days[idx],
+            None => {
+                days.push(DayDifficulty {
+                    year,
+                    day,
+                    votes: HashMap::new(),
+                    global_time_sec: None,
+                });
+                days.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+impl DayDifficulty {
+    /// Average vote of the members blended with the time the global leaderboard took to fill,
+    /// out of 10. Either is used alone when the other is missing.
+    pub fn index(&self) -> Option<f64> {
+        let votes = (!self.votes.is_empty()).then(|| {
+            self.votes.values().map(|vote| *vote as f64).sum::<f64>() / self.votes.len() as f64
+        });
+        let global = self.global_time_sec.map(|sec| {
+            let minutes =
+                (sec as f64 / 60.0).clamp(DIFFICULTY_EASY_MINUTES, DIFFICULTY_HARD_MINUTES);
+            10.0 * (minutes / DIFFICULTY_EASY_MINUTES).ln()
+                / (DIFFICULTY_HARD_MINUTES / DIFFICULTY_EASY_MINUTES).ln()
+        });
+        let weight = config::SETTINGS.difficulty_vote_weight.clamp(0.0, 1.0);
+        match (votes, global) {
+            (Some(votes), Some(global)) => Some(weight * votes + (1.0 - weight) * global),
+            (votes, global) => votes.or(global),
         }
     }
 }
@@ -0,0 +1,26 @@
+//! Notifications to systemd, when the bot runs as a `Type=notify` service. They do nothing
+//! otherwise.
+
+use sd_notify::NotifyState;
+use tracing::warn;
+
+fn notify(state: NotifyState) {
+    if let Err(e) = sd_notify::notify(false, &[state]) {
+        warn!("Could not notify systemd. {e}");
+    }
+}
+
+/// The bot is connected to Slack and ready to announce.
+pub fn notify_ready() {
+    notify(NotifyState::Ready);
+}
+
+/// The bot is shutting down.
+pub fn notify_stopping() {
+    notify(NotifyState::Stopping);
+}
+
+/// The bot is alive, resetting the watchdog timer (`WatchdogSec`) of the service.
+pub fn notify_watchdog() {
+    notify(NotifyState::Watchdog);
+}
@@ -1,17 +1,34 @@
-use crate::core::leaderboard::{Entry, Leaderboard};
-use chrono::{Datelike, Duration, Utc};
+use crate::{
+    config::AnonymousPolicy,
+    core::{
+        leaderboard::{day_index, Entry, Identifier, Leaderboard, ProblemPart},
+        source::PUZZLE_EVENT,
+    },
+};
+use chrono::{DateTime, Datelike, Duration, Utc};
 use itertools::Itertools;
+use rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng};
 use serde::Serialize;
 use std::{
     cmp::Reverse,
     collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
 };
+use tracing::warn;
+
+// Offset of the AoC server clock from the host clock, in milliseconds
+static CLOCK_SKEW_MS: AtomicI64 = AtomicI64::new(0);
+// Offsets below this are noise, the Date header of HTTP responses having a 1 second resolution
+const CLOCK_SKEW_TOLERANCE_MS: i64 = 2000;
 
 pub fn exponential_decay(max: f32, decay_rate: f32, time: i32) -> usize {
     (max * (1.0 - decay_rate).powi(time)).round() as usize
 }
 
-pub fn ordinal_number_suffix(num: u8) -> &'static str {
+pub fn ordinal_number_suffix(num: u16) -> &'static str {
     let s = num.to_string();
     if s.ends_with('1') && !s.ends_with("11") {
         "st"
@@ -24,12 +41,63 @@ pub fn ordinal_number_suffix(num: u8) -> &'static str {
     }
 }
 
-pub fn format_rank(rank: u8) -> String {
+/// Escape control characters of Slack messages (see https://api.slack.com/reference/surfaces/formatting#escaping).
+pub fn escape_slack_entities(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape control characters and neutralize markup characters, which Slack offers no way to
+/// escape, by replacing them with look-alike characters.
+pub fn escape_slack(s: &str) -> String {
+    escape_slack_entities(s)
+        .replace('*', "∗")
+        .replace('_', "＿")
+        .replace('~', "∼")
+        .replace('`', "ˋ")
+}
+
+/// Number of single character insertions, deletions or substitutions turning a into b.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+pub fn format_rank(rank: u16) -> String {
     format!("{}{}", rank, ordinal_number_suffix(rank))
 }
 
+/// Records the time given by the AoC server (e.g. the Date header of a response), so that
+/// release times are compared to the AoC clock rather than to a skewed host clock.
+pub fn record_server_time(server_time: DateTime<Utc>) {
+    let skew = (server_time - Utc::now()).num_milliseconds();
+    let skew = match skew.abs() < CLOCK_SKEW_TOLERANCE_MS {
+        true => 0,
+        false => skew,
+    };
+    let previous = CLOCK_SKEW_MS.swap(skew, Ordering::Relaxed);
+    if skew != 0 && (skew - previous).abs() >= CLOCK_SKEW_TOLERANCE_MS {
+        warn!("AoC server clock is {skew}ms ahead of the host clock, using the AoC clock.");
+    }
+}
+
+/// Current time of the AoC server, the host time corrected by the last known skew.
+pub fn now() -> DateTime<Utc> {
+    Utc::now() + Duration::milliseconds(CLOCK_SKEW_MS.load(Ordering::Relaxed))
+}
+
 pub fn current_year_day() -> (i32, u8) {
-    let now = Utc::now();
+    let now = now();
     let year = now.year();
 
     // We start taking the current year into account 10 days before the first puzzle unlocks.
@@ -42,7 +110,7 @@ pub fn current_year_day() -> (i32, u8) {
         true => year,
         false => year - 1,
     };
-    let day = now.day() as u8;
+    let day = PUZZLE_EVENT.day_at(year, now);
     (year, day)
 }
 
@@ -65,9 +133,230 @@ pub fn format_duration_with_days(duration: Duration) -> String {
 }
 
 pub fn get_new_members(cur: &Leaderboard, new: &Leaderboard) -> Vec<String> {
-    let cur = cur.iter().map(|e| &e.id.name).collect::<HashSet<&String>>();
-    let new = new.iter().map(|e| &e.id.name).collect::<HashSet<&String>>();
-    new.difference(&cur).map(|n| n.to_string()).collect()
+    let cur = cur.members_ids();
+    new.iter()
+        .map(|e| &e.id)
+        .filter(|id| !cur.contains(&id.numeric))
+        .unique()
+        .map(|id| id.name.to_string())
+        .collect()
+}
+
+/// (former name, new name) of the members whose display name changed between the current
+/// leaderboard and the new one.
+pub fn get_renamed_members(cur: &Leaderboard, new: &Leaderboard) -> Vec<(String, String)> {
+    let cur = cur
+        .iter()
+        .map(|e| (e.id.numeric, &e.id.name))
+        .collect::<HashMap<u64, &Arc<str>>>();
+    new.iter()
+        .map(|e| &e.id)
+        .unique()
+        .filter_map(|id| match cur.get(&id.numeric) {
+            Some(&name) if *name != id.name => Some((name.to_string(), id.name.to_string())),
+            _ => None,
+        })
+        .sorted()
+        .collect()
+}
+
+/// (member, day, part, time since release) of the first completions of the parts of the year in
+/// the new leaderboard, for parts nobody had completed in the current one. Only completions of
+/// new days are considered, within a day of their release.
+pub fn get_first_bloods(
+    cur: &Leaderboard,
+    new: &Leaderboard,
+    year: i32,
+) -> Vec<(Identifier, u8, ProblemPart, Duration)> {
+    let completed = cur
+        .iter()
+        .filter(|e| e.year == year)
+        .map(|e| (e.day, e.part))
+        .collect::<HashSet<(u8, ProblemPart)>>();
+    new.iter()
+        .filter(|e| e.year == year && !completed.contains(&(e.day, e.part)))
+        .into_group_map_by(|e| (e.day, e.part))
+        .into_iter()
+        .filter_map(|((day, part), entries)| {
+            let first = entries.into_iter().min_by_key(|e| (e.timestamp, &e.id))?;
+            let duration = first.duration_since_release().ok()?;
+            (duration < Duration::days(1)).then(|| (first.id.clone(), day, part, duration))
+        })
+        .sorted_by_key(|(_id, day, part, _duration)| (*day, *part))
+        .collect()
+}
+
+/// Days of the year all the members have now completed, with the last member to finish and the
+/// time it took them since the release. Every member of the private leaderboard is waited for,
+/// including those without any star yet, but the excluded ones. The group needs at least two
+/// members for it to be a milestone.
+pub fn get_everyone_finished(
+    cur: &Leaderboard,
+    new: &Leaderboard,
+    year: i32,
+    excluded: &[u64],
+) -> Vec<(u8, Identifier, Duration)> {
+    let roster = new
+        .members_for_year(year)
+        .into_iter()
+        .filter(|id| !excluded.contains(id))
+        .collect::<HashSet<u64>>();
+    if roster.len() < 2 {
+        return vec![];
+    }
+    let known = cur.members_ids();
+    let completed = cur
+        .iter()
+        .filter(|e| e.year == year && e.part == ProblemPart::SECOND)
+        .map(|e| (e.id.numeric, e.day))
+        .collect::<HashSet<(u64, u8)>>();
+    new.iter()
+        .filter(|e| {
+            e.year == year && e.part == ProblemPart::SECOND && roster.contains(&e.id.numeric)
+        })
+        .into_group_map_by(|e| e.day)
+        .into_iter()
+        .filter(|(_day, entries)| entries.len() == roster.len())
+        .filter_map(|(day, entries)| {
+            let last = entries.into_iter().max_by_key(|e| (e.timestamp, &e.id))?;
+            // Already announced, or caught up at once by a member who just joined.
+            if completed.contains(&(last.id.numeric, day)) || !known.contains(&last.id.numeric) {
+                return None;
+            }
+            let duration = last.duration_since_release().ok()?;
+            Some((day, last.id.clone(), duration))
+        })
+        .sorted_by_key(|(day, _id, _duration)| *day)
+        .collect()
+}
+
+/// Highest milestone of total stars for the year crossed by the new leaderboard, with its total
+/// stars and the maximum the members of the year could collect over the season. A current
+/// leaderboard without any star for the year is not trusted (e.g. failed initialization).
+pub fn get_star_milestone(
+    cur: &Leaderboard,
+    new: &Leaderboard,
+    year: i32,
+    milestones: &[usize],
+) -> Option<(usize, usize, usize)> {
+    let before = cur.iter().filter(|e| e.year == year).count();
+    let after = new.iter().filter(|e| e.year == year).count();
+    if before == 0 {
+        return None;
+    }
+    let milestone = milestones
+        .iter()
+        .filter(|m| before < **m && **m <= after)
+        .max()?;
+    let (total, max) = collective_progress(new, year);
+    Some((*milestone, total, max))
+}
+
+/// Stars earned during the year by the members of the leaderboard, with the maximum possible
+/// for the members active that year.
+pub fn collective_progress(leaderboard: &Leaderboard, year: i32) -> (usize, usize) {
+    let total = leaderboard.iter().filter(|e| e.year == year).count();
+    let n_members = leaderboard
+        .iter()
+        .filter(|e| e.year == year)
+        .map(|e| e.id.numeric)
+        .unique()
+        .count();
+    (total, n_members * PUZZLE_EVENT.max_stars())
+}
+
+/// Members who just earned all the stars of the year, with their finishing order among the
+/// members who completed the year, by time of their last star.
+pub fn get_season_completions(
+    cur: &Leaderboard,
+    new: &Leaderboard,
+    year: i32,
+) -> Vec<(Identifier, usize)> {
+    let all_stars = PUZZLE_EVENT.max_stars();
+    let known = cur.members_ids();
+    let completed_before = cur
+        .iter()
+        .filter(|e| e.year == year)
+        .counts_by(|e| e.id.numeric)
+        .into_iter()
+        .filter(|(_id, n_stars)| *n_stars == all_stars)
+        .map(|(id, _n_stars)| id)
+        .collect::<HashSet<u64>>();
+    new.iter()
+        .filter(|e| e.year == year)
+        .into_group_map_by(|e| e.id.numeric)
+        .into_values()
+        .filter(|entries| entries.len() == all_stars)
+        .filter_map(|entries| entries.into_iter().max_by_key(|e| e.timestamp))
+        .sorted_by_key(|last| (last.timestamp, &last.id))
+        .enumerate()
+        .filter(|(_idx, last)| {
+            known.contains(&last.id.numeric) && !completed_before.contains(&last.id.numeric)
+        })
+        // idx is zero-based
+        .map(|(idx, last)| (last.id.clone(), idx + 1))
+        .collect()
+}
+
+/// Member invited to walk through their solution of the day, among the members with stars for
+/// the day. Members put in the spotlight the fewest times this year come first, and among them
+/// those with both stars are twice as likely to be picked. Anonymous members can't be reached,
+/// and quiet members don't want to be named, so they are left out.
+pub fn pick_spotlight(
+    leaderboard: &Leaderboard,
+    year: i32,
+    day: u8,
+    spotlights: &[(i32, u8, u64)],
+    quiet: &HashSet<u64>,
+) -> Option<Identifier> {
+    let candidates = leaderboard
+        .iter()
+        .filter(|e| e.year == year && e.day == day)
+        .filter(|e| !Identifier::is_anonymous_name(&e.id.name) && !quiet.contains(&e.id.numeric))
+        .counts_by(|e| e.id.clone())
+        .into_iter()
+        .map(|(id, n_stars)| {
+            let n_spotlights = spotlights
+                .iter()
+                .filter(|(y, _d, member)| *y == year && *member == id.numeric)
+                .count();
+            (id, n_stars, n_spotlights)
+        })
+        .sorted()
+        .collect::<Vec<_>>();
+    let fewest = candidates
+        .iter()
+        .map(|(_id, _n, n_spotlights)| *n_spotlights)
+        .min()?;
+    let candidates = candidates
+        .into_iter()
+        .filter(|(_id, _n, n_spotlights)| *n_spotlights == fewest)
+        .collect::<Vec<_>>();
+    let weights = WeightedIndex::new(candidates.iter().map(|(_id, n_stars, _n)| *n_stars)).ok()?;
+    let (id, _n_stars, _n_spotlights) = candidates
+        .into_iter()
+        .nth(weights.sample(&mut thread_rng()))?;
+    Some(id)
+}
+
+/// Members with completions for the year in the current leaderboard, but not in the new one.
+/// Completions are never undone, so these members left the leaderboard. A new leaderboard
+/// without any completion for the year is not trusted (e.g. partial response).
+pub fn get_left_members(cur: &Leaderboard, new: &Leaderboard, year: i32) -> Vec<Identifier> {
+    let new = new
+        .iter()
+        .filter(|e| e.year == year)
+        .map(|e| e.id.numeric)
+        .collect::<HashSet<u64>>();
+    if new.is_empty() {
+        return vec![];
+    }
+    cur.iter()
+        .filter(|e| e.year == year && !new.contains(&e.id.numeric))
+        .map(|e| e.id.clone())
+        .unique()
+        .sorted()
+        .collect()
 }
 
 #[derive(Serialize, Debug)]
@@ -79,11 +368,32 @@ pub struct DayHighlight {
     pub name: String,
     pub delta: Option<String>,
     pub new_points: usize,
+    // Whether the member was the first of the leaderboard to complete a part of the day
+    pub first: bool,
+    // Whether the new parts were completed within the day following the release of the puzzle
+    pub on_time: bool,
+    // Time between both parts, and latest time since release of the new parts, for thresholds
+    #[serde(skip)]
+    pub delta_duration: Option<Duration>,
+    #[serde(skip)]
+    pub release_duration: Option<Duration>,
+}
+
+// Identity of a star, regardless of its completion time and rank: (member id, year, day, part)
+type EntryKey = (u64, i32, u8, ProblemPart);
+
+fn entry_key(entry: &Entry) -> EntryKey {
+    (entry.id.numeric, entry.year, entry.day, entry.part)
 }
 
 /// Retrieve needed info to compute highlights statistics
 pub fn compute_highlights(current: &Leaderboard, new: &Leaderboard) -> Vec<DayHighlight> {
-    let new_entries = new.difference(current).collect::<HashSet<_>>();
+    // Parts completed since the current leaderboard, looked up by key so the diff is linear.
+    let current_keys = current.iter().map(entry_key).collect::<HashSet<EntryKey>>();
+    let new_entries = new
+        .iter()
+        .filter(|e| !current_keys.contains(&entry_key(e)))
+        .collect::<HashSet<_>>();
 
     // buffers
     let mut target_days_per_member = HashMap::new();
@@ -100,17 +410,22 @@ pub fn compute_highlights(current: &Leaderboard, new: &Leaderboard) -> Vec<DayHi
     // We can now compute the points changes for each id for the year/day
     let current_scores = current.daily_scores_per_year_member();
     let new_scores = new.daily_scores_per_year_member();
-    let entries_of_interest =
-        target_year_day_combinations
-            .iter()
-            .fold(HashMap::new(), |mut acc, (year, day)| {
-                let year_day_member = new
-                    .entries_per_member_for_year_day(*year, *day)
-                    .into_iter()
-                    .map(|(id, entries)| ((year, day, id), entries));
-                acc.extend(year_day_member);
-                acc
-            });
+
+    // Entries of the members and first completion times of the parts, for the days of interest.
+    let mut entries_of_interest: HashMap<(i32, u8, &Identifier), Vec<&Entry>> = HashMap::new();
+    let mut first_completions: HashMap<(i32, u8, ProblemPart), DateTime<Utc>> = HashMap::new();
+    new.iter()
+        .filter(|e| target_year_day_combinations.contains(&(e.year, e.day)))
+        .for_each(|e| {
+            entries_of_interest
+                .entry((e.year, e.day, &e.id))
+                .or_default()
+                .push(e);
+            first_completions
+                .entry((e.year, e.day, e.part))
+                .and_modify(|first| *first = (*first).min(e.timestamp))
+                .or_insert(e.timestamp);
+        });
 
     let highlights = target_days_per_member
         .iter()
@@ -119,7 +434,8 @@ pub fn compute_highlights(current: &Leaderboard, new: &Leaderboard) -> Vec<DayHi
                 .unique()
                 .map(|d| {
                     // Difference in score
-                    let day_index = *d as usize - 1; // arrays are zero-indexed
+                    // days are validated when inserted
+                    let day_index = day_index(*d).unwrap_or_default();
                     let score_increase = new_scores
                         .get(&(*year, &id))
                         .and_then(|days| Some(days[day_index]))
@@ -133,32 +449,232 @@ pub fn compute_highlights(current: &Leaderboard, new: &Leaderboard) -> Vec<DayHi
 
                     // compute delta if any
                     let (year, day) = (year, d);
-                    let hits = entries_of_interest.get(&(year, day, &id)).unwrap();
+                    let hits = entries_of_interest.get(&(*year, *day, *id)).unwrap();
                     // compute delta
                     let durations = hits
                         .iter()
                         .filter_map(|s| s.duration_since_release().ok())
                         .sorted()
                         .collect::<Vec<Duration>>();
-                    let delta = match durations.len() > 1 {
-                        true => Some(format_duration(durations[1] - durations[0])),
+                    let delta_duration = match durations.len() > 1 {
+                        true => Some(durations[1] - durations[0]),
                         false => None,
                     };
+                    let delta = delta_duration.map(format_duration);
+                    let release_duration = hits
+                        .iter()
+                        .filter(|e| new_entries.contains(*e))
+                        .filter_map(|e| e.duration_since_release().ok())
+                        .max();
+
+                    // first to complete one of the new parts for the day
+                    let first = hits.iter().filter(|e| new_entries.contains(*e)).any(|e| {
+                        first_completions.get(&(e.year, e.day, e.part)) == Some(&e.timestamp)
+                    });
+
+                    // new parts completed before the release of the next puzzle
+                    let on_time = hits.iter().filter(|e| new_entries.contains(*e)).all(|e| {
+                        e.duration_since_release()
+                            .is_ok_and(|duration| duration < Duration::days(1))
+                    });
 
                     DayHighlight {
                         parts_duration: durations.iter().map(|d| format_duration(*d)).collect(),
                         year: *year,
                         day: *day,
-                        name: id.name.clone(),
+                        name: id.name.to_string(),
                         n_stars: days.iter().filter(|d| d == &day).count(),
                         delta,
                         new_points: score_increase,
+                        first,
+                        on_time,
+                        delta_duration,
+                        release_duration,
                     }
                 })
                 .collect::<Vec<DayHighlight>>()
         })
-        .sorted_by_key(|h| Reverse(h.new_points))
+        .sorted_by_key(|h| (Reverse(h.new_points), h.name.clone(), h.year, h.day))
         .collect::<Vec<DayHighlight>>();
 
     highlights
 }
+
+/// Completions significant enough to be announced: of members among the given top members,
+/// with both parts within the maximum delta, and within the maximum time since release. No
+/// threshold given means every completion is significant.
+pub fn significant_highlights(
+    highlights: Vec<DayHighlight>,
+    top_members: Option<&HashSet<String>>,
+    max_delta: Option<Duration>,
+    max_since_release: Option<Duration>,
+) -> Vec<DayHighlight> {
+    highlights
+        .into_iter()
+        .filter(|h| top_members.map_or(true, |top| top.contains(&h.name)))
+        // Completions of a single part have no delta to compare.
+        .filter(|h| match (max_delta, h.delta_duration) {
+            (Some(max), Some(delta)) => delta <= max,
+            _ => true,
+        })
+        .filter(|h| match (max_since_release, h.release_duration) {
+            (Some(max), Some(duration)) => duration <= max,
+            _ => true,
+        })
+        .collect()
+}
+
+/// Highlights of the anonymous members shown, left out or counted together for each day,
+/// according to the policy.
+pub fn present_anonymous_highlights(
+    highlights: Vec<DayHighlight>,
+    policy: AnonymousPolicy,
+) -> Vec<DayHighlight> {
+    let (anonymous, mut highlights): (Vec<_>, Vec<_>) = highlights
+        .into_iter()
+        .partition(|h| Identifier::is_anonymous_name(&h.name));
+    match policy {
+        AnonymousPolicy::SHOW => highlights.extend(anonymous),
+        AnonymousPolicy::HIDE => (),
+        AnonymousPolicy::AGGREGATE => {
+            for ((year, day), mut day_highlights) in
+                anonymous.into_iter().into_group_map_by(|h| (h.year, h.day))
+            {
+                // A single anonymous member is shown as is.
+                if day_highlights.len() == 1 {
+                    highlights.append(&mut day_highlights);
+                    continue;
+                }
+                highlights.push(DayHighlight {
+                    parts_duration: vec![],
+                    year,
+                    day,
+                    n_stars: day_highlights.iter().map(|h| h.n_stars).sum(),
+                    name: format!("{} anonymous members", day_highlights.len()),
+                    delta: None,
+                    new_points: day_highlights.iter().map(|h| h.new_points).sum(),
+                    first: day_highlights.iter().any(|h| h.first),
+                    on_time: day_highlights.iter().all(|h| h.on_time),
+                    delta_duration: None,
+                    release_duration: day_highlights
+                        .iter()
+                        .filter_map(|h| h.release_duration)
+                        .max(),
+                });
+            }
+        }
+    };
+    highlights.sort_by_key(|h| (Reverse(h.new_points), h.name.clone(), h.year, h.day));
+    highlights
+}
+
+/// Highlights announced together, those of a same member for a same day merged into one, e.g.
+/// both parts of a day completed in two announcements held meanwhile. Highlights are given in
+/// the order they were computed, the later ones knowing about both parts.
+pub fn merge_highlights(highlights: Vec<DayHighlight>) -> Vec<DayHighlight> {
+    let mut merged: Vec<DayHighlight> = vec![];
+    for highlight in highlights {
+        match merged
+            .iter_mut()
+            .find(|h| (&h.name, h.year, h.day) == (&highlight.name, highlight.year, highlight.day))
+        {
+            Some(h) => {
+                if highlight.parts_duration.len() >= h.parts_duration.len() {
+                    h.parts_duration = highlight.parts_duration;
+                }
+                h.delta = highlight.delta.or(h.delta.take());
+                h.delta_duration = highlight.delta_duration.or(h.delta_duration);
+                h.n_stars += highlight.n_stars;
+                h.new_points += highlight.new_points;
+                h.first |= highlight.first;
+                h.on_time &= highlight.on_time;
+                h.release_duration = h.release_duration.max(highlight.release_duration);
+            }
+            None => merged.push(highlight),
+        }
+    }
+    merged.sort_by_key(|h| (Reverse(h.new_points), h.name.clone(), h.year, h.day));
+    merged
+}
+
+/// New members, the anonymous ones being shown, left out or counted together according to the
+/// policy.
+pub fn present_anonymous_members(members: Vec<String>, policy: AnonymousPolicy) -> Vec<String> {
+    let (anonymous, mut members): (Vec<_>, Vec<_>) = members
+        .into_iter()
+        .partition(|name| Identifier::is_anonymous_name(name));
+    match policy {
+        AnonymousPolicy::SHOW => members.extend(anonymous),
+        AnonymousPolicy::HIDE => (),
+        AnonymousPolicy::AGGREGATE if anonymous.len() > 1 => {
+            members.push(format!("{} anonymous members", anonymous.len()))
+        }
+        AnonymousPolicy::AGGREGATE => members.extend(anonymous),
+    };
+    members
+}
+
+/// First completions, those of anonymous members being left out when hidden. A single member
+/// has nothing to be counted with, so it is shown otherwise.
+pub fn present_anonymous_first_bloods(
+    first_bloods: Vec<(Identifier, u8, ProblemPart, Duration)>,
+    policy: AnonymousPolicy,
+) -> Vec<(Identifier, u8, ProblemPart, Duration)> {
+    match policy {
+        AnonymousPolicy::HIDE => first_bloods
+            .into_iter()
+            .filter(|(id, ..)| !Identifier::is_anonymous_name(&id.name))
+            .collect(),
+        AnonymousPolicy::SHOW | AnonymousPolicy::AGGREGATE => first_bloods,
+    }
+}
+
+/// Days completed by everyone, the last finisher being left out of the shout-out when anonymous
+/// members are hidden. The day itself is announced anyway.
+pub fn present_anonymous_everyone_finished(
+    everyone_finished: Vec<(u8, Identifier, Duration)>,
+    policy: AnonymousPolicy,
+) -> Vec<(u8, Option<String>, Duration)> {
+    everyone_finished
+        .into_iter()
+        .map(|(day, id, duration)| {
+            let name = match policy {
+                AnonymousPolicy::HIDE if Identifier::is_anonymous_name(&id.name) => None,
+                _ => Some(id.name.to_string()),
+            };
+            (day, name, duration)
+        })
+        .collect()
+}
+
+/// Completions of the year, those of anonymous members being left out when hidden. A single member
+/// has nothing to be counted with, so it is shown otherwise.
+pub fn present_anonymous_season_completions(
+    completions: Vec<(Identifier, usize)>,
+    policy: AnonymousPolicy,
+) -> Vec<(Identifier, usize)> {
+    match policy {
+        AnonymousPolicy::HIDE => completions
+            .into_iter()
+            .filter(|(id, _order)| !Identifier::is_anonymous_name(&id.name))
+            .collect(),
+        AnonymousPolicy::SHOW | AnonymousPolicy::AGGREGATE => completions,
+    }
+}
+
+/// Renamed members, renames from or to an anonymous name being left out unless anonymous members
+/// are shown, as they would tie the anonymous member to its name.
+pub fn present_anonymous_renames(
+    renames: Vec<(String, String)>,
+    policy: AnonymousPolicy,
+) -> Vec<(String, String)> {
+    match policy {
+        AnonymousPolicy::SHOW => renames,
+        AnonymousPolicy::HIDE | AnonymousPolicy::AGGREGATE => renames
+            .into_iter()
+            .filter(|(before, after)| {
+                !Identifier::is_anonymous_name(before) && !Identifier::is_anonymous_name(after)
+            })
+            .collect(),
+    }
+}
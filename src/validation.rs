@@ -0,0 +1,141 @@
+use crate::{
+    client::{aoc::AoC, github::GitHub, slack::AoCSlackClient},
+    config::{self, PuzzleEventKind},
+    core::{
+        leaderboard::{Entry, AOC_DAYS},
+        source::AocSource,
+    },
+    error::{BotError, BotResult},
+    scheduler::JobProcess,
+    secrets,
+    utils::now,
+};
+use chrono::Datelike;
+use chrono_tz::Tz;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+/// Checks the whole configuration at startup, so that the bot fails right away with all the
+/// problems found rather than later in December.
+pub async fn validate_configuration(jobs: &[JobProcess<'_>]) -> BotResult<()> {
+    let settings = &config::SETTINGS;
+    let mut problems = vec![];
+
+    if settings.nudge_hour > 23 {
+        problems.push(format!(
+            "NUDGE_HOUR must be between 0 and 23, not {}.",
+            settings.nudge_hour
+        ));
+    }
+    let timezones = settings
+        .member_timezones
+        .iter()
+        .flat_map(|timezones| timezones.values())
+        .chain([&settings.timezone, &settings.event_timezone]);
+    for timezone in timezones {
+        if timezone.parse::<Tz>().is_err() {
+            problems.push(format!("'{timezone}' is not a known timezone."));
+        }
+    }
+    if settings.slack_page_size == Some(0) {
+        problems.push(
+            "SLACK_PAGE_SIZE must be a number of rows, or unset for no pagination.".to_string(),
+        );
+    }
+    if let PuzzleEventKind::SCHEDULED = settings.get_puzzle_event() {
+        problems.extend(check_scheduled_event());
+    }
+    if let Err(e) = GitHub::day_pattern(1) {
+        problems.push(format!(
+            "GITHUB_DAY_PATTERN is not a valid regular expression. {e}"
+        ));
+    }
+    for schedule in jobs.iter().filter_map(|job| job.schedule()) {
+        if let Err(e) = cron::Schedule::from_str(schedule) {
+            problems.push(format!("'{schedule}' is not a valid cron expression. {e}"));
+        }
+    }
+
+    // Leaderboards of a scheduled event come from its feed instead.
+    let from_aoc = settings.event_leaderboard_url.is_none();
+    if from_aoc && settings.aoc_private_leaderboard_id == 0 {
+        problems.push(
+            "AOC_PRIVATE_LEADERBOARD_ID must be the id of the private leaderboard, as in its url."
+                .to_string(),
+        );
+    } else if from_aoc && settings.aoc_fixtures_dir.is_none() {
+        problems.extend(check_aoc_session().await);
+    }
+
+    if secrets::slack_token().is_empty() {
+        problems.push("SLACK_TOKEN is not set.".to_string());
+    } else {
+        problems.extend(AoCSlackClient::new().check_configuration().await);
+    }
+
+    match problems.is_empty() {
+        true => {
+            info!("Configuration checked.");
+            Ok(())
+        }
+        false => Err(BotError::Config(format!(
+            "Invalid configuration:\n- {}",
+            problems.join("\n- ")
+        ))),
+    }
+}
+
+/// Problems with the settings of a scheduled puzzle event, which must fit in the leaderboard.
+fn check_scheduled_event() -> Vec<String> {
+    let settings = &config::SETTINGS;
+    let mut problems = vec![];
+    if !(1..=AOC_DAYS).contains(&settings.event_days) {
+        problems.push(format!(
+            "EVENT_DAYS must be between 1 and {AOC_DAYS}, not {}.",
+            settings.event_days
+        ));
+    }
+    if !(1..=2).contains(&settings.event_parts) {
+        problems.push(format!(
+            "EVENT_PARTS must be 1 or 2, not {}.",
+            settings.event_parts
+        ));
+    }
+    if let Err(e) = Entry::puzzle_unlock(now().year(), 1) {
+        problems.push(format!(
+            "EVENT_MONTH, EVENT_FIRST_DAY and EVENT_UNLOCK_HOUR do not give a release time. {e}"
+        ));
+    }
+    if settings.event_leaderboard_url.is_none() && settings.aoc_fixtures_dir.is_none() {
+        problems.push("EVENT_LEADERBOARD_URL is required by a scheduled event.".to_string());
+    }
+    problems
+}
+
+/// Problem with the AoC session cookie, which must give access to the private leaderboard.
+async fn check_aoc_session() -> Option<String> {
+    let settings = &config::SETTINGS;
+    if secrets::aoc_session_cookie().is_empty() {
+        return Some("AOC_SESSION_COOKIE is not set.".to_string());
+    }
+
+    // Latest season started, whose private leaderboard exists.
+    let now = now();
+    let year = match Entry::puzzle_unlock(now.year(), 1) {
+        Ok(unlock) if unlock <= now => now.year(),
+        _ => now.year() - 1,
+    };
+    match AoC::new().fetch_private(year).await {
+        Ok(_) => None,
+        // Not the configuration's fault, the next scrapes will tell.
+        Err(e @ BotError::Unavailable(_)) => {
+            warn!("AoC is unavailable, the session cookie could not be checked. {e}");
+            None
+        }
+        Err(e) => Some(format!(
+            "AOC_SESSION_COOKIE does not give access to private leaderboard {} of {year}: the \
+             cookie may have expired, or its member may not be in the leaderboard. {e}",
+            settings.aoc_private_leaderboard_id
+        )),
+    }
+}